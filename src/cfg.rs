@@ -3,6 +3,8 @@ use thiserror::Error;
 
 use serde::{Deserialize, Serialize};
 
+use crate::pipeline::QcPipeline;
+
 /// Configuration Error
 #[derive(Debug, Clone, Error)]
 pub enum Error {
@@ -52,6 +54,11 @@ pub struct QcConfig {
     #[serde(default)]
     pub report: QcReportType,
 
+    /// Preprocessing [QcPipeline], applied by the [QcReport](crate::prelude::QcReport)
+    /// (see [QcContext::apply_pipeline](crate::prelude::QcContext::apply_pipeline))
+    #[serde(default)]
+    pub pipeline: QcPipeline,
+
     #[cfg(feature = "navigation")]
     #[cfg_attr(docsrs, doc(cfg(feature = "navigation")))]
     #[serde(default)]
@@ -78,6 +85,13 @@ impl QcConfig {
         s
     }
 
+    /// Build a [QcConfig] with this preprocessing [QcPipeline].
+    pub fn with_pipeline(&self, pipeline: QcPipeline) -> Self {
+        let mut s = self.clone();
+        s.pipeline = pipeline;
+        s
+    }
+
     /// Build a [QcConfig] with updated user defined RX position as ECEF coordinates.
     #[cfg(feature = "navigation")]
     #[cfg_attr(docsrs, doc(cfg(feature = "navigation")))]
@@ -99,6 +113,16 @@ impl Render for QcConfig {
                     (self.report.to_string())
                 }
             }
+            @if !self.pipeline.steps.is_empty() {
+                tr {
+                    td {
+                        "Pipeline"
+                    }
+                    td {
+                        (self.pipeline.render())
+                    }
+                }
+            }
         }
     }
 }
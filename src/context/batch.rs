@@ -0,0 +1,311 @@
+//! Context time batching
+use std::collections::HashMap;
+
+use qc_traits::{Filter, FilterItem, MaskOperand, Preprocessing};
+
+use crate::{
+    context::{BlobData, BlobKey},
+    prelude::{Duration, Epoch, ProductType, QcContext},
+};
+
+/// Products that are only looked up: they are preserved as is in every batch.
+const LOOKUP_PRODUCTS: [ProductType; 4] = [
+    ProductType::ANTEX,
+    ProductType::CodeBias,
+    ProductType::EarthOrientation,
+    ProductType::StationCoordinates,
+];
+
+/// Measurement products: when present, they define the batches.
+const MEASUREMENT_PRODUCTS: [ProductType; 3] = [
+    ProductType::Observation,
+    ProductType::MeteoObservation,
+    ProductType::DORIS,
+];
+
+/// [QcBatchOptions] define how [QcContext::time_batches] cuts a [QcContext].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct QcBatchOptions {
+    /// Duration of each batch. Batches are aligned to multiples of this
+    /// duration in the [QcContext] timescale (midnight for daily batches).
+    pub period: Duration,
+    /// Interpolation order of high precision orbits and clocks.
+    /// Each batch preserves (order / 2 + 1) samples of these products
+    /// before and after its window, so they can be interpolated up to its edges.
+    pub interpolation_order: usize,
+    /// Broadcast ephemerides published this long before the window
+    /// are preserved, so the first epochs of the batch may be resolved.
+    pub ephemeris_margin: Duration,
+}
+
+impl Default for QcBatchOptions {
+    /// Daily batches, 11th order interpolation
+    /// and a 4 hour ephemeris margin.
+    fn default() -> Self {
+        Self {
+            period: Duration::from_days(1.0),
+            interpolation_order: 11,
+            ephemeris_margin: Duration::from_hours(4.0),
+        }
+    }
+}
+
+impl QcBatchOptions {
+    /// Daily batches (midnight to midnight)
+    pub fn daily() -> Self {
+        Self::default()
+    }
+
+    /// Hourly batches
+    pub fn hourly() -> Self {
+        Self::default().with_period(Duration::from_hours(1.0))
+    }
+
+    /// Copies and returns [QcBatchOptions] with custom batch duration
+    pub fn with_period(&self, period: Duration) -> Self {
+        let mut s = *self;
+        s.period = period;
+        s
+    }
+
+    /// Copies and returns [QcBatchOptions] with this orbit and clock interpolation order
+    pub fn with_interpolation_order(&self, order: usize) -> Self {
+        let mut s = *self;
+        s.interpolation_order = order;
+        s
+    }
+
+    /// Copies and returns [QcBatchOptions] with this broadcast ephemeris margin
+    pub fn with_ephemeris_margin(&self, margin: Duration) -> Self {
+        let mut s = *self;
+        s.ephemeris_margin = margin;
+        s
+    }
+
+    /// Margin preserved around each window, for a product sampled at this rate
+    fn interpolation_margin(&self, sampling: Duration) -> Duration {
+        sampling * ((self.interpolation_order / 2 + 1) as f64)
+    }
+}
+
+impl BlobData {
+    /// Returns (first, last) [Epoch] of this time dependent [BlobData]
+    pub(crate) fn time_span(&self) -> Option<(Epoch, Epoch)> {
+        match self {
+            Self::RINEX(rinex) => Some((rinex.first_epoch()?, rinex.last_epoch()?)),
+            #[cfg(feature = "sp3")]
+            Self::SP3(sp3) => Some((sp3.first_epoch()?, sp3.last_epoch()?)),
+            Self::BIAS(_) | Self::EOP(_) | Self::SNX(_) => None,
+        }
+    }
+
+    /// Returns sampling interval of this time dependent [BlobData]
+    fn sampling_interval(&self) -> Option<Duration> {
+        match self {
+            Self::RINEX(rinex) => rinex.sampling_interval(),
+            #[cfg(feature = "sp3")]
+            Self::SP3(sp3) => Some(sp3.header.sampling_period),
+            Self::BIAS(_) | Self::EOP(_) | Self::SNX(_) => None,
+        }
+    }
+
+    /// Returns true if this time dependent [BlobData] contains any [Epoch] within [start, end[
+    fn has_epochs_within(&self, start: Epoch, end: Epoch) -> bool {
+        let within = |t: Epoch| t >= start && t < end;
+
+        match self {
+            Self::RINEX(rinex) => rinex.epoch_iter().any(within),
+            #[cfg(feature = "sp3")]
+            Self::SP3(sp3) => sp3.epochs_iter().any(within),
+            Self::BIAS(_) | Self::EOP(_) | Self::SNX(_) => false,
+        }
+    }
+
+    /// Returns a copy of this [BlobData] restricted to [start, end[,
+    /// or None when no data remains.
+    pub(crate) fn time_windowed(&self, start: Epoch, end: Epoch) -> Option<Self> {
+        let lower = Filter::mask(MaskOperand::GreaterEquals, FilterItem::EpochItem(start));
+        let upper = Filter::mask(MaskOperand::LowerThan, FilterItem::EpochItem(end));
+
+        let mut data = self.clone();
+
+        match &mut data {
+            Self::RINEX(rinex) => {
+                rinex.filter_mut(&lower);
+                rinex.filter_mut(&upper);
+            }
+            #[cfg(feature = "sp3")]
+            Self::SP3(sp3) => {
+                sp3.filter_mut(&lower);
+                sp3.filter_mut(&upper);
+            }
+            Self::BIAS(_) | Self::EOP(_) | Self::SNX(_) => {}
+        }
+
+        data.time_span()?;
+        Some(data)
+    }
+}
+
+impl QcContext {
+    /// Returns true if this [ProductType] defines the batches of this [QcContext]:
+    /// measurements when they exist, any time dependent product otherwise.
+    fn defines_batches(&self, product: ProductType) -> bool {
+        if self
+            .blob
+            .keys()
+            .any(|key| MEASUREMENT_PRODUCTS.contains(&key.product))
+        {
+            MEASUREMENT_PRODUCTS.contains(&product)
+        } else {
+            !LOOKUP_PRODUCTS.contains(&product)
+        }
+    }
+
+    /// Builds the [QcContext] of the [start, end[ window, if it contains
+    /// any data that defines the batches.
+    fn time_batch(&self, start: Epoch, end: Epoch, opts: &QcBatchOptions) -> Option<Self> {
+        let mut ctx = self.empty_clone();
+        let mut has_data = false;
+
+        // window of each time dependent blob
+        let mut windows = HashMap::<BlobKey, (Epoch, Epoch)>::new();
+
+        for (key, data) in self.blob.iter() {
+            if LOOKUP_PRODUCTS.contains(&key.product) {
+                ctx.blob.insert(key.clone(), data.clone());
+                continue;
+            }
+
+            let (lower, upper) = match key.product {
+                #[cfg(feature = "sp3")]
+                ProductType::HighPrecisionOrbit => {
+                    let margin = opts
+                        .interpolation_margin(data.sampling_interval().unwrap_or(Duration::ZERO));
+                    (start - margin, end + margin)
+                }
+                ProductType::HighPrecisionClock => {
+                    let margin = opts
+                        .interpolation_margin(data.sampling_interval().unwrap_or(Duration::ZERO));
+                    (start - margin, end + margin)
+                }
+                ProductType::BroadcastNavigation => (start - opts.ephemeris_margin, end),
+                _ => (start, end),
+            };
+
+            if self.defines_batches(key.product) && data.has_epochs_within(start, end) {
+                has_data = true;
+            }
+
+            if let Some(data) = data.time_windowed(lower, upper) {
+                ctx.blob.insert(key.clone(), data);
+                windows.insert(key.clone(), (lower, upper));
+            }
+        }
+
+        if !has_data {
+            return None;
+        }
+
+        // batches preserve the provenance of their data:
+        // lookup products, and the files that overlap the window of their blob
+        ctx.copy_files(self, |product, _, meta| {
+            if LOOKUP_PRODUCTS.contains(&product) {
+                return true;
+            }
+
+            let key = BlobKey {
+                product,
+                station: if product.is_station_dependent() {
+                    meta.station.clone()
+                } else {
+                    None
+                },
+            };
+
+            match (windows.get(&key), meta.time_span) {
+                (Some((lower, upper)), Some((first, last))) => first < *upper && last >= *lower,
+                (Some(_), None) => true,
+                (None, _) => false,
+            }
+        });
+
+        Some(ctx)
+    }
+
+    /// Cuts current [QcContext] into consecutive time batches, as described by
+    /// [QcBatchOptions]. Each batch is a standalone [QcContext], returned with the
+    /// [Epoch] its window starts at.
+    ///
+    /// - Observation, Meteo, DORIS and IONEX products are strictly trimmed to the window.
+    /// - High precision orbits and clocks are trimmed to the window, extended by
+    ///   their interpolation margin ([QcBatchOptions::interpolation_order]).
+    /// - Broadcast navigation is trimmed to the window, extended by
+    ///   [QcBatchOptions::ephemeris_margin] before it.
+    /// - ANTEX, biases, EOP and station coordinates are preserved as is.
+    ///
+    /// Batches are defined by the measurements, when any were loaded.
+    /// Windows that do not contain any data are not returned.
+    ///
+    /// This is typically used to generate daily reports from a multi-day
+    /// [QcContext], or to export IGS-style daily files:
+    /// ```
+    /// use gnss_qc::prelude::{QcContext, QcBatchOptions};
+    ///
+    /// let mut context = QcContext::new();
+    ///
+    /// context.load_gzip_rinex_file("data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+    ///     .unwrap();
+    ///
+    /// context.load_gzip_rinex_file("data/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+    ///     .unwrap();
+    ///
+    /// let batches = context.time_batches(&QcBatchOptions::hourly());
+    ///
+    /// assert_eq!(batches.len(), 24);
+    ///
+    /// for (start, batch) in batches {
+    ///     let obs = batch.observation().unwrap();
+    ///     assert_eq!(obs.first_epoch(), Some(start));
+    ///     assert!(batch.has_brdc_navigation());
+    /// }
+    /// ```
+    pub fn time_batches(&self, opts: &QcBatchOptions) -> Vec<(Epoch, Self)> {
+        let mut batches = Vec::new();
+
+        if opts.period <= Duration::ZERO {
+            return batches;
+        }
+
+        let timescale = match self.timescale() {
+            Some(timescale) => timescale,
+            None => return batches,
+        };
+
+        let span = self
+            .blob
+            .iter()
+            .filter(|(key, _)| self.defines_batches(key.product))
+            .filter_map(|(_, data)| data.time_span())
+            .reduce(|(first, last), (t0, t1)| (first.min(t0), last.max(t1)));
+
+        let (first, last) = match span {
+            Some(span) => span,
+            None => return batches,
+        };
+
+        let mut start = first.to_time_scale(timescale).floor(opts.period);
+
+        while start <= last {
+            let end = start + opts.period;
+
+            if let Some(batch) = self.time_batch(start, end, opts) {
+                batches.push((start, batch));
+            }
+
+            start = end;
+        }
+
+        batches
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{
+    context::{parse_file, BlobData, BlobKey, FileFormat, FileMetadata},
+    error::Error,
+    prelude::{BiasSolutions, ProductType, QcContext},
+};
+
+use std::path::Path;
+
+impl QcContext {
+    /// Add these [BiasSolutions] into current [QcContext].
+    pub fn load_bias<P: AsRef<Path>>(&mut self, path: P, bias: BiasSolutions) -> Result<(), Error> {
+        let prod_type = ProductType::CodeBias;
+
+        let path_buf = path.as_ref().to_path_buf();
+
+        let key = BlobKey::from_product(prod_type);
+
+        self.metadata
+            .insert(path_buf.clone(), FileMetadata::from_bias(&bias));
+
+        // extend context blob
+        if let Some(inner) = self.blob.get_mut(&key) {
+            if let Some(inner) = inner.as_mut_bias() {
+                inner.merge_mut(&bias);
+            }
+        } else {
+            self.blob.insert(key, BlobData::BIAS(bias));
+        }
+
+        if let Some(paths) = self.files.get_mut(&prod_type) {
+            paths.push(path_buf);
+        } else {
+            self.files.insert(prod_type, vec![path_buf]);
+        }
+
+        Ok(())
+    }
+
+    /// Load a readable Bias-SINEX file into this [QcContext].
+    pub fn load_bias_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
+        let (blob, digest) = parse_file(&path, FileFormat::BiasSINEX, false)?;
+        self.load_parsed(path, blob, digest)
+    }
+
+    /// Returns reference to inner [ProductType::CodeBias] data
+    pub fn bias(&self) -> Option<&BiasSolutions> {
+        self.data(ProductType::CodeBias)?.as_bias()
+    }
+
+    /// Returns mutable reference to inner [ProductType::CodeBias] data
+    pub fn bias_mut(&mut self) -> Option<&mut BiasSolutions> {
+        self.data_mut(ProductType::CodeBias)?.as_mut_bias()
+    }
+
+    /// Returns true if [ProductType::CodeBias] are present in Self
+    pub fn has_bias(&self) -> bool {
+        self.bias().is_some()
+    }
+}
@@ -1,23 +1,147 @@
-use crate::prelude::Rinex;
+use std::path::Path;
+
+use qc_traits::Merge;
+
+use crate::{
+    error::Error,
+    prelude::{BiasSolutions, EarthOrientation, ProductType, Rinex, StationSolutions},
+};
 
 #[cfg(feature = "sp3")]
 use crate::prelude::SP3;
 
+#[cfg(feature = "snapshot")]
+use serde::{Deserialize, Serialize};
+
+/// [BlobKey] identifies one [BlobData] stored in the [QcContext](crate::prelude::QcContext).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "snapshot", derive(Serialize, Deserialize))]
+pub(crate) struct BlobKey {
+    /// [ProductType]
+    pub product: ProductType,
+    /// Station (marker) name, only for station dependent [ProductType]s.
+    pub station: Option<String>,
+}
+
+impl BlobKey {
+    /// Builds a new [BlobKey] for products that do not depend on the station.
+    pub fn from_product(product: ProductType) -> Self {
+        Self {
+            product,
+            station: None,
+        }
+    }
+}
+
+/// Returns true if this (uppercase) name is a 9 character station identifier:
+/// 4 character site code, monument and receiver numbers, ISO country code.
+pub(crate) fn is_station_identifier(name: &str) -> bool {
+    let bytes = name.as_bytes();
+
+    bytes.len() == 9
+        && bytes[..4].iter().all(|c| c.is_ascii_alphanumeric())
+        && bytes[4..6].iter().all(|c| c.is_ascii_digit())
+        && bytes[6..].iter().all(|c| c.is_ascii_uppercase())
+}
+
+/// Normalizes this station (marker) name into the station key used by the
+/// [QcContext](crate::prelude::QcContext). 9 character station identifiers ("ESBC00DNK")
+/// are reduced to their 4 character site code ("ESBC"), so one station is described
+/// by a single key, whether it was identified from its header, a V2 or a V3 file name.
+/// Other names are preserved (uppercase).
+pub(crate) fn station_key(name: &str) -> String {
+    let name = name.trim().to_uppercase();
+
+    if is_station_identifier(&name) {
+        name[..4].to_string()
+    } else {
+        name
+    }
+}
+
+/// Determines the station key (see [station_key]) of this [Rinex], from its header
+/// or from its file name when the header does not describe it.
+pub(crate) fn station_name(rinex: &Rinex, path: &Path) -> Option<String> {
+    if let Some(marker) = &rinex.header.geodetic_marker {
+        let name = marker.name.trim();
+        if !name.is_empty() {
+            return Some(station_key(name));
+        }
+    }
+
+    let filename = path.file_name()?.to_string_lossy().to_string();
+
+    // removes possible .crx ; .gz extensions
+    let stem = filename.split('.').next()?;
+
+    if stem.contains('_') {
+        // V3 long file name: 9 character station identifier
+        let site = stem.split('_').next()?;
+        Some(station_key(&site.chars().take(9).collect::<String>()))
+    } else if stem.len() >= 4 {
+        // V2 short file name: 4 character site code
+        Some(station_key(&stem.chars().take(4).collect::<String>()))
+    } else {
+        None
+    }
+}
+
 #[derive(Clone)]
+#[cfg_attr(feature = "snapshot", derive(Serialize, Deserialize))]
 pub enum BlobData {
     /// [Rinex] content
     RINEX(Rinex),
     #[cfg(feature = "sp3")]
     /// [SP3] content
     SP3(SP3),
+    /// [BiasSolutions] content
+    BIAS(BiasSolutions),
+    /// [EarthOrientation] content
+    EOP(EarthOrientation),
+    /// [StationSolutions] content
+    SNX(StationSolutions),
 }
 
 impl BlobData {
+    /// Returns the [ProductType] this [BlobData] represents
+    pub fn product_type(&self) -> ProductType {
+        match self {
+            Self::RINEX(r) => ProductType::from(r.header.rinex_type),
+            #[cfg(feature = "sp3")]
+            Self::SP3(_) => ProductType::HighPrecisionOrbit,
+            Self::BIAS(_) => ProductType::CodeBias,
+            Self::EOP(_) => ProductType::EarthOrientation,
+            Self::SNX(_) => ProductType::StationCoordinates,
+        }
+    }
+
+    /// Merges rhs [BlobData] into mutable Self.
+    /// Both must describe the same [ProductType].
+    pub fn merge_mut(&mut self, rhs: &Self) -> Result<(), Error> {
+        match (self, rhs) {
+            (Self::RINEX(lhs), Self::RINEX(rhs)) => Ok(lhs.merge_mut(rhs)?),
+            #[cfg(feature = "sp3")]
+            (Self::SP3(lhs), Self::SP3(rhs)) => Ok(lhs.merge_mut(rhs)?),
+            (Self::BIAS(lhs), Self::BIAS(rhs)) => {
+                lhs.merge_mut(rhs);
+                Ok(())
+            }
+            (Self::EOP(lhs), Self::EOP(rhs)) => {
+                lhs.merge_mut(rhs);
+                Ok(())
+            }
+            (Self::SNX(lhs), Self::SNX(rhs)) => {
+                lhs.merge_mut(rhs);
+                Ok(())
+            }
+            _ => Err(Error::ProductTypeMismatch),
+        }
+    }
+
     /// Returns reference to underlying [Rinex] data
     pub fn as_rinex(&self) -> Option<&Rinex> {
         match self {
             Self::RINEX(r) => Some(r),
-            #[cfg(feature = "sp3")]
             _ => None,
         }
     }
@@ -26,7 +150,54 @@ impl BlobData {
     pub fn as_mut_rinex(&mut self) -> Option<&mut Rinex> {
         match self {
             Self::RINEX(r) => Some(r),
-            #[cfg(feature = "sp3")]
+            _ => None,
+        }
+    }
+
+    /// Returns reference to underlying [BiasSolutions]
+    pub fn as_bias(&self) -> Option<&BiasSolutions> {
+        match self {
+            Self::BIAS(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// Returns mutable reference to underlying [BiasSolutions]
+    pub fn as_mut_bias(&mut self) -> Option<&mut BiasSolutions> {
+        match self {
+            Self::BIAS(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// Returns reference to underlying [EarthOrientation]
+    pub fn as_eop(&self) -> Option<&EarthOrientation> {
+        match self {
+            Self::EOP(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    /// Returns mutable reference to underlying [EarthOrientation]
+    pub fn as_mut_eop(&mut self) -> Option<&mut EarthOrientation> {
+        match self {
+            Self::EOP(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    /// Returns reference to underlying [StationSolutions]
+    pub fn as_station_solutions(&self) -> Option<&StationSolutions> {
+        match self {
+            Self::SNX(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns mutable reference to underlying [StationSolutions]
+    pub fn as_mut_station_solutions(&mut self) -> Option<&mut StationSolutions> {
+        match self {
+            Self::SNX(s) => Some(s),
             _ => None,
         }
     }
@@ -52,3 +223,24 @@ impl BlobData {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::station_key;
+
+    #[test]
+    fn station_keys() {
+        for (name, expected) in [
+            ("ESBC", "ESBC"),
+            ("esbc", "ESBC"),
+            (" ESBC00DNK ", "ESBC"),
+            ("esbc00dnk", "ESBC"),
+            ("AJAC", "AJAC"),
+            ("MY_ROVER", "MY_ROVER"),
+            ("ROVER1234", "ROVER1234"),
+            ("ESBC0XDNK", "ESBC0XDNK"),
+        ] {
+            assert_eq!(station_key(name), expected, "\"{}\"", name);
+        }
+    }
+}
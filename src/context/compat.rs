@@ -0,0 +1,504 @@
+//! Pre-merge compatibility checks
+use std::{collections::HashMap, fmt, path::Path};
+
+use itertools::Itertools;
+
+use rinex::{
+    hardware::{Antenna, Receiver},
+    prelude::Observable,
+};
+
+use crate::{
+    context::{blob::station_key, BlobKey, FileMetadata},
+    error::Error,
+    prelude::{Constellation, Duration, ProductType, QcContext, Rinex, TimeScale},
+};
+
+#[cfg(feature = "sp3")]
+use crate::prelude::SP3;
+
+/// Approximate receiver positions (as described by RINEX headers) may differ this much,
+/// in meters, before they are reported as a [QcMergeConflict::ReceiverPosition].
+const RX_POSITION_TOLERANCE_M: f64 = 10.0;
+
+/// [QcMergeConflict] describes one incompatibility between an incoming product
+/// and the data of the same kind that is already loaded. Merging would
+/// silently mix both descriptions.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QcMergeConflict {
+    /// Geodetic marker names differ: both describe the same site,
+    /// but not the same 9 character station identifier.
+    StationName { loaded: String, incoming: String },
+    /// Approximate receiver positions (ECEF m) differ
+    ReceiverPosition {
+        loaded: (f64, f64, f64),
+        incoming: (f64, f64, f64),
+    },
+    /// Receivers (model and serial number) differ
+    Receiver { loaded: String, incoming: String },
+    /// Antennas (model and serial number) differ
+    Antenna { loaded: String, incoming: String },
+    /// Sampling intervals differ
+    SamplingInterval {
+        loaded: Duration,
+        incoming: Duration,
+    },
+    /// Timescales differ
+    TimeScale {
+        loaded: TimeScale,
+        incoming: TimeScale,
+    },
+    /// Observable sets of this [Constellation] differ
+    Observables {
+        constellation: Constellation,
+        /// Loaded observables that the incoming product does not describe
+        missing: Vec<Observable>,
+        /// Incoming observables that were not loaded yet
+        extra: Vec<Observable>,
+    },
+    /// SP3 coordinate systems differ
+    CoordinateSystem { loaded: String, incoming: String },
+}
+
+impl fmt::Display for QcMergeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::StationName { loaded, incoming } => {
+                write!(
+                    f,
+                    "station \"{}\" is loaded, incoming \"{}\"",
+                    loaded, incoming
+                )
+            }
+            Self::ReceiverPosition { loaded, incoming } => {
+                write!(
+                    f,
+                    "receiver located at ({:.3}, {:.3}, {:.3}) m, incoming at ({:.3}, {:.3}, {:.3}) m",
+                    loaded.0, loaded.1, loaded.2, incoming.0, incoming.1, incoming.2,
+                )
+            }
+            Self::Receiver { loaded, incoming } => {
+                write!(
+                    f,
+                    "receiver \"{}\" is loaded, incoming \"{}\"",
+                    loaded, incoming
+                )
+            }
+            Self::Antenna { loaded, incoming } => {
+                write!(
+                    f,
+                    "antenna \"{}\" is loaded, incoming \"{}\"",
+                    loaded, incoming
+                )
+            }
+            Self::SamplingInterval { loaded, incoming } => {
+                write!(
+                    f,
+                    "sampled every {} but incoming every {}",
+                    loaded, incoming
+                )
+            }
+            Self::TimeScale { loaded, incoming } => {
+                write!(f, "expressed in {} but incoming in {}", loaded, incoming)
+            }
+            Self::Observables {
+                constellation,
+                missing,
+                extra,
+            } => {
+                write!(f, "{} observables differ", constellation)?;
+                if !missing.is_empty() {
+                    write!(f, ", missing: {}", missing.iter().join(", "))?;
+                }
+                if !extra.is_empty() {
+                    write!(f, ", extra: {}", extra.iter().join(", "))?;
+                }
+                Ok(())
+            }
+            Self::CoordinateSystem { loaded, incoming } => {
+                write!(
+                    f,
+                    "coordinates expressed in {} but incoming in {}",
+                    loaded, incoming
+                )
+            }
+        }
+    }
+}
+
+/// [QcMergeConflictType] is the kind of a [QcMergeConflict]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum QcMergeConflictType {
+    StationName,
+    ReceiverPosition,
+    Receiver,
+    Antenna,
+    SamplingInterval,
+    TimeScale,
+    Observables,
+    CoordinateSystem,
+}
+
+impl QcMergeConflict {
+    /// Returns the [QcMergeConflictType] of this [QcMergeConflict]
+    pub fn conflict_type(&self) -> QcMergeConflictType {
+        match self {
+            Self::StationName { .. } => QcMergeConflictType::StationName,
+            Self::ReceiverPosition { .. } => QcMergeConflictType::ReceiverPosition,
+            Self::Receiver { .. } => QcMergeConflictType::Receiver,
+            Self::Antenna { .. } => QcMergeConflictType::Antenna,
+            Self::SamplingInterval { .. } => QcMergeConflictType::SamplingInterval,
+            Self::TimeScale { .. } => QcMergeConflictType::TimeScale,
+            Self::Observables { .. } => QcMergeConflictType::Observables,
+            Self::CoordinateSystem { .. } => QcMergeConflictType::CoordinateSystem,
+        }
+    }
+}
+
+/// [QcMergePolicy] decides what happens when a [QcMergeConflict] is detected.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum QcMergePolicy {
+    /// The incoming product is not loaded
+    Reject,
+    /// The incoming product is loaded and the conflict is reported
+    #[default]
+    Warn,
+    /// The incoming product is loaded and the conflict is ignored
+    Force,
+}
+
+/// [QcMergePolicies] associates a [QcMergePolicy] to each [QcMergeConflictType].
+/// By default, all conflicts are reported ([QcMergePolicy::Warn]).
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct QcMergePolicies {
+    /// [QcMergePolicy] applied to conflicts that are not customized
+    pub default: QcMergePolicy,
+    /// Customized [QcMergePolicy] per [QcMergeConflictType]
+    pub policies: HashMap<QcMergeConflictType, QcMergePolicy>,
+}
+
+impl QcMergePolicies {
+    /// Copies and returns [QcMergePolicies] with this default [QcMergePolicy]
+    pub fn with_default(&self, policy: QcMergePolicy) -> Self {
+        let mut s = self.clone();
+        s.default = policy;
+        s
+    }
+
+    /// Copies and returns [QcMergePolicies] with this [QcMergePolicy]
+    /// for this [QcMergeConflictType]
+    pub fn with_policy(&self, conflict: QcMergeConflictType, policy: QcMergePolicy) -> Self {
+        let mut s = self.clone();
+        s.policies.insert(conflict, policy);
+        s
+    }
+
+    /// Returns the [QcMergePolicy] applied to this [QcMergeConflictType]
+    pub fn policy(&self, conflict: QcMergeConflictType) -> QcMergePolicy {
+        self.policies
+            .get(&conflict)
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    /// Applies these policies: returns the conflicts to report,
+    /// or the first conflict that rejects the incoming product.
+    fn apply(&self, conflicts: Vec<QcMergeConflict>) -> Result<Vec<QcMergeConflict>, Error> {
+        if let Some(conflict) = conflicts
+            .iter()
+            .find(|c| self.policy(c.conflict_type()) == QcMergePolicy::Reject)
+        {
+            return Err(Error::MergeConflict(conflict.clone()));
+        }
+
+        Ok(conflicts
+            .into_iter()
+            .filter(|c| self.policy(c.conflict_type()) == QcMergePolicy::Warn)
+            .collect())
+    }
+}
+
+/// Returns true if these marker names describe the same station. Blobs are keyed by site code:
+/// a site code and a 9 character identifier of this site describe the same station,
+/// two distinct identifiers of this site (monument, receiver or country) do not.
+fn same_station(lhs: &str, rhs: &str) -> bool {
+    let (lhs, rhs) = (lhs.trim().to_uppercase(), rhs.trim().to_uppercase());
+    lhs == rhs || (lhs.len() != rhs.len() && station_key(&lhs) == station_key(&rhs))
+}
+
+fn receiver_name(rcvr: &Receiver) -> String {
+    format!("{} #{}", rcvr.model, rcvr.sn)
+}
+
+fn antenna_name(antenna: &Antenna) -> String {
+    format!("{} #{}", antenna.model, antenna.sn)
+}
+
+/// Compares these two RINEX headers (and epochs)
+fn rinex_conflicts(loaded: &Rinex, incoming: &Rinex) -> Vec<QcMergeConflict> {
+    let mut conflicts = Vec::new();
+
+    let (lhs, rhs) = (&loaded.header, &incoming.header);
+
+    if let (Some(lhs), Some(rhs)) = (&lhs.geodetic_marker, &rhs.geodetic_marker) {
+        let (lhs, rhs) = (lhs.name.trim(), rhs.name.trim());
+        if !lhs.is_empty() && !rhs.is_empty() && !same_station(lhs, rhs) {
+            conflicts.push(QcMergeConflict::StationName {
+                loaded: lhs.to_string(),
+                incoming: rhs.to_string(),
+            });
+        }
+    }
+
+    if let (Some(lhs), Some(rhs)) = (lhs.rx_position, rhs.rx_position) {
+        let distance =
+            ((lhs.0 - rhs.0).powi(2) + (lhs.1 - rhs.1).powi(2) + (lhs.2 - rhs.2).powi(2)).sqrt();
+
+        if distance > RX_POSITION_TOLERANCE_M {
+            conflicts.push(QcMergeConflict::ReceiverPosition {
+                loaded: lhs,
+                incoming: rhs,
+            });
+        }
+    }
+
+    if let (Some(lhs), Some(rhs)) = (&lhs.rcvr, &rhs.rcvr) {
+        let (lhs, rhs) = (receiver_name(lhs), receiver_name(rhs));
+        if lhs != rhs {
+            conflicts.push(QcMergeConflict::Receiver {
+                loaded: lhs,
+                incoming: rhs,
+            });
+        }
+    }
+
+    if let (Some(lhs), Some(rhs)) = (&lhs.rcvr_antenna, &rhs.rcvr_antenna) {
+        let (lhs, rhs) = (antenna_name(lhs), antenna_name(rhs));
+        if lhs != rhs {
+            conflicts.push(QcMergeConflict::Antenna {
+                loaded: lhs,
+                incoming: rhs,
+            });
+        }
+    }
+
+    // navigation frames are expressed in the timescale of each constellation,
+    // at the publication rate of each agency
+    if ProductType::from(lhs.rinex_type) != ProductType::BroadcastNavigation {
+        if let (Some(lhs), Some(rhs)) = (loaded.sampling_interval(), incoming.sampling_interval()) {
+            if lhs != rhs {
+                conflicts.push(QcMergeConflict::SamplingInterval {
+                    loaded: lhs,
+                    incoming: rhs,
+                });
+            }
+        }
+
+        if let (Some(lhs), Some(rhs)) = (loaded.first_epoch(), incoming.first_epoch()) {
+            if lhs.time_scale != rhs.time_scale {
+                conflicts.push(QcMergeConflict::TimeScale {
+                    loaded: lhs.time_scale,
+                    incoming: rhs.time_scale,
+                });
+            }
+        }
+    }
+
+    if let (Some(lhs), Some(rhs)) = (&lhs.obs, &rhs.obs) {
+        for (constellation, loaded_codes) in lhs.codes.iter().sorted_by_key(|(c, _)| **c) {
+            if let Some(incoming_codes) = rhs.codes.get(constellation) {
+                let missing = loaded_codes
+                    .iter()
+                    .filter(|code| !incoming_codes.contains(code))
+                    .cloned()
+                    .collect::<Vec<_>>();
+
+                let extra = incoming_codes
+                    .iter()
+                    .filter(|code| !loaded_codes.contains(code))
+                    .cloned()
+                    .collect::<Vec<_>>();
+
+                if !missing.is_empty() || !extra.is_empty() {
+                    conflicts.push(QcMergeConflict::Observables {
+                        constellation: *constellation,
+                        missing,
+                        extra,
+                    });
+                }
+            }
+        }
+    }
+
+    conflicts
+}
+
+/// Compares these two SP3 headers
+#[cfg(feature = "sp3")]
+fn sp3_conflicts(loaded: &SP3, incoming: &SP3) -> Vec<QcMergeConflict> {
+    let mut conflicts = Vec::new();
+
+    let (lhs, rhs) = (&loaded.header, &incoming.header);
+
+    if lhs.sampling_period != rhs.sampling_period {
+        conflicts.push(QcMergeConflict::SamplingInterval {
+            loaded: lhs.sampling_period,
+            incoming: rhs.sampling_period,
+        });
+    }
+
+    if lhs.timescale != rhs.timescale {
+        conflicts.push(QcMergeConflict::TimeScale {
+            loaded: lhs.timescale,
+            incoming: rhs.timescale,
+        });
+    }
+
+    if lhs.coord_system != rhs.coord_system {
+        conflicts.push(QcMergeConflict::CoordinateSystem {
+            loaded: lhs.coord_system.clone(),
+            incoming: rhs.coord_system.clone(),
+        });
+    }
+
+    conflicts
+}
+
+impl QcContext {
+    /// Dry run of [Self::load_rinex]: returns the [QcMergeConflict]s between
+    /// this [Rinex] and the data it would be merged into. Returns nothing when this
+    /// [Rinex] would not be merged (new product or new station).
+    /// ```
+    /// use gnss_qc::prelude::{QcContext, QcMergeConflict, Rinex};
+    ///
+    /// let mut context = QcContext::new();
+    ///
+    /// context.load_gzip_rinex_file("data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+    ///     .unwrap();
+    ///
+    /// let mut rinex = Rinex::from_gzip_file("data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+    ///     .unwrap();
+    ///
+    /// if let Some(rcvr) = &mut rinex.header.rcvr {
+    ///     rcvr.sn = "0000".to_string();
+    /// }
+    ///
+    /// let conflicts = context.rinex_merge_conflicts("ESBC00DNK_R_20201770001_01D_30S_MO.crx", &rinex);
+    ///
+    /// assert_eq!(conflicts.len(), 1);
+    /// assert!(matches!(conflicts[0], QcMergeConflict::Receiver { .. }));
+    /// ```
+    pub fn rinex_merge_conflicts<P: AsRef<Path>>(
+        &self,
+        path: P,
+        rinex: &Rinex,
+    ) -> Vec<QcMergeConflict> {
+        let meta = FileMetadata::from_rinex(path.as_ref(), rinex);
+
+        let key = BlobKey {
+            product: ProductType::from(rinex.header.rinex_type),
+            station: meta.station,
+        };
+
+        match self.blob.get(&key).and_then(|data| data.as_rinex()) {
+            Some(loaded) => rinex_conflicts(loaded, rinex),
+            None => Vec::new(),
+        }
+    }
+
+    /// [Self::load_rinex] applying these [QcMergePolicies]. Returns the
+    /// [QcMergeConflict]s to report ([QcMergePolicy::Warn]), or
+    /// [Error::MergeConflict] when a conflict rejects this [Rinex],
+    /// in which case the [QcContext] is left untouched.
+    pub fn load_rinex_with_policies<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        rinex: Rinex,
+        policies: &QcMergePolicies,
+    ) -> Result<Vec<QcMergeConflict>, Error> {
+        let conflicts = policies.apply(self.rinex_merge_conflicts(&path, &rinex))?;
+        self.load_rinex(path, rinex)?;
+        Ok(conflicts)
+    }
+
+    /// Dry run of [Self::load_sp3]: returns the [QcMergeConflict]s between
+    /// this [SP3] and the orbits already loaded.
+    #[cfg(feature = "sp3")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sp3")))]
+    pub fn sp3_merge_conflicts(&self, sp3: &SP3) -> Vec<QcMergeConflict> {
+        match self.sp3() {
+            Some(loaded) => sp3_conflicts(loaded, sp3),
+            None => Vec::new(),
+        }
+    }
+
+    /// [Self::load_sp3] applying these [QcMergePolicies]. Returns the
+    /// [QcMergeConflict]s to report ([QcMergePolicy::Warn]), or
+    /// [Error::MergeConflict] when a conflict rejects this [SP3],
+    /// in which case the [QcContext] is left untouched.
+    #[cfg(feature = "sp3")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sp3")))]
+    pub fn load_sp3_with_policies<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        sp3: SP3,
+        policies: &QcMergePolicies,
+    ) -> Result<Vec<QcMergeConflict>, Error> {
+        let conflicts = policies.apply(self.sp3_merge_conflicts(&sp3))?;
+        self.load_sp3(path, sp3)?;
+        Ok(conflicts)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn merge_policies() {
+        let conflicts = vec![
+            QcMergeConflict::TimeScale {
+                loaded: TimeScale::GPST,
+                incoming: TimeScale::UTC,
+            },
+            QcMergeConflict::CoordinateSystem {
+                loaded: "IGS20".to_string(),
+                incoming: "ITRF2014".to_string(),
+            },
+        ];
+
+        let policies = QcMergePolicies::default();
+        assert_eq!(policies.apply(conflicts.clone()).unwrap(), conflicts);
+
+        let policies = policies.with_policy(QcMergeConflictType::TimeScale, QcMergePolicy::Force);
+        assert_eq!(
+            policies.apply(conflicts.clone()).unwrap(),
+            vec![conflicts[1].clone()]
+        );
+
+        let policies = policies.with_default(QcMergePolicy::Reject);
+        match policies.apply(conflicts.clone()) {
+            Err(Error::MergeConflict(conflict)) => assert_eq!(conflict, conflicts[1]),
+            _ => panic!("conflict should have been rejected"),
+        }
+
+        assert_eq!(
+            conflicts[0].to_string(),
+            "expressed in GPST but incoming in UTC"
+        );
+    }
+
+    #[test]
+    fn station_names() {
+        for (lhs, rhs, expected) in [
+            ("ESBC00DNK", "ESBC00DNK", true),
+            ("ESBC00DNK", "esbc", true),
+            ("ESBC", "ESBC00DNK", true),
+            ("ESBC00DNK", "ESBC01DNK", false),
+            ("ESBC00DNK", "ESBC00SWE", false),
+            ("ROVER", "ROVER", true),
+        ] {
+            assert_eq!(same_station(lhs, rhs), expected, "{} / {}", lhs, rhs);
+        }
+    }
+}
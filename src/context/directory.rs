@@ -0,0 +1,254 @@
+//! Directory ingestion
+use std::{
+    collections::HashSet,
+    fs::read_dir,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    error::Error,
+    prelude::{ProductType, QcContext},
+};
+
+/// [QcDirectoryOptions] to select the files [QcContext::load_directory] should consider.
+#[derive(Debug, Clone, Default)]
+pub struct QcDirectoryOptions {
+    /// Walk sub-directories as well
+    pub recursive: bool,
+    /// File extensions to consider (case insensitive, without leading dot).
+    /// Compound extensions like "crx.gz" are supported.
+    /// When empty, all extensions are considered.
+    pub extensions: Vec<String>,
+    /// File name pattern, supporting '*' and '?' wildcards.
+    pub pattern: Option<String>,
+}
+
+impl QcDirectoryOptions {
+    /// Build [QcDirectoryOptions] that walk sub-directories as well.
+    pub fn with_recursion(&self) -> Self {
+        let mut s = self.clone();
+        s.recursive = true;
+        s
+    }
+
+    /// Build [QcDirectoryOptions] that also accept this file extension.
+    pub fn with_extension(&self, extension: &str) -> Self {
+        let mut s = self.clone();
+        s.extensions
+            .push(extension.trim_start_matches('.').to_lowercase());
+        s
+    }
+
+    /// Build [QcDirectoryOptions] with file name pattern (glob) to match.
+    pub fn with_pattern(&self, pattern: &str) -> Self {
+        let mut s = self.clone();
+        s.pattern = Some(pattern.to_string());
+        s
+    }
+
+    /// Returns true if this file name should be considered.
+    fn matches(&self, name: &str) -> bool {
+        if !self.extensions.is_empty() {
+            let lowered = name.to_lowercase();
+            if !self
+                .extensions
+                .iter()
+                .any(|ext| lowered.ends_with(&format!(".{}", ext)))
+            {
+                return false;
+            }
+        }
+
+        if let Some(pattern) = &self.pattern {
+            wildcard_match(pattern.as_bytes(), name.as_bytes())
+        } else {
+            true
+        }
+    }
+}
+
+/// Glob like matching, supporting '*' and '?' wildcards.
+fn wildcard_match(pattern: &[u8], name: &[u8]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack = Option::<(usize, usize)>::None;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == b'?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == b'*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = backtrack {
+            p = star_p + 1;
+            n = star_n + 1;
+            backtrack = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == b'*')
+}
+
+/// Outcome of [QcContext::load_directory], per file.
+#[derive(Debug)]
+pub enum QcLoadingOutcome {
+    /// File was loaded as this [ProductType]
+    Loaded(ProductType),
+    /// File was skipped because its (readable) format is not supported
+    Skipped,
+    /// File could not be loaded: it was identified but is invalid,
+    /// its Gzip compression is corrupt, or it is binary content we could not decode.
+    /// Also reported for sub-directories that could not be read.
+    Failed(Error),
+}
+
+impl QcLoadingOutcome {
+    /// True if file was loaded into the [QcContext]
+    pub fn is_loaded(&self) -> bool {
+        matches!(self, Self::Loaded(_))
+    }
+}
+
+/// Lists files to be considered, sorted by name for reproducible loading.
+/// Directories are identified by their canonical path, so each of them is walked
+/// only once, even when symbolic links form a loop.
+/// Sub-directories that cannot be walked are listed with the [Error] that occurred,
+/// so the rest of the tree is still considered.
+fn list_files(
+    dir: &Path,
+    opts: &QcDirectoryOptions,
+    visited: &mut HashSet<PathBuf>,
+) -> Result<Vec<(PathBuf, Option<Error>)>, Error> {
+    let mut files = Vec::new();
+
+    if !visited.insert(dir.canonicalize()?) {
+        return Ok(files);
+    }
+
+    let mut entries = read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .collect::<Vec<_>>();
+
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            if opts.recursive {
+                match list_files(&path, opts, visited) {
+                    Ok(nested) => files.extend(nested),
+                    Err(e) => files.push((path, Some(e))),
+                }
+            }
+        } else if let Some(name) = path.file_name() {
+            if opts.matches(&name.to_string_lossy()) {
+                files.push((path, None));
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+impl QcContext {
+    /// Loads all supported files contained in this directory.
+    /// Unlike [QcContext::load_file], this does not stop on the first failure:
+    /// the [QcLoadingOutcome] of each file that matched the [QcDirectoryOptions]
+    /// is returned. Files that do not match the options are not reported.
+    /// Sub-directories that cannot be read are reported as [QcLoadingOutcome::Failed],
+    /// and the rest of the tree is still loaded.
+    /// Returns an [Error] only if the directory itself cannot be read.
+    /// ```
+    /// use gnss_qc::prelude::{QcContext, QcDirectoryOptions, QcLoadingOutcome, ProductType};
+    ///
+    /// let mut context = QcContext::new();
+    ///
+    /// let opts = QcDirectoryOptions::default()
+    ///     .with_recursion()
+    ///     .with_pattern("ESBC00DNK*");
+    ///
+    /// let outcomes = context.load_directory("data/CRNX", &opts)
+    ///     .unwrap();
+    ///
+    /// for (path, outcome) in outcomes.iter() {
+    ///     match outcome {
+    ///         QcLoadingOutcome::Loaded(product) => {
+    ///             assert_eq!(*product, ProductType::Observation);
+    ///         },
+    ///         QcLoadingOutcome::Skipped => {},
+    ///         QcLoadingOutcome::Failed(e) => panic!("failed to load {}: {}", path.display(), e),
+    ///     }
+    /// }
+    ///
+    /// assert!(context.has_observation());
+    /// ```
+    pub fn load_directory<P: AsRef<Path>>(
+        &mut self,
+        dir: P,
+        opts: &QcDirectoryOptions,
+    ) -> Result<Vec<(PathBuf, QcLoadingOutcome)>, Error> {
+        let files = list_files(dir.as_ref(), opts, &mut HashSet::new())?;
+
+        let outcomes = files
+            .into_iter()
+            .map(|(path, error)| {
+                if let Some(e) = error {
+                    return (path, QcLoadingOutcome::Failed(e));
+                }
+
+                let outcome = match self.load_file(&path) {
+                    Ok(product) => QcLoadingOutcome::Loaded(product),
+                    Err(Error::NonSupportedFileFormat) => QcLoadingOutcome::Skipped,
+                    Err(e) => QcLoadingOutcome::Failed(e),
+                };
+                (path, outcome)
+            })
+            .collect();
+
+        Ok(outcomes)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{wildcard_match, QcDirectoryOptions};
+
+    #[test]
+    fn directory_options_matching() {
+        for (pattern, name, expected) in [
+            ("*", "ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz", true),
+            ("ESBC*", "ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz", true),
+            (
+                "*_MO.crx.gz",
+                "ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz",
+                true,
+            ),
+            (
+                "*_MN.rnx*",
+                "ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz",
+                false,
+            ),
+            ("AJAC???0.21O", "AJAC3550.21O", true),
+            ("AJAC???0.21O", "AJAC35500.21O", false),
+            ("", "AJAC3550.21O", false),
+        ] {
+            assert_eq!(
+                wildcard_match(pattern.as_bytes(), name.as_bytes()),
+                expected,
+                "\"{}\" / \"{}\"",
+                pattern,
+                name
+            );
+        }
+
+        let opts = QcDirectoryOptions::default()
+            .with_extension("crx.gz")
+            .with_extension(".SP3");
+
+        assert!(opts.matches("ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz"));
+        assert!(opts.matches("example.sp3"));
+        assert!(!opts.matches("AJAC3550.21O"));
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{
+    context::{parse_file, BlobData, BlobKey, FileFormat, FileMetadata},
+    error::Error,
+    prelude::{EarthOrientation, EopSample, Epoch, ProductType, QcContext},
+};
+
+use std::path::Path;
+
+impl QcContext {
+    /// Add this [EarthOrientation] into current [QcContext].
+    pub fn load_eop<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        eop: EarthOrientation,
+    ) -> Result<(), Error> {
+        let prod_type = ProductType::EarthOrientation;
+
+        let path_buf = path.as_ref().to_path_buf();
+
+        let key = BlobKey::from_product(prod_type);
+
+        self.metadata
+            .insert(path_buf.clone(), FileMetadata::from_eop(&eop));
+
+        // extend context blob
+        if let Some(inner) = self.blob.get_mut(&key) {
+            if let Some(inner) = inner.as_mut_eop() {
+                inner.merge_mut(&eop);
+            }
+        } else {
+            self.blob.insert(key, BlobData::EOP(eop));
+        }
+
+        if let Some(paths) = self.files.get_mut(&prod_type) {
+            paths.push(path_buf);
+        } else {
+            self.files.insert(prod_type, vec![path_buf]);
+        }
+
+        Ok(())
+    }
+
+    /// Load a readable IERS EOP file (finals2000A, C04 or IGS ERP) into this [QcContext].
+    pub fn load_eop_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
+        let (blob, digest) = parse_file(&path, FileFormat::EOP, false)?;
+        self.load_parsed(path, blob, digest)
+    }
+
+    /// Returns reference to inner [ProductType::EarthOrientation] data
+    pub fn eop(&self) -> Option<&EarthOrientation> {
+        self.data(ProductType::EarthOrientation)?.as_eop()
+    }
+
+    /// Returns mutable reference to inner [ProductType::EarthOrientation] data
+    pub fn eop_mut(&mut self) -> Option<&mut EarthOrientation> {
+        self.data_mut(ProductType::EarthOrientation)?.as_mut_eop()
+    }
+
+    /// Returns true if [ProductType::EarthOrientation] is present in Self
+    pub fn has_eop(&self) -> bool {
+        self.eop().is_some()
+    }
+
+    /// Returns the Earth orientation interpolated at this [Epoch],
+    /// when [ProductType::EarthOrientation] was loaded and covers this [Epoch].
+    pub fn earth_orientation(&self, t: Epoch) -> Option<EopSample> {
+        self.eop()?.interpolate(t)
+    }
+}
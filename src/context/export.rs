@@ -0,0 +1,457 @@
+//! Context export
+use std::{
+    collections::HashSet,
+    fs::{create_dir_all, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+#[cfg(feature = "flate2")]
+use flate2::{write::GzEncoder, Compression};
+
+use crate::{
+    context::{blob::is_station_identifier, BlobData},
+    error::Error,
+    prelude::{Constellation, Duration, Epoch, ProductType, QcContext, Rinex},
+};
+
+/// [ProductType]s that [QcContext::export] writes out
+const EXPORTED_PRODUCTS: &[ProductType] = &[
+    ProductType::Observation,
+    ProductType::MeteoObservation,
+    ProductType::BroadcastNavigation,
+    ProductType::HighPrecisionClock,
+    #[cfg(feature = "sp3")]
+    ProductType::HighPrecisionOrbit,
+];
+
+/// [QcExportOptions] to select how [QcContext::export] writes each product.
+#[derive(Debug, Clone, Default)]
+pub struct QcExportOptions {
+    /// Gzip compress all exported files
+    pub gzip: bool,
+    /// Compact (CRINEX) the Observation RINEX
+    pub crinex: bool,
+    /// Three letter (ISO) country code, completing station names
+    /// that are shorter than 9 characters. "XXX" when not defined.
+    pub country: Option<String>,
+}
+
+impl QcExportOptions {
+    /// Build [QcExportOptions] that Gzip compress all exported files.
+    pub fn with_gzip(&self) -> Self {
+        let mut s = self.clone();
+        s.gzip = true;
+        s
+    }
+
+    /// Build [QcExportOptions] that compact the Observation RINEX (CRINEX).
+    pub fn with_crinex(&self) -> Self {
+        let mut s = self.clone();
+        s.crinex = true;
+        s
+    }
+
+    /// Build [QcExportOptions] with this three letter (ISO) country code.
+    pub fn with_country(&self, country: &str) -> Self {
+        let mut s = self.clone();
+        s.country = Some(country.to_uppercase());
+        s
+    }
+}
+
+/// Returns the day of year (starting at 1) of this calendar date
+fn day_of_year(year: i32, month: u8, day: u8) -> u16 {
+    const CUMULATED_DAYS: [u16; 12] = [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
+
+    let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+    let mut doy = CUMULATED_DAYS[month as usize - 1] + day as u16;
+
+    if leap && month > 2 {
+        doy += 1;
+    }
+
+    doy
+}
+
+/// Formats the start time field of IGS file names (YYYYDDDHHMM),
+/// expressed in the [Epoch] timescale.
+fn start_time_code(t: Epoch) -> String {
+    let (y, m, d, hh, mm, _, _) = t.to_gregorian(t.time_scale);
+    format!("{:04}{:03}{:02}{:02}", y, day_of_year(y, m, d), hh, mm)
+}
+
+/// Formats the period and sampling fields of IGS file names (for example "01D", "30S"),
+/// using the largest unit that describes this [Duration].
+fn duration_code(dt: Duration) -> String {
+    let seconds = dt.to_seconds();
+
+    if seconds <= 0.0 {
+        return "00U".to_string();
+    }
+
+    if seconds < 1.0 {
+        return format!("{:02}Z", (1.0 / seconds).round() as u32);
+    }
+
+    for (unit, unit_s) in [
+        ('Y', 365.0 * 86400.0),
+        ('D', 86400.0),
+        ('H', 3600.0),
+        ('M', 60.0),
+    ] {
+        if seconds >= unit_s {
+            return format!("{:02}{}", (seconds / unit_s).round() as u32, unit);
+        }
+    }
+
+    format!("{:02}S", seconds.round() as u32)
+}
+
+/// Returns the constellation letter of IGS file names
+fn constellation_code(constellation: Option<Constellation>) -> char {
+    match constellation {
+        Some(Constellation::GPS) => 'G',
+        Some(Constellation::Glonass) => 'R',
+        Some(Constellation::Galileo) => 'E',
+        Some(Constellation::BeiDou) => 'C',
+        Some(Constellation::QZSS) => 'J',
+        Some(Constellation::IRNSS) => 'I',
+        Some(Constellation::SBAS) => 'S',
+        _ => 'M',
+    }
+}
+
+/// Returns the 9 character site name of IGS file names.
+/// Shorter names are completed, longer names are truncated:
+/// distinct names may result in the same site name.
+fn site_code(station: &str, opts: &QcExportOptions) -> String {
+    let station = station.trim().to_uppercase();
+
+    if station.len() >= 9 {
+        station.chars().take(9).collect()
+    } else {
+        format!(
+            "{:0<4}00{}",
+            station.chars().take(4).collect::<String>(),
+            opts.country.as_deref().unwrap_or("XXX")
+        )
+    }
+}
+
+/// Returns the three letter analysis center code of IGS file names
+fn agency_code(agency: Option<&str>) -> String {
+    let agency = agency
+        .map(|agency| agency.trim().to_uppercase())
+        .filter(|agency| !agency.is_empty())
+        .unwrap_or_else(|| "XXX".to_string());
+
+    format!("{:X<3}", agency.chars().take(3).collect::<String>())
+}
+
+/// Updates this [Rinex] header to describe its (possibly preprocessed) content
+fn update_rinex_header(rinex: &mut Rinex) {
+    let mut constellations = rinex.constellations_iter().collect::<Vec<_>>();
+    constellations.sort();
+    constellations.dedup();
+
+    match constellations.len() {
+        0 => {}
+        1 => rinex.header.constellation = Some(constellations[0]),
+        _ => rinex.header.constellation = Some(Constellation::Mixed),
+    }
+
+    rinex.header.program = Some(format!("gnss-qc v{}", env!("CARGO_PKG_VERSION")));
+    rinex.header.sampling_interval = rinex.sampling_interval();
+
+    let (first, last) = (rinex.first_epoch(), rinex.last_epoch());
+
+    let observables = rinex
+        .signal_observations_iter()
+        .map(|(_, signal)| (signal.sv.constellation, signal.observable.clone()))
+        .collect::<HashSet<_>>();
+
+    if let Some(obs) = &mut rinex.header.obs {
+        obs.timeof_first_obs = first;
+        obs.timeof_last_obs = last;
+
+        // observables that were filtered out are no longer described
+        for (constellation, codes) in obs.codes.iter_mut() {
+            codes.retain(|code| observables.contains(&(*constellation, code.clone())));
+        }
+
+        obs.codes.retain(|_, codes| !codes.is_empty());
+    }
+}
+
+/// Creates this file and writes its content, possibly Gzip compressed
+fn write_file<F>(path: &Path, gzip: bool, format: F) -> Result<(), Error>
+where
+    F: FnOnce(&mut BufWriter<&mut dyn Write>) -> Result<(), Error>,
+{
+    if gzip {
+        #[cfg(feature = "flate2")]
+        {
+            let fd = File::create(path)?;
+            let mut encoder = GzEncoder::new(fd, Compression::default());
+            let mut writer = BufWriter::new(&mut encoder as &mut dyn Write);
+            format(&mut writer)?;
+            writer.flush()?;
+            drop(writer);
+            encoder.finish()?;
+            return Ok(());
+        }
+
+        #[cfg(not(feature = "flate2"))]
+        return Err(Error::NonSupportedFileFormat);
+    }
+
+    let mut fd = File::create(path)?;
+    let mut writer = BufWriter::new(&mut fd as &mut dyn Write);
+    format(&mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+impl QcContext {
+    /// Exports each product of this [QcContext] to this directory, which is created
+    /// if needed. This is typically used after loading, preprocessing ([Self::filter_mut],
+    /// [Self::repair_mut]) or time shifting the context.
+    ///
+    /// - Observation (one file per station), Meteo, Broadcast Navigation and Clock
+    ///   products are written as RINEX, with headers that describe the new content.
+    /// - Observations are written as CRINEX when [QcExportOptions::crinex] is set.
+    /// - High precision orbits are written as SP3.
+    /// - All files are Gzip compressed when [QcExportOptions::gzip] is set.
+    ///
+    /// Other products (DORIS, IONEX, ANTEX, biases, EOP and station coordinates)
+    /// are not exported. Files follow the IGS (V3 long) naming conventions:
+    /// stations are named after their 9 character identifier when the header describes it.
+    /// Distinct stations that would share one file name are reported as
+    /// [Error::FileNameCollision], rather than overwritten.
+    /// Gzip compression requires the "flate2" feature.
+    ///
+    /// Returns the exported files.
+    /// ```
+    /// use gnss_qc::prelude::{QcContext, QcExportOptions};
+    ///
+    /// let mut context = QcContext::new();
+    ///
+    /// context.load_gzip_rinex_file("data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+    ///     .unwrap();
+    ///
+    /// let opts = QcExportOptions::default()
+    ///     .with_crinex()
+    ///     .with_gzip();
+    ///
+    /// let directory = std::env::temp_dir()
+    ///     .join(format!("esbc-export-{}", std::process::id()));
+    ///
+    /// let files = context.export(&directory, &opts)
+    ///     .unwrap();
+    ///
+    /// assert!(files[0].ends_with("ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz"));
+    ///
+    /// # std::fs::remove_dir_all(&directory).unwrap();
+    /// ```
+    pub fn export<P: AsRef<Path>>(
+        &self,
+        directory: P,
+        opts: &QcExportOptions,
+    ) -> Result<Vec<PathBuf>, Error> {
+        #[cfg(not(feature = "flate2"))]
+        if opts.gzip {
+            return Err(Error::NonSupportedFileFormat);
+        }
+
+        let directory = directory.as_ref();
+        create_dir_all(directory)?;
+
+        let mut keys = self
+            .blob
+            .keys()
+            .filter(|key| EXPORTED_PRODUCTS.contains(&key.product))
+            .collect::<Vec<_>>();
+
+        keys.sort();
+
+        let mut exported = Vec::with_capacity(keys.len());
+
+        for key in keys {
+            let path = match &self.blob[key] {
+                BlobData::RINEX(rinex) => {
+                    self.export_rinex(directory, key.station.as_deref(), rinex, opts, &exported)?
+                }
+                #[cfg(feature = "sp3")]
+                BlobData::SP3(sp3) => {
+                    let name = match (sp3.first_epoch(), sp3.last_epoch()) {
+                        (Some(first), Some(last)) => format!(
+                            "{}0OPSFIN_{}_{}_{}_ORB.SP3",
+                            agency_code(Some(&sp3.header.agency)),
+                            start_time_code(first),
+                            duration_code(last - first + sp3.header.sampling_period),
+                            duration_code(sp3.header.sampling_period),
+                        ),
+                        _ => continue,
+                    };
+
+                    let path = directory.join(if opts.gzip {
+                        format!("{}.gz", name)
+                    } else {
+                        name
+                    });
+
+                    write_file(&path, opts.gzip, |w| Ok(sp3.format(w)?))?;
+                    path
+                }
+                _ => continue,
+            };
+
+            exported.push(path);
+        }
+
+        Ok(exported)
+    }
+
+    /// Exports this [Rinex] product, returns the exported file.
+    /// Files that were `exported` already are never overwritten.
+    fn export_rinex(
+        &self,
+        directory: &Path,
+        station: Option<&str>,
+        rinex: &Rinex,
+        opts: &QcExportOptions,
+        exported: &[PathBuf],
+    ) -> Result<PathBuf, Error> {
+        let product = ProductType::from(rinex.header.rinex_type);
+
+        let is_crinex = rinex
+            .header
+            .obs
+            .as_ref()
+            .map(|obs| obs.crinex.is_some())
+            .unwrap_or(false);
+
+        let mut rinex = if product != ProductType::Observation || opts.crinex == is_crinex {
+            rinex.clone()
+        } else if opts.crinex {
+            rinex.rnx2crnx()
+        } else {
+            rinex.crnx2rnx()
+        };
+
+        update_rinex_header(&mut rinex);
+
+        // station keys are reduced to the site code: prefer the complete identifier
+        let station = rinex
+            .header
+            .geodetic_marker
+            .as_ref()
+            .map(|marker| marker.name.trim().to_uppercase())
+            .filter(|name| is_station_identifier(name))
+            .or_else(|| station.map(|station| station.to_string()))
+            .unwrap_or_default();
+
+        let (first, last) = (
+            rinex.first_epoch().ok_or(Error::FileNameDetermination)?,
+            rinex.last_epoch().ok_or(Error::FileNameDetermination)?,
+        );
+
+        let sampling = rinex.sampling_interval();
+
+        let period = duration_code(last - first + sampling.unwrap_or_default());
+        let sampling = sampling
+            .map(duration_code)
+            .unwrap_or_else(|| "00U".to_string());
+        let constellation = constellation_code(rinex.header.constellation);
+
+        let name = match product {
+            ProductType::Observation => format!(
+                "{}_R_{}_{}_{}_{}O.{}",
+                site_code(&station, opts),
+                start_time_code(first),
+                period,
+                sampling,
+                constellation,
+                if opts.crinex { "crx" } else { "rnx" },
+            ),
+            ProductType::MeteoObservation => format!(
+                "{}_R_{}_{}_{}_MM.rnx",
+                site_code(&station, opts),
+                start_time_code(first),
+                period,
+                sampling,
+            ),
+            ProductType::BroadcastNavigation => format!(
+                "BRDC00WRD_R_{}_{}_{}N.rnx",
+                start_time_code(first),
+                period,
+                constellation,
+            ),
+            ProductType::HighPrecisionClock => format!(
+                "{}0OPSFIN_{}_{}_{}_CLK.CLK",
+                agency_code(rinex.header.agency.as_deref()),
+                start_time_code(first),
+                period,
+                sampling,
+            ),
+            _ => return Err(Error::NonSupportedFileFormat),
+        };
+
+        let path = directory.join(if opts.gzip {
+            format!("{}.gz", name)
+        } else {
+            name
+        });
+
+        if exported.contains(&path) {
+            return Err(Error::FileNameCollision(
+                path.file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string(),
+            ));
+        }
+
+        write_file(&path, opts.gzip, |w| Ok(rinex.format(w)?))?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{day_of_year, duration_code, site_code, start_time_code, QcExportOptions};
+    use crate::prelude::{Duration, Epoch};
+    use std::str::FromStr;
+
+    #[test]
+    fn igs_file_name_fields() {
+        assert_eq!(day_of_year(2020, 1, 1), 1);
+        assert_eq!(day_of_year(2020, 6, 25), 177);
+        assert_eq!(day_of_year(2021, 12, 31), 365);
+        assert_eq!(day_of_year(2024, 12, 31), 366);
+
+        let t = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        assert_eq!(start_time_code(t), "20201770000");
+
+        let t = Epoch::from_str("2024-01-07T02:15:00 GPST").unwrap();
+        assert_eq!(start_time_code(t), "20240070215");
+
+        for (dt, expected) in [
+            (Duration::from_days(1.0), "01D"),
+            (Duration::from_hours(1.0), "01H"),
+            (Duration::from_seconds(900.0), "15M"),
+            (Duration::from_seconds(30.0), "30S"),
+            (Duration::from_seconds(0.1), "10Z"),
+            (Duration::from_days(365.0), "01Y"),
+            (Duration::ZERO, "00U"),
+        ] {
+            assert_eq!(duration_code(dt), expected);
+        }
+
+        let opts = QcExportOptions::default();
+        assert_eq!(site_code("ESBC00DNK", &opts), "ESBC00DNK");
+        assert_eq!(site_code("ajac", &opts), "AJAC00XXX");
+        assert_eq!(site_code("MOJN", &opts.with_country("dnk")), "MOJN00DNK");
+    }
+}
@@ -1,24 +1,41 @@
-use rinex::Rinex;
-
-use crate::{error::Error, prelude::QcContext};
+use crate::{
+    context::{parse_file, FileFormat},
+    error::Error,
+    prelude::QcContext,
+};
 
 use std::path::Path;
 
-#[cfg(feature = "sp3")]
-use crate::prelude::SP3;
-
 impl QcContext {
     /// Load a Gzip compressed RINEX file from readable [Path].
     pub fn load_gzip_rinex_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
-        let rinex = Rinex::from_gzip_file(&path)?;
-        self.load_rinex(path, rinex)
+        let (blob, digest) = parse_file(&path, FileFormat::RINEX, true)?;
+        self.load_parsed(path, blob, digest)
     }
 
     #[cfg(feature = "sp3")]
     #[cfg_attr(docsrs, doc(cfg(feature = "sp3")))]
-    /// Load a Gzip compressed [SP3] file from readable [Path].
+    /// Load a Gzip compressed [SP3](crate::prelude::SP3) file from readable [Path].
     pub fn load_gzip_sp3_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
-        let sp3 = SP3::from_gzip_file(&path)?;
-        self.load_sp3(path, sp3)
+        let (blob, digest) = parse_file(&path, FileFormat::SP3, true)?;
+        self.load_parsed(path, blob, digest)
+    }
+
+    /// Load a Gzip compressed Bias-SINEX file from readable [Path].
+    pub fn load_gzip_bias_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
+        let (blob, digest) = parse_file(&path, FileFormat::BiasSINEX, true)?;
+        self.load_parsed(path, blob, digest)
+    }
+
+    /// Load a Gzip compressed IERS EOP file from readable [Path].
+    pub fn load_gzip_eop_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
+        let (blob, digest) = parse_file(&path, FileFormat::EOP, true)?;
+        self.load_parsed(path, blob, digest)
+    }
+
+    /// Load a Gzip compressed SINEX (station coordinates) file from readable [Path].
+    pub fn load_gzip_station_sinex_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
+        let (blob, digest) = parse_file(&path, FileFormat::SINEX, true)?;
+        self.load_parsed(path, blob, digest)
     }
 }
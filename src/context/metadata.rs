@@ -0,0 +1,280 @@
+//! Per file metadata
+use std::{
+    io::{copy, sink, Read},
+    path::Path,
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::{
+    context::blob::{station_name, BlobData},
+    prelude::{
+        BiasSolutions, EarthOrientation, EopFormat, Epoch, ProductType, Rinex, StationSolutions,
+    },
+};
+
+#[cfg(feature = "sp3")]
+use crate::prelude::SP3;
+
+#[cfg(feature = "snapshot")]
+use serde::{Deserialize, Serialize};
+
+/// [FileMetadata] describes each file loaded into a [QcContext](crate::prelude::QcContext).
+/// It is collected when loading, because the file identity is lost once merged.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "snapshot", derive(Serialize, Deserialize))]
+pub(crate) struct FileMetadata {
+    /// [ProductType] of this file
+    pub product: ProductType,
+    /// Station (marker) name, for station dependent products
+    pub station: Option<String>,
+    /// Production agency
+    pub agency: Option<String>,
+    /// Operator / observer
+    pub operator: Option<String>,
+    /// SHA-256 of the file content (as stored, possibly compressed),
+    /// when this library parsed the file.
+    pub sha256: Option<String>,
+    /// File size in bytes, when this library parsed the file.
+    pub size: Option<u64>,
+    /// Instant this file was loaded
+    pub loaded_at: Option<Epoch>,
+    /// (first, last) [Epoch] described by this file, for time dependent products
+    pub time_span: Option<(Epoch, Epoch)>,
+    /// Header summary, as detected
+    pub header: String,
+}
+
+/// [FileDigest] of a file content (as stored, possibly compressed)
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FileDigest {
+    /// SHA-256 (hex)
+    pub sha256: String,
+    /// Size in bytes
+    pub size: u64,
+}
+
+impl FileDigest {
+    /// Computes the [FileDigest] of this buffer
+    #[cfg(any(feature = "rtcm", feature = "ubx", feature = "binex"))]
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(bytes);
+
+        Self {
+            sha256: hex(&hasher.finalize()),
+            size: bytes.len() as u64,
+        }
+    }
+}
+
+/// Lowercase hexadecimal representation
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// [HashingReader] computes the [FileDigest] of the content it reads.
+/// It is placed underneath the parser (and decompressor) so each file is read
+/// only once, and the digest describes exactly the content that was parsed.
+pub(crate) struct HashingReader<R: Read> {
+    inner: R,
+    hasher: Sha256,
+    size: u64,
+}
+
+impl<R: Read> HashingReader<R> {
+    /// Wraps this [Read]able source
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+            size: 0,
+        }
+    }
+
+    /// Returns the [FileDigest] of the whole content.
+    /// Content the parser did not consume is read (and hashed) first.
+    pub fn digest(mut self) -> std::io::Result<FileDigest> {
+        copy(&mut self, &mut sink())?;
+
+        Ok(FileDigest {
+            sha256: hex(&self.hasher.finalize()),
+            size: self.size,
+        })
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.size += n as u64;
+        Ok(n)
+    }
+}
+
+impl FileMetadata {
+    /// Builds new [FileMetadata]. The file content is not hashed here:
+    /// see [Self::set_digest].
+    fn new(
+        product: ProductType,
+        station: Option<String>,
+        agency: Option<String>,
+        operator: Option<String>,
+        time_span: Option<(Epoch, Epoch)>,
+        header: String,
+    ) -> Self {
+        Self {
+            product,
+            station,
+            agency,
+            operator,
+            sha256: None,
+            size: None,
+            loaded_at: Epoch::now().ok(),
+            time_span,
+            header,
+        }
+    }
+
+    /// Completes this [FileMetadata] with the [FileDigest] computed while parsing
+    pub fn set_digest(&mut self, digest: FileDigest) {
+        self.sha256 = Some(digest.sha256);
+        self.size = Some(digest.size);
+    }
+
+    /// Collects [FileMetadata] from this [BlobData]
+    pub fn from_blob(path: &Path, blob: &BlobData) -> Self {
+        match blob {
+            BlobData::RINEX(rinex) => Self::from_rinex(path, rinex),
+            #[cfg(feature = "sp3")]
+            BlobData::SP3(sp3) => Self::from_sp3(sp3),
+            BlobData::BIAS(bias) => Self::from_bias(bias),
+            BlobData::EOP(eop) => Self::from_eop(eop),
+            BlobData::SNX(snx) => Self::from_station_solutions(snx),
+        }
+    }
+
+    /// Collects [FileMetadata] from this [Rinex] file
+    pub fn from_rinex(path: &Path, rinex: &Rinex) -> Self {
+        let product = ProductType::from(rinex.header.rinex_type);
+
+        let crinex = rinex
+            .header
+            .obs
+            .as_ref()
+            .map(|obs| obs.crinex.is_some())
+            .unwrap_or(false);
+
+        let mut header = format!(
+            "{} V{}.{:02} {}",
+            if crinex { "CRINEX" } else { "RINEX" },
+            rinex.header.version.major,
+            rinex.header.version.minor,
+            product,
+        );
+
+        if let Some(constellation) = rinex.header.constellation {
+            header.push_str(&format!(" ({})", constellation));
+        }
+
+        Self::new(
+            product,
+            if product.is_station_dependent() {
+                station_name(rinex, path)
+            } else {
+                None
+            },
+            rinex
+                .header
+                .agency
+                .as_ref()
+                .map(|agency| agency.trim().to_string())
+                .filter(|agency| !agency.is_empty()),
+            rinex
+                .header
+                .observer
+                .as_ref()
+                .map(|observer| observer.trim().to_string())
+                .filter(|observer| !observer.is_empty()),
+            rinex.first_epoch().zip(rinex.last_epoch()),
+            header,
+        )
+    }
+
+    /// Collects [FileMetadata] from this [SP3] file
+    #[cfg(feature = "sp3")]
+    pub fn from_sp3(sp3: &SP3) -> Self {
+        let agency = sp3.header.agency.trim().to_string();
+
+        let header = format!(
+            "SP3-{} ({}) {} {}",
+            sp3.header.version,
+            sp3.header.constellation,
+            sp3.header.timescale,
+            sp3.header.coord_system,
+        );
+
+        Self::new(
+            ProductType::HighPrecisionOrbit,
+            None,
+            if agency.is_empty() {
+                None
+            } else {
+                Some(agency)
+            },
+            None,
+            sp3.first_epoch().zip(sp3.last_epoch()),
+            header,
+        )
+    }
+
+    /// Collects [FileMetadata] from these [BiasSolutions]
+    pub fn from_bias(bias: &BiasSolutions) -> Self {
+        let header = format!(
+            "Bias-SINEX ({} solutions, {})",
+            bias.solutions.len(),
+            bias.timescale
+        );
+
+        Self::new(
+            ProductType::CodeBias,
+            None,
+            bias.agency.clone(),
+            None,
+            None,
+            header,
+        )
+    }
+
+    /// Collects [FileMetadata] from this [EarthOrientation]
+    pub fn from_eop(eop: &EarthOrientation) -> Self {
+        let header = format!("{} ({} values)", eop.format, eop.samples.len());
+
+        Self::new(
+            ProductType::EarthOrientation,
+            None,
+            match eop.format {
+                EopFormat::Finals2000A | EopFormat::C04 => Some("IERS".to_string()),
+                EopFormat::IgsErp => None,
+            },
+            None,
+            None,
+            header,
+        )
+    }
+
+    /// Collects [FileMetadata] from these [StationSolutions]
+    pub fn from_station_solutions(solutions: &StationSolutions) -> Self {
+        let header = format!("SINEX ({} stations)", solutions.stations.len());
+
+        Self::new(
+            ProductType::StationCoordinates,
+            None,
+            solutions.agency.clone(),
+            None,
+            None,
+            header,
+        )
+    }
+}
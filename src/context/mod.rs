@@ -5,12 +5,65 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use crate::prelude::{Rinex, TimeScale};
+use crate::prelude::{Constellation, Rinex, TimeScale};
 
+use itertools::Itertools;
 use qc_traits::Merge;
 
 pub(crate) mod blob;
-use blob::BlobData;
+use blob::{station_key, BlobData, BlobKey};
+
+mod metadata;
+pub(crate) use metadata::{FileDigest, FileMetadata, HashingReader};
+
+mod partition;
+
+mod unload;
+
+mod parallel;
+
+mod sniffing;
+use sniffing::{parse_file, FileFormat};
+
+mod bias;
+
+mod eop;
+
+mod station;
+
+#[cfg(any(feature = "rtcm", feature = "ubx", feature = "binex"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "rtcm", feature = "ubx", feature = "binex")))
+)]
+mod stream;
+
+mod batch;
+pub use batch::QcBatchOptions;
+
+mod compat;
+pub use compat::{QcMergeConflict, QcMergeConflictType, QcMergePolicies, QcMergePolicy};
+
+mod directory;
+pub use directory::{QcDirectoryOptions, QcLoadingOutcome};
+
+mod export;
+pub use export::QcExportOptions;
+
+mod pipeline;
+
+mod realtime;
+pub use realtime::{QcSlidingWindow, QcWindowSummary};
+
+mod repair;
+pub use repair::QcRepairSummary;
+
+mod provenance;
+pub use provenance::QcFileProvenance;
+
+#[cfg(feature = "snapshot")]
+#[cfg_attr(docsrs, doc(cfg(feature = "snapshot")))]
+pub(crate) mod snapshot;
 
 #[cfg(feature = "flate2")]
 #[cfg_attr(docsrs, doc(cfg(feature = "flate2")))]
@@ -28,9 +81,29 @@ mod navigation;
 #[cfg_attr(docsrs, doc(cfg(feature = "navigation")))]
 pub mod time;
 
-use qc_traits::{Filter, Preprocessing, Repair, RepairTrait};
+#[cfg(feature = "navigation")]
+#[cfg_attr(docsrs, doc(cfg(feature = "navigation")))]
+mod timecorr;
+
+#[cfg(feature = "navigation")]
+#[cfg_attr(docsrs, doc(cfg(feature = "navigation")))]
+mod orbit;
+
+#[cfg(feature = "navigation")]
+pub use orbit::{QcOrbitInterpolator, QcOrbitOptions, QcOrbitSource, QcSatelliteState};
+
+#[cfg(feature = "navigation")]
+use orbit::QcOrbitSamples;
+
+#[cfg(feature = "navigation")]
+use std::sync::{Arc, OnceLock};
+
+#[cfg(feature = "navigation")]
+pub use timecorr::{QcTimeCorrection, QcTimeCorrectionSource, QcTimeCorrectionsDB};
+
+use qc_traits::{Filter, Preprocessing};
 
-use crate::{error::Error, prelude::ProductType};
+use crate::{error::Error, pipeline::QcPipeline, prelude::ProductType};
 
 #[cfg(feature = "navigation")]
 use crate::prelude::{Almanac, Frame};
@@ -38,13 +111,22 @@ use crate::prelude::{Almanac, Frame};
 /// [QcContext] is a general structure capable to store most common
 /// GNSS data. It is dedicated to post processing workflows,
 /// precise timing or atmosphere analysis.
+///
+/// Station dependent products (see [ProductType::is_station_dependent])
+/// are stored per station, so one [QcContext] may describe a complete network.
 #[derive(Clone)]
 pub struct QcContext {
     /// Files merged into this [QcContext]
     pub(crate) files: HashMap<ProductType, Vec<PathBuf>>,
 
-    /// Context blob created by merging each members of each category
-    pub(crate) blob: HashMap<ProductType, BlobData>,
+    /// [FileMetadata] of each file loaded
+    pub(crate) metadata: HashMap<PathBuf, FileMetadata>,
+
+    /// Context blob created by merging each members of each category (and station)
+    pub(crate) blob: HashMap<BlobKey, BlobData>,
+
+    /// [QcPipeline] steps applied so far, in order
+    pub(crate) preprocessing: QcPipeline,
 
     #[cfg(feature = "navigation")]
     #[cfg_attr(docsrs, doc(cfg(feature = "navigation")))]
@@ -55,6 +137,25 @@ pub struct QcContext {
     #[cfg_attr(docsrs, doc(cfg(feature = "navigation")))]
     /// ECEF [Frame]
     pub earth_cef: Frame,
+
+    #[cfg(feature = "navigation")]
+    /// High precision positions gathered by [Self::orbit_interpolator],
+    /// discarded whenever the high precision orbits may have been modified.
+    pub(crate) orbit_samples: OnceLock<Arc<QcOrbitSamples>>,
+}
+
+/// Returns true if broadcast navigation frames of this [Constellation]
+/// are natively expressed in this [TimeScale]
+fn is_native_timescale(constellation: Constellation, timescale: TimeScale) -> bool {
+    match constellation {
+        Constellation::GPS => timescale == TimeScale::GPST,
+        Constellation::QZSS => matches!(timescale, TimeScale::GPST | TimeScale::QZSST),
+        Constellation::Galileo => timescale == TimeScale::GST,
+        Constellation::BeiDou => timescale == TimeScale::BDT,
+        Constellation::Glonass => timescale == TimeScale::UTC,
+        c if c.is_sbas() => timescale == TimeScale::GPST,
+        _ => false,
+    }
 }
 
 impl QcContext {
@@ -87,11 +188,41 @@ impl QcContext {
 
         Self {
             files: Default::default(),
+            metadata: Default::default(),
             blob: Default::default(),
+            preprocessing: Default::default(),
             #[cfg(feature = "navigation")]
             almanac,
             #[cfg(feature = "navigation")]
             earth_cef,
+            #[cfg(feature = "navigation")]
+            orbit_samples: Default::default(),
+        }
+    }
+
+    /// Discards the high precision positions cached for orbit interpolation.
+    /// Call this whenever the high precision orbits may have been modified.
+    pub(crate) fn invalidate_orbits(&mut self) {
+        #[cfg(feature = "navigation")]
+        {
+            self.orbit_samples = Default::default();
+        }
+    }
+
+    /// Returns a new empty [QcContext] that shares the setup of Self
+    /// (like the navigation [Almanac] and [Frame]).
+    pub(crate) fn empty_clone(&self) -> Self {
+        Self {
+            files: Default::default(),
+            metadata: Default::default(),
+            blob: Default::default(),
+            preprocessing: self.preprocessing.clone(),
+            #[cfg(feature = "navigation")]
+            almanac: self.almanac.clone(),
+            #[cfg(feature = "navigation")]
+            earth_cef: self.earth_cef,
+            #[cfg(feature = "navigation")]
+            orbit_samples: Default::default(),
         }
     }
 
@@ -129,20 +260,24 @@ impl QcContext {
     ///
     /// assert_eq!(context.timescale(), Some(TimeScale::GPST));
     /// ```
+    ///
+    /// Other products may be expressed in other timescales:
+    /// use [Self::timescale_conflicts] to verify that the whole context agrees.
     pub fn timescale(&self) -> Option<TimeScale> {
         if let Some(obs) = self.observation() {
             let first = obs.first_epoch()?;
             Some(first.time_scale)
-        // } else if let Some(dor) = self.doris() {
-        //     let first = dor.first_epoch()?;
-        //     Some(first.time_scale)
+        } else if let Some(dor) = self.doris() {
+            let first = dor.first_epoch()?;
+            Some(first.time_scale)
         } else if let Some(clk) = self.clock() {
             let first = clk.first_epoch()?;
             Some(first.time_scale)
-        } else if self.meteo().is_some() {
+        } else if let Some(meteo) = self.meteo() {
+            let first = meteo.first_epoch()?;
+            Some(first.time_scale)
+        } else if self.ionex().is_some() {
             Some(TimeScale::UTC)
-        // } else if self.ionex().is_some() {
-        //    Some(TimeScale::UTC)
         } else {
             #[cfg(feature = "sp3")]
             if let Some(sp3) = self.sp3() {
@@ -153,6 +288,113 @@ impl QcContext {
         }
     }
 
+    /// Returns the [TimeScale]s in which each temporal product is expressed:
+    /// Observation, Meteo, Clock and Navigation RINEX, and SP3.
+    /// Navigation frames are natively expressed in the timescale of each constellation.
+    pub fn timescales(&self) -> Vec<(ProductType, TimeScale)> {
+        let mut timescales = Vec::<(ProductType, TimeScale)>::new();
+
+        for (key, data) in self.blob.iter().sorted_by_key(|(key, _)| *key) {
+            let product_timescales = match (key.product, data) {
+                (ProductType::Observation, BlobData::RINEX(rinex)) => rinex
+                    .record
+                    .as_obs()
+                    .map(|rec| rec.keys().map(|k| k.epoch.time_scale).dedup().collect())
+                    .unwrap_or_default(),
+                (ProductType::MeteoObservation, BlobData::RINEX(rinex)) => rinex
+                    .record
+                    .as_meteo()
+                    .map(|rec| rec.keys().map(|k| k.epoch.time_scale).dedup().collect())
+                    .unwrap_or_default(),
+                (ProductType::HighPrecisionClock, BlobData::RINEX(rinex)) => rinex
+                    .record
+                    .as_clock()
+                    .map(|rec| rec.keys().map(|t| t.time_scale).dedup().collect())
+                    .unwrap_or_default(),
+                (ProductType::BroadcastNavigation, BlobData::RINEX(rinex)) => rinex
+                    .record
+                    .as_nav()
+                    .map(|rec| rec.keys().map(|k| k.epoch.time_scale).dedup().collect())
+                    .unwrap_or_default(),
+                #[cfg(feature = "sp3")]
+                (ProductType::HighPrecisionOrbit, BlobData::SP3(sp3)) => vec![sp3.header.timescale],
+                _ => Vec::<TimeScale>::new(),
+            };
+
+            for timescale in product_timescales {
+                if !timescales.contains(&(key.product, timescale)) {
+                    timescales.push((key.product, timescale));
+                }
+            }
+        }
+
+        timescales
+    }
+
+    /// Returns the (product, [TimeScale]) pairs that disagree with [Self::timescale].
+    /// Empty when the whole [QcContext] agrees on one [TimeScale], for example
+    /// after a [TimeScale] transposition. Navigation frames expressed in the native
+    /// timescale of their constellation (as defined by their ICD) agree with any [TimeScale].
+    /// ```
+    /// use gnss_qc::prelude::{QcContext, ProductType, TimeScale};
+    ///
+    /// let mut context = QcContext::new();
+    ///
+    /// context.load_gzip_rinex_file("data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+    ///     .unwrap();
+    ///
+    /// // GPST observations
+    /// assert!(context.timescale_conflicts().is_empty());
+    ///
+    /// // Multi-GNSS navigation, natively expressed in GPST, GST, BDT..
+    /// context.load_gzip_rinex_file("data/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+    ///     .unwrap();
+    ///
+    /// assert!(context.timescale_conflicts().is_empty());
+    ///
+    /// assert!(context
+    ///     .timescales()
+    ///     .contains(&(ProductType::BroadcastNavigation, TimeScale::GST)));
+    /// ```
+    pub fn timescale_conflicts(&self) -> Vec<(ProductType, TimeScale)> {
+        let timescale = match self.timescale() {
+            Some(timescale) => timescale,
+            None => return Vec::new(),
+        };
+
+        let mut conflicts = self
+            .timescales()
+            .into_iter()
+            .filter(|(product, ts)| {
+                *product != ProductType::BroadcastNavigation && *ts != timescale
+            })
+            .collect::<Vec<_>>();
+
+        for (key, data) in self.blob.iter().sorted_by_key(|(key, _)| *key) {
+            if key.product != ProductType::BroadcastNavigation {
+                continue;
+            }
+
+            let record = match data.as_rinex().and_then(|rinex| rinex.record.as_nav()) {
+                Some(record) => record,
+                None => continue,
+            };
+
+            for k in record.keys() {
+                let ts = k.epoch.time_scale;
+
+                if ts != timescale
+                    && !is_native_timescale(k.sv.constellation, ts)
+                    && !conflicts.contains(&(key.product, ts))
+                {
+                    conflicts.push((key.product, ts));
+                }
+            }
+        }
+
+        conflicts
+    }
+
     /// Returns path to File considered as Primary product in this Context.
     /// When a unique file had been loaded, it is obviously considered Primary.
     pub fn primary_path(&self) -> Option<&PathBuf> {
@@ -162,14 +404,17 @@ impl QcContext {
          */
         for product in [
             ProductType::Observation,
-            // ProductType::DORIS,
+            ProductType::DORIS,
             ProductType::BroadcastNavigation,
             ProductType::MeteoObservation,
-            // ProductType::IONEX,
+            ProductType::IONEX,
             ProductType::ANTEX,
             ProductType::HighPrecisionClock,
             #[cfg(feature = "sp3")]
             ProductType::HighPrecisionOrbit,
+            ProductType::CodeBias,
+            ProductType::EarthOrientation,
+            ProductType::StationCoordinates,
         ] {
             if let Some(paths) = self.files(product) {
                 /*
@@ -227,32 +472,126 @@ impl QcContext {
             .reduce(|k, _| k)
     }
 
-    /// Returns reference to inner data of given category
+    /// Returns name of the Primary station for this station dependent [ProductType],
+    /// which is the station of the first file loaded in this category.
+    pub(crate) fn primary_station(&self, product: ProductType) -> Option<String> {
+        if !product.is_station_dependent() {
+            return None;
+        }
+        let path = self.files(product)?.first()?;
+        self.metadata.get(path)?.station.clone()
+    }
+
+    /// Returns [BlobKey] of the Primary data of given category
+    fn primary_key(&self, product: ProductType) -> BlobKey {
+        BlobKey {
+            product,
+            station: self.primary_station(product),
+        }
+    }
+
+    /// Returns reference to inner data of given category.
+    /// For station dependent products, this is the Primary station.
     pub(crate) fn data(&self, product: ProductType) -> Option<&BlobData> {
-        self.blob
-            .iter()
-            .filter_map(|(prod_type, data)| {
-                if *prod_type == product {
-                    Some(data)
-                } else {
-                    None
-                }
-            })
-            .reduce(|k, _| k)
+        let key = self.primary_key(product);
+        self.blob.get(&key)
     }
 
-    /// Returns mutable reference to inner data of given category
+    /// Returns mutable reference to inner data of given category.
+    /// For station dependent products, this is the Primary station.
     pub(crate) fn data_mut(&mut self, product: ProductType) -> Option<&mut BlobData> {
-        self.blob
-            .iter_mut()
-            .filter_map(|(prod_type, data)| {
-                if *prod_type == product {
-                    Some(data)
+        let key = self.primary_key(product);
+        self.blob.get_mut(&key)
+    }
+
+    /// Returns reference to inner data of given category, for given station.
+    pub(crate) fn station_data(&self, product: ProductType, station: &str) -> Option<&BlobData> {
+        self.blob.get(&BlobKey {
+            product,
+            station: Some(station_key(station)),
+        })
+    }
+
+    /// Returns mutable reference to inner data of given category, for given station.
+    pub(crate) fn station_data_mut(
+        &mut self,
+        product: ProductType,
+        station: &str,
+    ) -> Option<&mut BlobData> {
+        self.blob.get_mut(&BlobKey {
+            product,
+            station: Some(station_key(station)),
+        })
+    }
+
+    /// Returns an iterator over all stations (markers) described
+    /// by this [QcContext], in alphabetical order.
+    /// ```
+    /// use gnss_qc::prelude::QcContext;
+    ///
+    /// let mut context = QcContext::new();
+    ///
+    /// context.load_rinex_file("data/OBS/V2/AJAC3550.21O")
+    ///     .unwrap();
+    ///
+    /// context.load_gzip_rinex_file("data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+    ///     .unwrap();
+    ///
+    /// // each station is kept apart
+    /// assert_eq!(context.stations_iter().count(), 2);
+    ///
+    /// for station in context.stations_iter() {
+    ///     assert!(context.observation_of(station).is_some());
+    /// }
+    /// ```
+    pub fn stations_iter(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        Box::new(
+            self.blob
+                .keys()
+                .filter_map(|k| k.station.as_deref())
+                .sorted()
+                .dedup(),
+        )
+    }
+
+    /// Returns files loaded for this station
+    pub fn station_files(&self, station: &str) -> Vec<&PathBuf> {
+        let station = station_key(station);
+        self.metadata
+            .iter()
+            .filter_map(|(path, meta)| {
+                if meta.station.as_ref() == Some(&station) {
+                    Some(path)
                 } else {
                     None
                 }
             })
-            .reduce(move |k, _| k)
+            .sorted()
+            .collect()
+    }
+
+    /// Returns reference to [ProductType::Observation] data of this station (marker).
+    pub fn observation_of(&self, station: &str) -> Option<&Rinex> {
+        self.station_data(ProductType::Observation, station)?
+            .as_rinex()
+    }
+
+    /// Returns mutable reference to [ProductType::Observation] data of this station (marker).
+    pub fn observation_of_mut(&mut self, station: &str) -> Option<&mut Rinex> {
+        self.station_data_mut(ProductType::Observation, station)?
+            .as_mut_rinex()
+    }
+
+    /// Returns reference to [ProductType::MeteoObservation] data of this station (marker).
+    pub fn meteo_of(&self, station: &str) -> Option<&Rinex> {
+        self.station_data(ProductType::MeteoObservation, station)?
+            .as_rinex()
+    }
+
+    /// Returns mutable reference to [ProductType::MeteoObservation] data of this station (marker).
+    pub fn meteo_of_mut(&mut self, station: &str) -> Option<&mut Rinex> {
+        self.station_data_mut(ProductType::MeteoObservation, station)?
+            .as_mut_rinex()
     }
 
     /// Returns reference to inner RINEX data of given category
@@ -282,15 +621,17 @@ impl QcContext {
         self.data_mut(product)?.as_mut_rinex()
     }
 
-    /// Returns reference to inner [ProductType::Observation] data
+    /// Returns reference to inner [ProductType::Observation] data.
+    /// When several stations were loaded, this is the Primary station,
+    /// use [Self::observation_of] to select a specific station.
     pub fn observation(&self) -> Option<&Rinex> {
         self.data(ProductType::Observation)?.as_rinex()
     }
 
-    // /// Returns reference to inner [ProductType::DORIS] RINEX data
-    // pub fn doris(&self) -> Option<&Rinex> {
-    //     self.data(ProductType::DORIS)?.as_rinex()
-    // }
+    /// Returns reference to inner [ProductType::DORIS] RINEX data
+    pub fn doris(&self) -> Option<&Rinex> {
+        self.data(ProductType::DORIS)?.as_rinex()
+    }
 
     /// Returns reference to inner [ProductType::BroadcastNavigation] data
     pub fn brdc_navigation(&self) -> Option<&Rinex> {
@@ -312,20 +653,20 @@ impl QcContext {
         self.data(ProductType::ANTEX)?.as_rinex()
     }
 
-    // /// Returns reference to inner [ProductType::IONEX] data
-    // pub fn ionex(&self) -> Option<&Rinex> {
-    //     self.data(ProductType::IONEX)?.as_rinex()
-    // }
+    /// Returns reference to inner [ProductType::IONEX] data
+    pub fn ionex(&self) -> Option<&Rinex> {
+        self.data(ProductType::IONEX)?.as_rinex()
+    }
 
     /// Returns mutable reference to inner [ProductType::Observation] data
     pub fn observation_mut(&mut self) -> Option<&mut Rinex> {
         self.data_mut(ProductType::Observation)?.as_mut_rinex()
     }
 
-    // /// Returns mutable reference to inner [ProductType::DORIS] RINEX data
-    // pub fn doris_mut(&mut self) -> Option<&mut Rinex> {
-    //     self.data_mut(ProductType::DORIS)?.as_mut_rinex()
-    // }
+    /// Returns mutable reference to inner [ProductType::DORIS] RINEX data
+    pub fn doris_mut(&mut self) -> Option<&mut Rinex> {
+        self.data_mut(ProductType::DORIS)?.as_mut_rinex()
+    }
 
     /// Returns mutable reference to inner [ProductType::Observation] data
     pub fn brdc_navigation_mut(&mut self) -> Option<&mut Rinex> {
@@ -349,10 +690,10 @@ impl QcContext {
         self.data_mut(ProductType::ANTEX)?.as_mut_rinex()
     }
 
-    // /// Returns mutable reference to inner [ProductType::IONEX] data
-    // pub fn ionex_mut(&mut self) -> Option<&mut Rinex> {
-    //     self.data_mut(ProductType::IONEX)?.as_mut_rinex()
-    // }
+    /// Returns mutable reference to inner [ProductType::IONEX] data
+    pub fn ionex_mut(&mut self) -> Option<&mut Rinex> {
+        self.data_mut(ProductType::IONEX)?.as_mut_rinex()
+    }
 
     /// Returns true if [ProductType::Observation] are present in Self
     pub fn has_observation(&self) -> bool {
@@ -364,55 +705,129 @@ impl QcContext {
         self.brdc_navigation().is_some()
     }
 
-    // /// Returns true if at least one [ProductType::DORIS] file is present
-    // pub fn has_doris(&self) -> bool {
-    //     self.doris().is_some()
-    // }
+    /// Returns true if at least one [ProductType::DORIS] file is present
+    pub fn has_doris(&self) -> bool {
+        self.doris().is_some()
+    }
 
     /// Returns true if [ProductType::MeteoObservation] are present in Self
     pub fn has_meteo(&self) -> bool {
         self.meteo().is_some()
     }
 
+    /// Returns true if [ProductType::IONEX] maps are present in Self
+    pub fn has_ionex(&self) -> bool {
+        self.ionex().is_some()
+    }
+
     /// Load a readable [Rinex] file into this [QcContext].
     pub fn load_rinex_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
-        let rinex = Rinex::from_file(&path)?;
-        self.load_rinex(path, rinex)
+        let (blob, digest) = parse_file(&path, FileFormat::RINEX, false)?;
+        self.load_parsed(path, blob, digest)
     }
 
     /// Load a single [Rinex] file into this [QcContext].
     /// File revision must be supported and must be correctly formatted
     /// for this operation to be effective.
+    /// Like the data already loaded, it is preprocessed with the recorded
+    /// [QcPipeline] (see [Self::preprocessing]).
     pub fn load_rinex<P: AsRef<Path>>(&mut self, path: P, rinex: Rinex) -> Result<(), Error> {
+        if !self.preprocessing.steps.is_empty() {
+            return self.load_preprocessed(path.as_ref(), BlobData::RINEX(rinex));
+        }
+
         let prod_type = ProductType::from(rinex.header.rinex_type);
 
         let path_buf = path.as_ref().to_path_buf();
 
+        let meta = FileMetadata::from_rinex(&path_buf, &rinex);
+
+        let key = BlobKey {
+            product: prod_type,
+            station: meta.station.clone(),
+        };
+
         // extend context blob
-        if let Some(paths) = self
-            .files
-            .iter_mut()
-            .filter_map(|(prod, files)| {
-                if *prod == prod_type {
-                    Some(files)
-                } else {
-                    None
-                }
-            })
-            .reduce(|k, _| k)
-        {
-            if let Some(inner) = self.blob.get_mut(&prod_type).and_then(|k| k.as_mut_rinex()) {
+        if let Some(inner) = self.blob.get_mut(&key) {
+            if let Some(inner) = inner.as_mut_rinex() {
                 inner.merge_mut(&rinex)?;
-                paths.push(path_buf);
             }
         } else {
-            self.blob.insert(prod_type, BlobData::RINEX(rinex));
+            self.blob.insert(key, BlobData::RINEX(rinex));
+        }
+
+        self.metadata.insert(path_buf.clone(), meta);
+
+        if let Some(paths) = self.files.get_mut(&prod_type) {
+            paths.push(path_buf);
+        } else {
             self.files.insert(prod_type, vec![path_buf]);
         }
 
         Ok(())
     }
 
+    /// Load parsed [BlobData] into this [QcContext].
+    pub(crate) fn load_blob<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        blob: BlobData,
+    ) -> Result<(), Error> {
+        match blob {
+            BlobData::RINEX(rinex) => self.load_rinex(path, rinex),
+            #[cfg(feature = "sp3")]
+            BlobData::SP3(sp3) => self.load_sp3(path, sp3),
+            BlobData::BIAS(bias) => self.load_bias(path, bias),
+            BlobData::EOP(eop) => self.load_eop(path, eop),
+            BlobData::SNX(snx) => self.load_station_solutions(path, snx),
+        }
+    }
+
+    /// Load [BlobData] parsed from this readable file, with its [FileDigest].
+    pub(crate) fn load_parsed<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        blob: BlobData,
+        digest: FileDigest,
+    ) -> Result<(), Error> {
+        self.load_blob(&path, blob)?;
+
+        if let Some(meta) = self.metadata.get_mut(path.as_ref()) {
+            meta.set_digest(digest);
+        }
+
+        Ok(())
+    }
+
+    /// Merges the content of this [QcContext], freshly loaded, into Self.
+    /// All blobs are merged first: Self is not modified on failure.
+    pub(crate) fn extend_loaded(&mut self, fresh: Self) -> Result<(), Error> {
+        let mut merged = Vec::with_capacity(fresh.blob.len());
+
+        for (key, data) in fresh.blob {
+            let data = match self.blob.get(&key) {
+                Some(base) => {
+                    let mut base = base.clone();
+                    base.merge_mut(&data)?;
+                    base
+                }
+                None => data,
+            };
+
+            merged.push((key, data));
+        }
+
+        self.blob.extend(merged);
+        self.invalidate_orbits();
+
+        for (product, paths) in fresh.files {
+            self.files.entry(product).or_default().extend(paths);
+        }
+
+        self.metadata.extend(fresh.metadata);
+        Ok(())
+    }
+
     /// True if current [QcContext] is compatible with basic post processed navigation.
     /// It does not mean you can actually perform post processed navigation, you need the "navigation"
     /// feature for that.
@@ -423,8 +838,8 @@ impl QcContext {
     /// Returns true if provided Input products allow Ionosphere bias
     /// model optimization
     pub fn iono_bias_model_optimization(&self) -> bool {
-        // self.ionex().is_some() // TODO: BRDC V3 or V4
-        false
+        // TODO: BRDC V3 or V4
+        self.has_ionex()
     }
 
     /// Returns true if provided Input products allow Troposphere bias
@@ -436,36 +851,19 @@ impl QcContext {
     /// Apply preprocessing filter algorithm to mutable [Self].
     /// Filter will apply to all data contained in the context.
     pub fn filter_mut(&mut self, filter: &Filter) {
-        if let Some(data) = self.observation_mut() {
-            data.filter_mut(filter);
-        }
-        if let Some(data) = self.brdc_navigation_mut() {
-            data.filter_mut(filter);
-        }
-        // if let Some(data) = self.doris_mut() {
-        //    data.filter_mut(filter);
-        //}
-        if let Some(data) = self.meteo_mut() {
-            data.filter_mut(filter);
-        }
-        if let Some(data) = self.clock_mut() {
-            data.filter_mut(filter);
-        }
-
-        // if let Some(data) = self.ionex_mut() {
-        //     data.filter_mut(filter);
-        // }
+        self.invalidate_orbits();
 
-        #[cfg(feature = "sp3")]
-        if let Some(data) = self.sp3_mut() {
-            data.filter_mut(filter);
-        }
-    }
-
-    /// Fix given [Repair] condition
-    pub fn repair_mut(&mut self, r: Repair) {
-        if let Some(rinex) = self.observation_mut() {
-            rinex.repair_mut(r);
+        for (key, data) in self.blob.iter_mut() {
+            if key.product == ProductType::ANTEX {
+                continue;
+            }
+            match data {
+                BlobData::RINEX(rinex) => rinex.filter_mut(filter),
+                #[cfg(feature = "sp3")]
+                BlobData::SP3(sp3) => sp3.filter_mut(filter),
+                // biases, EOP and station coordinates are not filtered: they are only looked up
+                BlobData::BIAS(_) | BlobData::EOP(_) | BlobData::SNX(_) => {}
+            }
         }
     }
 
@@ -504,19 +902,27 @@ impl std::fmt::Debug for QcContext {
         write!(f, "Primary: \"{}\"", self.name())?;
         for product in [
             ProductType::Observation,
+            ProductType::DORIS,
             ProductType::BroadcastNavigation,
             ProductType::MeteoObservation,
             ProductType::HighPrecisionClock,
-            // ProductType::IONEX,
+            ProductType::IONEX,
             ProductType::ANTEX,
             #[cfg(feature = "sp3")]
             ProductType::HighPrecisionOrbit,
+            ProductType::CodeBias,
+            ProductType::EarthOrientation,
+            ProductType::StationCoordinates,
         ] {
             if let Some(files) = self.files(product) {
                 write!(f, "\n{}: ", product)?;
                 write!(f, "{:?}", files,)?;
             }
         }
+        for station in self.stations_iter() {
+            write!(f, "\nStation {}: ", station)?;
+            write!(f, "{:?}", self.station_files(station))?;
+        }
         Ok(())
     }
 }
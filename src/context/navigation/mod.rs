@@ -14,11 +14,11 @@ use anise::{
 
 use crate::{
     navigation::{NavFilter, NavFilterType},
-    prelude::{Constellation, QcContext},
+    prelude::{Constellation, ProductType, QcContext, Rinex},
 };
 
 #[cfg(feature = "navigation")]
-use crate::prelude::{Orbit, ReferenceEcefPosition};
+use crate::prelude::{EopSample, Epoch, Orbit, ReferenceEcefPosition};
 
 #[derive(Debug, Error)]
 pub enum NavigationError {
@@ -79,9 +79,12 @@ impl QcContext {
     pub fn new_alamac_frame(almanac: Almanac, frame: Frame) -> Self {
         Self {
             files: Default::default(),
+            metadata: Default::default(),
             blob: Default::default(),
+            preprocessing: Default::default(),
             almanac,
             earth_cef: frame,
+            orbit_samples: Default::default(),
         }
     }
 
@@ -97,33 +100,122 @@ impl QcContext {
             }
         };
 
-        let frame = almanac
-            .frame_from_uid(EARTH_J2000)
-            .unwrap_or_else(|e| panic!("anise internal error: {}", e));
+        let frame = Self::default_frame(&almanac);
 
         (almanac, frame)
     }
 
-    /// Returns a possible [ReferenceEcefPosition] if defined in current [QcContext].
-    /// NB: this is only picked from a possible [Rinex] Observations, not any
-    /// other possible source. If no Observations were loaded, there is no point
-    /// asking for this in this current form.
-    pub fn reference_rx_position(&self) -> Option<ReferenceEcefPosition> {
-        let obs_rinex = self.observation()?;
+    /// Obtains default ECEF [Frame] definition from this [Almanac]
+    pub(crate) fn default_frame(almanac: &Almanac) -> Frame {
+        almanac
+            .frame_from_uid(EARTH_J2000)
+            .unwrap_or_else(|e| panic!("anise internal error: {}", e))
+    }
+
+    /// Returns the [ReferenceEcefPosition] of this station (Observation RINEX),
+    /// at its first [Epoch](crate::prelude::Epoch). SINEX station coordinates are preferred,
+    /// when loaded and matching the station (by marker name or DOMES number), and
+    /// propagated to that epoch. Otherwise, the position is picked from the RINEX header.
+    fn observation_reference_position(
+        &self,
+        station: Option<&str>,
+        obs_rinex: &Rinex,
+    ) -> Option<ReferenceEcefPosition> {
         let t = obs_rinex.first_epoch()?;
+
+        if let Some(solutions) = self.station_solutions() {
+            let domes = obs_rinex
+                .header
+                .geodetic_marker
+                .as_ref()
+                .and_then(|marker| marker.number());
+
+            if let Some(coordinates) = solutions.station(station, domes.as_deref(), t) {
+                return Some(ReferenceEcefPosition::from_station_coordinates(
+                    coordinates,
+                    solutions.agency.clone(),
+                    t,
+                ));
+            }
+        }
+
         let rx_orbit = obs_rinex.header.rx_orbit(t, self.earth_cef)?;
-        let pos = ReferenceEcefPosition::from_orbit(&rx_orbit);
-        Some(pos)
+        Some(ReferenceEcefPosition::from_orbit(&rx_orbit))
     }
 
-    /// Returns a possible reference position, expressed as [Orbit], if defined in current [QcContext].
-    /// NB: this is only picked from a possible [Rinex] Observations, not any
-    /// other possible source. If no Observations were loaded, there is no point
-    /// asking for this in this current form.
-    pub fn reference_rx_orbit(&self) -> Option<Orbit> {
+    /// Returns a possible [ReferenceEcefPosition] of the primary station, if defined in current [QcContext].
+    /// It is picked from the SINEX station coordinates when they describe this station,
+    /// otherwise from the Observation RINEX header. The [ReferencePositionSource](crate::prelude::ReferencePositionSource) tells which.
+    /// When the primary Observation does not name its station, only its header position
+    /// (or SINEX coordinates matching its DOMES number) may be used.
+    /// If no Observations were loaded, there is no point asking for this in this current form.
+    pub fn reference_rx_position(&self) -> Option<ReferenceEcefPosition> {
+        let station = self.primary_station(ProductType::Observation);
         let obs_rinex = self.observation()?;
-        let t = obs_rinex.first_epoch()?;
-        obs_rinex.header.rx_orbit(t, self.earth_cef)
+        self.observation_reference_position(station.as_deref(), obs_rinex)
+    }
+
+    /// Returns a possible [ReferenceEcefPosition] of this station, if defined in current [QcContext].
+    /// See [Self::reference_rx_position] for more information.
+    pub fn station_reference_position(&self, station: &str) -> Option<ReferenceEcefPosition> {
+        let obs_rinex = self.observation_of(station)?;
+        self.observation_reference_position(Some(station), obs_rinex)
+    }
+
+    /// Returns a possible reference position of the primary station, expressed as [Orbit],
+    /// at its first observation. See [Self::reference_rx_position] for more information.
+    pub fn reference_rx_orbit(&self) -> Option<Orbit> {
+        let t = self.observation()?.first_epoch()?;
+        let position = self.reference_rx_position()?;
+        Some(position.to_orbit(t, self.earth_cef))
+    }
+
+    /// Returns the Earth orientation at this [Epoch]: interpolated from the
+    /// [ProductType::EarthOrientation] when loaded and covering this [Epoch],
+    /// otherwise nominal (UT1 = UTC, no polar motion).
+    fn earth_orientation_or_nominal(&self, t: Epoch) -> EopSample {
+        self.earth_orientation(t)
+            .unwrap_or_else(|| EopSample::nominal(t))
+    }
+
+    /// Expresses this Earth fixed [Orbit] (ITRF) in the Earth centered inertial frame
+    /// (Celestial Intermediate Reference System), at its [Epoch]: polar motion and
+    /// Earth rotation angle (from UT1) are obtained from the
+    /// [ProductType::EarthOrientation] when loaded, they are nominal otherwise.
+    /// Use this when propagating states in inertial space, and [Self::inertial_to_itrf_orbit]
+    /// to express them back in the Earth fixed frame. Precession and nutation are not
+    /// applied, the [Frame] is preserved.
+    pub fn itrf_to_inertial_orbit(&self, orbit: Orbit) -> Orbit {
+        let eop = self.earth_orientation_or_nominal(orbit.epoch);
+
+        let (r, v) = eop.itrs_to_cirs(
+            (orbit.radius_km.x, orbit.radius_km.y, orbit.radius_km.z),
+            (
+                orbit.velocity_km_s.x,
+                orbit.velocity_km_s.y,
+                orbit.velocity_km_s.z,
+            ),
+        );
+
+        Orbit::new(r.0, r.1, r.2, v.0, v.1, v.2, orbit.epoch, orbit.frame)
+    }
+
+    /// Expresses this Earth centered inertial [Orbit] (Celestial Intermediate Reference System)
+    /// in the Earth fixed frame (ITRF), at its [Epoch].
+    /// Inverse of [Self::itrf_to_inertial_orbit].
+    pub fn inertial_to_itrf_orbit(&self, orbit: Orbit) -> Orbit {
+        let eop = self.earth_orientation_or_nominal(orbit.epoch);
+
+        let (r, v) = eop.cirs_to_itrs(
+            (orbit.radius_km.x, orbit.radius_km.y, orbit.radius_km.z),
+            (
+                orbit.velocity_km_s.x,
+                orbit.velocity_km_s.y,
+                orbit.velocity_km_s.z,
+            ),
+        );
+
+        Orbit::new(r.0, r.1, r.2, v.0, v.1, v.2, orbit.epoch, orbit.frame)
     }
 
     /// Applies complex [NavFilter] to mutable [QcContext].
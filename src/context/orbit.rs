@@ -0,0 +1,359 @@
+//! Satellite orbits resolution
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use crate::prelude::{Duration, Epoch, Frame, Orbit, QcContext, Rinex, SV};
+
+/// [QcOrbitOptions] define how [QcOrbitInterpolator] resolves satellite states.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct QcOrbitOptions {
+    /// Lagrange interpolation order of high precision orbits.
+    /// (order + 1) consecutive samples are required to interpolate.
+    pub interpolation_order: usize,
+    /// Maximal spacing between two consecutive samples involved in one interpolation.
+    /// When not defined, 1.5 times the product sampling interval is tolerated.
+    pub max_gap: Option<Duration>,
+    /// Resolve states from broadcast ephemerides, when no high precision
+    /// orbit is loaded or it may not be interpolated at the desired [Epoch].
+    pub brdc_fallback: bool,
+}
+
+impl Default for QcOrbitOptions {
+    /// 11th order interpolation with broadcast fallback
+    fn default() -> Self {
+        Self {
+            interpolation_order: 11,
+            max_gap: None,
+            brdc_fallback: true,
+        }
+    }
+}
+
+impl QcOrbitOptions {
+    /// Copies and returns [QcOrbitOptions] with this interpolation order
+    pub fn with_interpolation_order(&self, order: usize) -> Self {
+        let mut s = *self;
+        s.interpolation_order = order;
+        s
+    }
+
+    /// Copies and returns [QcOrbitOptions] with this maximal gap tolerance
+    pub fn with_max_gap(&self, max_gap: Duration) -> Self {
+        let mut s = *self;
+        s.max_gap = Some(max_gap);
+        s
+    }
+
+    /// Copies and returns [QcOrbitOptions] with broadcast fallback enabled or disabled
+    pub fn with_brdc_fallback(&self, fallback: bool) -> Self {
+        let mut s = *self;
+        s.brdc_fallback = fallback;
+        s
+    }
+}
+
+/// [QcOrbitSource] describes how a [QcSatelliteState] was resolved
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum QcOrbitSource {
+    /// Interpolated from high precision orbits
+    #[cfg(feature = "sp3")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sp3")))]
+    SP3,
+    /// Resolved from broadcast ephemerides
+    BroadcastNavigation,
+}
+
+impl std::fmt::Display for QcOrbitSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            #[cfg(feature = "sp3")]
+            Self::SP3 => write!(f, "SP3"),
+            Self::BroadcastNavigation => write!(f, "BRDC"),
+        }
+    }
+}
+
+/// [QcSatelliteState] is the ECEF state of one satellite at one [Epoch]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct QcSatelliteState {
+    /// [SV]
+    pub sv: SV,
+    /// How this state was resolved
+    pub source: QcOrbitSource,
+    /// ECEF state, as [Orbit]
+    pub orbit: Orbit,
+}
+
+/// Evaluates the Lagrange polynomial passing through these (x, y) nodes, at x
+#[cfg(feature = "sp3")]
+fn lagrange(nodes: &[(f64, f64)], x: f64) -> f64 {
+    let mut y = 0.0;
+
+    for (j, (x_j, y_j)) in nodes.iter().enumerate() {
+        let mut basis = 1.0;
+
+        for (m, (x_m, _)) in nodes.iter().enumerate() {
+            if m != j {
+                basis *= (x - x_m) / (x_j - x_m);
+            }
+        }
+
+        y += y_j * basis;
+    }
+
+    y
+}
+
+/// High precision positions of a [QcContext], gathered once and cached
+/// until its high precision orbits are modified.
+#[derive(Debug, Default)]
+#[cfg_attr(not(feature = "sp3"), allow(dead_code))]
+pub(crate) struct QcOrbitSamples {
+    /// High precision positions [km], per [SV], in chronological order
+    samples: BTreeMap<SV, Vec<(Epoch, (f64, f64, f64))>>,
+    /// High precision orbits sampling interval
+    sampling_interval: Duration,
+}
+
+impl QcOrbitSamples {
+    /// Gathers the high precision positions of this [QcContext]
+    fn new(ctx: &QcContext) -> Self {
+        #[allow(unused_mut)]
+        let mut s = Self::default();
+
+        #[cfg(feature = "sp3")]
+        if let Some(sp3) = ctx.sp3() {
+            s.sampling_interval = sp3.header.sampling_period;
+
+            for (t, sv, _, _, position_km) in sp3.satellites_position_km_iter() {
+                s.samples.entry(sv).or_default().push((t, position_km));
+            }
+
+            for samples in s.samples.values_mut() {
+                samples.sort_by(|a, b| a.0.cmp(&b.0));
+            }
+        }
+
+        #[cfg(not(feature = "sp3"))]
+        let _ = ctx;
+
+        s
+    }
+}
+
+/// [QcOrbitInterpolator] resolves satellite ECEF states at arbitrary epochs,
+/// from the high precision orbits and broadcast ephemerides of a [QcContext].
+/// Create one with [QcContext::orbit_interpolator] and share it between your processes.
+#[cfg_attr(not(feature = "sp3"), allow(dead_code))]
+pub struct QcOrbitInterpolator<'a> {
+    /// [QcOrbitOptions]
+    options: QcOrbitOptions,
+    /// ECEF [Frame]
+    frame: Frame,
+    /// High precision positions, shared with the [QcContext]
+    samples: Arc<QcOrbitSamples>,
+    /// Broadcast navigation
+    brdc: Option<&'a Rinex>,
+}
+
+impl<'a> QcOrbitInterpolator<'a> {
+    /// Returns true if this [SV] is described by high precision orbits
+    pub fn has_sp3_orbit(&self, sv: SV) -> bool {
+        self.samples.samples.contains_key(&sv)
+    }
+
+    /// Resolves the state of this [SV] at this [Epoch].
+    /// High precision orbits are preferred. They are only interpolated inside their time frame
+    /// (no extrapolation at the day edges), with a window that is shifted near the edges and
+    /// may not contain a data gap. Otherwise, the broadcast ephemerides are used (if allowed).
+    pub fn state(&self, sv: SV, t: Epoch) -> Option<QcSatelliteState> {
+        #[cfg(feature = "sp3")]
+        if let Some(orbit) = self.interpolate(sv, t) {
+            return Some(QcSatelliteState {
+                sv,
+                source: QcOrbitSource::SP3,
+                orbit,
+            });
+        }
+
+        if !self.options.brdc_fallback {
+            return None;
+        }
+
+        self.brdc_state(sv, t)
+    }
+
+    /// Resolves the state of this [SV] at this [Epoch] from the broadcast ephemerides only,
+    /// whatever the [QcOrbitOptions]. This is typically used to assess them against high precision orbits.
+    pub fn brdc_state(&self, sv: SV, t: Epoch) -> Option<QcSatelliteState> {
+        let orbit = self.brdc?.sv_orbit(sv, t)?;
+
+        Some(QcSatelliteState {
+            sv,
+            source: QcOrbitSource::BroadcastNavigation,
+            orbit,
+        })
+    }
+
+    /// Resolves the state of each satellite observed by this Observation [Rinex],
+    /// at every [Epoch] it was observed.
+    pub fn observation_states_iter<'b>(
+        &'b self,
+        rinex: &'b Rinex,
+    ) -> Box<dyn Iterator<Item = (Epoch, QcSatelliteState)> + 'b> {
+        Box::new(
+            rinex
+                .signal_observations_iter()
+                .map(|(k, signal)| (k.epoch, signal.sv))
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .filter_map(move |(t, sv)| Some((t, self.state(sv, t)?))),
+        )
+    }
+
+    /// Resolves the state of each satellite described by the high precision orbits,
+    /// at every [Epoch] they were sampled.
+    #[cfg(feature = "sp3")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sp3")))]
+    pub fn sp3_states_iter(&self) -> Box<dyn Iterator<Item = (Epoch, QcSatelliteState)> + '_> {
+        Box::new(self.samples.samples.iter().flat_map(move |(sv, samples)| {
+            samples
+                .iter()
+                .filter_map(move |(t, _)| Some((*t, self.state(*sv, *t)?)))
+        }))
+    }
+
+    /// Interpolates the high precision orbit of this [SV] at this [Epoch]
+    #[cfg(feature = "sp3")]
+    fn interpolate(&self, sv: SV, t: Epoch) -> Option<Orbit> {
+        let samples = self.samples.samples.get(&sv)?;
+
+        let size = self.options.interpolation_order + 1;
+
+        if samples.len() < size {
+            return None;
+        }
+
+        // no extrapolation
+        let (first, last) = (samples.first()?.0, samples.last()?.0);
+
+        if t < first || t > last {
+            return None;
+        }
+
+        // centered window, shifted at the edges
+        let index = samples.partition_point(|(t_k, _)| *t_k <= t);
+        let start = index.saturating_sub(size / 2).min(samples.len() - size);
+
+        let window = &samples[start..start + size];
+
+        let max_gap = self
+            .options
+            .max_gap
+            .unwrap_or(self.samples.sampling_interval * 1.5);
+
+        if window
+            .windows(2)
+            .any(|pair| pair[1].0 - pair[0].0 > max_gap)
+        {
+            return None;
+        }
+
+        let t0 = window[0].0;
+
+        let component = |x: f64, pick: fn(&(f64, f64, f64)) -> f64| {
+            let nodes = window
+                .iter()
+                .map(|(t_k, pos)| ((*t_k - t0).to_seconds(), pick(pos)))
+                .collect::<Vec<_>>();
+
+            let value = lagrange(&nodes, x);
+
+            // central difference, on the same polynomial
+            let rate = lagrange(&nodes, x + 0.5) - lagrange(&nodes, x - 0.5);
+
+            (value, rate)
+        };
+
+        let x = (t - t0).to_seconds();
+
+        let (x_km, vx_km_s) = component(x, |pos| pos.0);
+        let (y_km, vy_km_s) = component(x, |pos| pos.1);
+        let (z_km, vz_km_s) = component(x, |pos| pos.2);
+
+        Some(Orbit::new(
+            x_km, y_km, z_km, vx_km_s, vy_km_s, vz_km_s, t, self.frame,
+        ))
+    }
+}
+
+impl QcContext {
+    /// Creates a [QcOrbitInterpolator] from the high precision orbits (when loaded)
+    /// and broadcast ephemerides of this [QcContext]. Reuse it to resolve as many states as needed.
+    /// High precision positions are gathered on first use and cached on the [QcContext],
+    /// until its high precision orbits are modified.
+    /// ```
+    /// use gnss_qc::prelude::{QcContext, QcOrbitOptions, QcOrbitSource};
+    ///
+    /// let mut context = QcContext::new();
+    ///
+    /// context.load_gzip_rinex_file("data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+    ///     .unwrap();
+    ///
+    /// context.load_gzip_rinex_file("data/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+    ///     .unwrap();
+    ///
+    /// let interpolator = context.orbit_interpolator(&QcOrbitOptions::default());
+    ///
+    /// let observation = context.observation().unwrap();
+    ///
+    /// // without SP3, states are resolved from broadcast ephemerides
+    /// for (_, state) in interpolator.observation_states_iter(observation) {
+    ///     assert_eq!(state.source, QcOrbitSource::BroadcastNavigation);
+    /// }
+    /// ```
+    pub fn orbit_interpolator(&self, options: &QcOrbitOptions) -> QcOrbitInterpolator<'_> {
+        let samples = self
+            .orbit_samples
+            .get_or_init(|| Arc::new(QcOrbitSamples::new(self)))
+            .clone();
+
+        QcOrbitInterpolator {
+            options: *options,
+            frame: self.earth_cef,
+            samples,
+            brdc: self.brdc_navigation(),
+        }
+    }
+
+    /// Resolves the state of this [SV] at this [Epoch], with [QcOrbitOptions::default].
+    /// The high precision positions are gathered on first use only,
+    /// yet prefer [Self::orbit_interpolator] when resolving many states.
+    pub fn sv_state(&self, sv: SV, t: Epoch) -> Option<QcSatelliteState> {
+        self.orbit_interpolator(&QcOrbitOptions::default())
+            .state(sv, t)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "sp3")]
+mod test {
+    use super::lagrange;
+
+    #[test]
+    fn lagrange_polynomial() {
+        // cubic is exactly described by 4 nodes
+        let cubic = |x: f64| 2.0 * x.powi(3) - x.powi(2) + 3.0 * x - 5.0;
+
+        let nodes = [0.0, 1.0, 2.5, 4.0]
+            .iter()
+            .map(|x| (*x, cubic(*x)))
+            .collect::<Vec<_>>();
+
+        for x in [0.0, 0.5, 1.7, 3.2, 4.0] {
+            assert!((lagrange(&nodes, x) - cubic(x)).abs() < 1.0E-9);
+        }
+    }
+}
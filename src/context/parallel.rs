@@ -0,0 +1,191 @@
+//! Parallel products loading
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+use crate::{
+    context::{BlobData, BlobKey, FileDigest},
+    error::Error,
+    prelude::{ProductType, QcContext},
+};
+
+#[cfg(any(feature = "rtcm", feature = "ubx", feature = "binex"))]
+use crate::context::stream::DecodedStream;
+
+/// Content of one file, parsed but not loaded yet
+enum Parsed {
+    /// Supported file, with its [FileDigest]
+    File(BlobData, FileDigest),
+    /// Binary stream, that may describe several products
+    #[cfg(any(feature = "rtcm", feature = "ubx", feature = "binex"))]
+    Stream(DecodedStream),
+}
+
+/// Identifies and parses this file, like [QcContext::load_file] does.
+fn parse(path: &Path) -> Result<Parsed, Error> {
+    match QcContext::read_file(path) {
+        Ok((data, digest)) => Ok(Parsed::File(data, digest)),
+        #[cfg(any(feature = "rtcm", feature = "ubx", feature = "binex"))]
+        Err(e @ (Error::NonSupportedFileFormat | Error::BinaryContent)) => {
+            let stream = QcContext::read_stream(path)?.ok_or(e)?;
+            Ok(Parsed::Stream(stream))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Parses all files concurrently. Results are returned in input order.
+fn parse_parallel<P: AsRef<Path> + Sync>(
+    paths: &[P],
+    workers: usize,
+) -> Vec<Result<Parsed, Error>> {
+    let next = AtomicUsize::new(0);
+
+    let results = Mutex::new(
+        (0..paths.len())
+            .map(|_| None)
+            .collect::<Vec<Option<Result<Parsed, Error>>>>(),
+    );
+
+    thread::scope(|scope| {
+        for _ in 0..workers.min(paths.len()) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+
+                let Some(path) = paths.get(index) else {
+                    break;
+                };
+
+                let parsed = parse(path.as_ref());
+
+                results.lock().unwrap()[index] = Some(parsed);
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|parsed| parsed.expect("internal error: file not parsed"))
+        .collect()
+}
+
+impl QcContext {
+    /// Loads all these files into current [QcContext], using all available CPU cores.
+    /// Files are parsed concurrently, then merged in input order, each [ProductType]
+    /// (and station) being merged concurrently. The result is identical to loading
+    /// each file with [Self::load_file], in the same order: binary streams are supported
+    /// (with the related features), and new data is preprocessed with the recorded
+    /// [QcPipeline](crate::prelude::QcPipeline) (see [Self::preprocessing]).
+    ///
+    /// Unlike sequential loading, current [QcContext] is not modified if any file fails to load,
+    /// the first [Error] (in input order) is returned.
+    /// On success, the [ProductType] of each file is returned, in input order.
+    /// ```
+    /// use gnss_qc::prelude::{QcContext, ProductType};
+    ///
+    /// let mut context = QcContext::new();
+    ///
+    /// let products = context.load_files_parallel(&[
+    ///     "data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz",
+    ///     "data/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz",
+    /// ]).unwrap();
+    ///
+    /// assert_eq!(products, vec![ProductType::Observation, ProductType::BroadcastNavigation]);
+    /// ```
+    pub fn load_files_parallel<P: AsRef<Path> + Sync>(
+        &mut self,
+        paths: &[P],
+    ) -> Result<Vec<ProductType>, Error> {
+        let workers = thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+
+        let parsed = parse_parallel(paths, workers)
+            .into_iter()
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        // each file is loaded and preprocessed on its own, like Self::load_file does
+        let mut products = Vec::with_capacity(parsed.len());
+        let mut loaded = Vec::with_capacity(parsed.len());
+
+        for (path, parsed) in paths.iter().zip(parsed.into_iter()) {
+            let mut fresh = self.empty_clone();
+            fresh.preprocessing = Default::default();
+
+            match parsed {
+                Parsed::File(data, digest) => {
+                    products.push(data.product_type());
+                    fresh.load_parsed(path, data, digest)?;
+                }
+                #[cfg(any(feature = "rtcm", feature = "ubx", feature = "binex"))]
+                Parsed::Stream(stream) => {
+                    // Observations prevail
+                    let product = fresh
+                        .load_stream(path, stream)?
+                        .last()
+                        .copied()
+                        .ok_or(Error::NonSupportedFileFormat)?;
+
+                    products.push(product);
+                }
+            }
+
+            fresh.apply_pipeline(&self.preprocessing)?;
+            loaded.push(fresh);
+        }
+
+        // group by destination, preserving input order
+        let mut groups = BTreeMap::<BlobKey, Vec<BlobData>>::new();
+        let mut merged = self.empty_clone();
+
+        for fresh in loaded {
+            for (key, data) in fresh.blob {
+                groups.entry(key).or_default().push(data);
+            }
+
+            for (product, paths) in fresh.files {
+                merged.files.entry(product).or_default().extend(paths);
+            }
+
+            merged.metadata.extend(fresh.metadata);
+        }
+
+        // merge each group concurrently
+        merged.blob = thread::scope(|scope| {
+            let handles = groups
+                .into_iter()
+                .map(|(key, group)| {
+                    scope.spawn(move || {
+                        let mut group = group.into_iter();
+
+                        let mut merged = group.next().expect("internal error: empty group");
+
+                        for data in group {
+                            merged.merge_mut(&data)?;
+                        }
+
+                        Ok::<_, Error>((key, merged))
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("internal error: merging thread panic"))
+                .collect::<Result<HashMap<_, _>, Error>>()
+        })?;
+
+        // then on top of what is already loaded: Self is not modified on failure
+        self.extend_loaded(merged)?;
+
+        Ok(products)
+    }
+}
@@ -0,0 +1,269 @@
+//! Context partitioning
+use itertools::Itertools;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    context::{blob::station_key, BlobData, BlobKey, FileMetadata},
+    error::Error,
+    prelude::{Duration, ProductType, QcContext},
+    scope::{QcScope, QcScopeType},
+};
+
+impl QcContext {
+    /// Returns all [QcScope]s of this [QcScopeType] described by current [QcContext].
+    /// ```
+    /// use gnss_qc::prelude::{QcContext, QcScope, QcScopeType, ProductType};
+    ///
+    /// let mut context = QcContext::new();
+    ///
+    /// context.load_gzip_rinex_file("data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+    ///     .unwrap();
+    ///
+    /// context.load_gzip_rinex_file("data/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     context.scopes(QcScopeType::ProductType),
+    ///     vec![
+    ///         QcScope::ProductType(ProductType::Observation),
+    ///         QcScope::ProductType(ProductType::BroadcastNavigation),
+    ///     ],
+    /// );
+    /// ```
+    pub fn scopes(&self, scope_type: QcScopeType) -> Vec<QcScope> {
+        self.metadata
+            .iter()
+            .filter_map(|(path, meta)| QcScope::from_file(scope_type, path, meta))
+            .sorted()
+            .dedup()
+            .collect()
+    }
+
+    /// Copies files (and their [FileMetadata]) that verify the predicate,
+    /// preserving their loading order. The predicate is evaluated per [ProductType],
+    /// because binary streams are loaded as several products.
+    pub(crate) fn copy_files<F: Fn(ProductType, &Path, &FileMetadata) -> bool>(
+        &mut self,
+        rhs: &Self,
+        predicate: F,
+    ) {
+        for (product, paths) in rhs.files.iter() {
+            let paths = paths
+                .iter()
+                .filter(|path| {
+                    rhs.metadata
+                        .get(*path)
+                        .map(|meta| predicate(*product, path, meta))
+                        .unwrap_or(false)
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+
+            for path in paths.iter() {
+                if let Some(meta) = rhs.metadata.get(path) {
+                    self.metadata.insert(path.clone(), meta.clone());
+                }
+            }
+
+            if !paths.is_empty() {
+                self.files.insert(*product, paths);
+            }
+        }
+    }
+
+    /// Returns the files (and their [FileMetadata]) merged into the blob of this [BlobKey]
+    pub(crate) fn blob_files(&self, key: &BlobKey) -> Vec<(&PathBuf, &FileMetadata)> {
+        self.files
+            .get(&key.product)
+            .map(|paths| {
+                paths
+                    .iter()
+                    .filter_map(|path| {
+                        let meta = self.metadata.get(path)?;
+                        if !key.product.is_station_dependent() || meta.station == key.station {
+                            Some((path, meta))
+                        } else {
+                            None
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Extracts the contribution of these files from this (merged) [BlobData], using their time span,
+    /// which is only feasible when they do not overlap the files that are left out.
+    fn extract_files(
+        data: &BlobData,
+        inside: &[(&PathBuf, &FileMetadata)],
+        outside: &[(&PathBuf, &FileMetadata)],
+    ) -> Option<BlobData> {
+        let spans = |files: &[(&PathBuf, &FileMetadata)]| {
+            files
+                .iter()
+                .map(|(_, meta)| meta.time_span)
+                .collect::<Option<Vec<_>>>()
+        };
+
+        let (inside, outside) = (spans(inside)?, spans(outside)?);
+
+        let overlapping = inside.iter().any(|(start, end)| {
+            outside
+                .iter()
+                .any(|(out_start, out_end)| start <= out_end && out_start <= end)
+        });
+
+        if overlapping {
+            return None;
+        }
+
+        let mut extracted = Option::<BlobData>::None;
+
+        // time windows are [start, end[
+        let end_margin = Duration::from_nanoseconds(1.0);
+
+        for (start, end) in inside.iter() {
+            if let Some(windowed) = data.time_windowed(*start, *end + end_margin) {
+                if let Some(extracted) = &mut extracted {
+                    extracted.merge_mut(&windowed).ok()?;
+                } else {
+                    extracted = Some(windowed);
+                }
+            }
+        }
+
+        extracted
+    }
+
+    /// Returns the [BlobData] of this [BlobKey], restricted to the contribution of the files
+    /// that verify the predicate, or None when none of them do.
+    /// The contribution is extracted from the data currently loaded (including any preprocessing)
+    /// using the file time spans. When that is not feasible (overlapping time spans),
+    /// the files are reloaded and the recorded [QcPipeline](crate::prelude::QcPipeline)
+    /// is applied again: this requires them to still be readable.
+    pub(crate) fn restricted_blob<F: Fn(&Path, &FileMetadata) -> bool>(
+        &self,
+        key: &BlobKey,
+        predicate: F,
+    ) -> Result<Option<BlobData>, Error> {
+        let data = match self.blob.get(key) {
+            Some(data) => data,
+            None => return Ok(None),
+        };
+
+        let (inside, outside): (Vec<_>, Vec<_>) = self
+            .blob_files(key)
+            .into_iter()
+            .partition(|(path, meta)| predicate(path, meta));
+
+        if inside.is_empty() {
+            return Ok(None);
+        }
+
+        if outside.is_empty() {
+            return Ok(Some(data.clone()));
+        }
+
+        if let Some(extracted) = Self::extract_files(data, &inside, &outside) {
+            return Ok(Some(extracted));
+        }
+
+        let mut reloaded = self.empty_clone();
+        reloaded.preprocessing = Default::default();
+
+        for (path, _) in inside.iter() {
+            reloaded.load_file(path)?;
+        }
+
+        reloaded.apply_pipeline(&self.preprocessing)?;
+
+        Ok(reloaded.blob.remove(key))
+    }
+
+    /// Builds a new [QcContext] restricted to this [QcScope].
+    ///
+    /// The scoped [QcContext] is obtained from the data currently loaded,
+    /// including any previous preprocessing, so it works for data loaded from memory or streams:
+    /// - [QcScope::ProductType] and [QcScope::Station] select the matching blobs.
+    ///   [QcScope::Station] preserves the products shared by all stations
+    ///   (like navigation, clock or orbit products).
+    /// - other scopes select the blobs made of files within the scope. When files within
+    ///   and out of the scope were merged together, the contribution of the first is extracted
+    ///   using their time span. When that is not feasible (overlapping time spans), the files
+    ///   are reloaded and the recorded [QcPipeline](crate::prelude::QcPipeline) is applied again:
+    ///   this requires the files to still be readable.
+    pub fn scoped(&self, scope: &QcScope) -> Result<Self, Error> {
+        let mut ctx = self.empty_clone();
+
+        match scope {
+            QcScope::ProductType(product) => {
+                for (key, data) in self.blob.iter() {
+                    if key.product == *product {
+                        ctx.blob.insert(key.clone(), data.clone());
+                    }
+                }
+                ctx.copy_files(self, |file_product, _, _| file_product == *product);
+            }
+            QcScope::Station(station) => {
+                let station = station_key(station);
+
+                for (key, data) in self.blob.iter() {
+                    if key.station.is_none() || key.station.as_ref() == Some(&station) {
+                        ctx.blob.insert(key.clone(), data.clone());
+                    }
+                }
+                ctx.copy_files(self, |file_product, _, meta| {
+                    !file_product.is_station_dependent() || meta.station.as_ref() == Some(&station)
+                });
+            }
+            _ => {
+                for key in self.blob.keys().sorted() {
+                    if let Some(data) =
+                        self.restricted_blob(key, |path, meta| scope.contains(path, meta))?
+                    {
+                        ctx.blob.insert(key.clone(), data);
+                    }
+                }
+
+                ctx.copy_files(self, |_, path, meta| scope.contains(path, meta));
+            }
+        }
+
+        Ok(ctx)
+    }
+
+    /// Partitions current [QcContext] by [QcScopeType]:
+    /// returns one [QcContext] per [QcScope] (see [Self::scoped]).
+    /// This is typically used to compare how products from different providers
+    /// behave, by generating one report per scope.
+    /// ```
+    /// use gnss_qc::prelude::{QcContext, QcScope, QcScopeType};
+    ///
+    /// let mut context = QcContext::new();
+    ///
+    /// context.load_rinex_file("data/OBS/V2/AJAC3550.21O")
+    ///     .unwrap();
+    ///
+    /// context.load_gzip_rinex_file("data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+    ///     .unwrap();
+    ///
+    /// // one context per original file
+    /// let partitions = context.partition(QcScopeType::FileName)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(partitions.len(), 2);
+    ///
+    /// for (scope, ctx) in partitions {
+    ///     assert!(ctx.has_observation());
+    /// }
+    /// ```
+    pub fn partition(&self, scope_type: QcScopeType) -> Result<Vec<(QcScope, Self)>, Error> {
+        self.scopes(scope_type)
+            .into_iter()
+            .map(|scope| {
+                let ctx = self.scoped(&scope)?;
+                Ok((scope, ctx))
+            })
+            .collect()
+    }
+}
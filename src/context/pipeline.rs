@@ -0,0 +1,95 @@
+//! Preprocessing pipelines
+use std::path::Path;
+
+use crate::{
+    context::BlobData,
+    error::Error,
+    pipeline::{QcPipeline, QcPipelineOperation},
+    prelude::{QcContext, Rinex},
+};
+
+impl QcContext {
+    /// Applies this [QcPipeline] to current [QcContext]: all steps are applied in order.
+    /// The pipeline is validated first, so an invalid step does not modify the [QcContext].
+    /// Applied steps are recorded (see [Self::preprocessing]) and described by the report.
+    /// ```
+    /// use gnss_qc::prelude::{QcContext, QcPipeline, QcPipelineStep};
+    ///
+    /// let mut context = QcContext::new();
+    ///
+    /// context.load_rinex_file("data/OBS/V2/AJAC3550.21O")
+    ///     .unwrap();
+    ///
+    /// let pipeline = QcPipeline::default()
+    ///     .with_step(QcPipelineStep::Filter("GPS".to_string()))
+    ///     .with_step(QcPipelineStep::Repair("zero".to_string()));
+    ///
+    /// context.apply_pipeline(&pipeline)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(context.preprocessing(), &pipeline);
+    /// ```
+    pub fn apply_pipeline(&mut self, pipeline: &QcPipeline) -> Result<(), Error> {
+        let operations = pipeline
+            .steps
+            .iter()
+            .map(|step| step.operation())
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        for (step, operation) in pipeline.steps.iter().zip(operations.iter()) {
+            match operation {
+                QcPipelineOperation::Filter(filter) => self.filter_mut(filter),
+                QcPipelineOperation::Repair(repair) => {
+                    self.repair_mut(*repair);
+                }
+                #[cfg(feature = "navigation")]
+                QcPipelineOperation::NavFilter(filter) => self.nav_filter_mut(filter),
+            }
+
+            self.preprocessing.steps.push(step.clone());
+        }
+
+        Ok(())
+    }
+
+    /// Returns the [QcPipeline] steps applied to this [QcContext] so far,
+    /// with [Self::apply_pipeline]. Data loaded afterwards is preprocessed
+    /// with the same steps, so the whole [QcContext] is consistent.
+    pub fn preprocessing(&self) -> &QcPipeline {
+        &self.preprocessing
+    }
+
+    /// Loads this [BlobData] into a new [QcContext] and applies the recorded [QcPipeline].
+    fn preprocessed(&self, path: &Path, blob: BlobData) -> Result<Self, Error> {
+        let mut fresh = self.empty_clone();
+        fresh.preprocessing = Default::default();
+
+        fresh.load_blob(path, blob)?;
+        fresh.apply_pipeline(&self.preprocessing)?;
+
+        Ok(fresh)
+    }
+
+    /// Loads this [BlobData], preprocessed with the recorded [QcPipeline] first.
+    pub(crate) fn load_preprocessed(&mut self, path: &Path, blob: BlobData) -> Result<(), Error> {
+        let fresh = self.preprocessed(path, blob)?;
+        self.extend_loaded(fresh)
+    }
+
+    /// Preprocesses this [Rinex] chunk with the recorded [QcPipeline],
+    /// before it is merged into the data already loaded.
+    pub(crate) fn preprocessed_rinex(&self, path: &Path, rinex: Rinex) -> Result<Rinex, Error> {
+        if self.preprocessing.steps.is_empty() {
+            return Ok(rinex);
+        }
+
+        self.preprocessed(path, BlobData::RINEX(rinex))?
+            .blob
+            .into_values()
+            .find_map(|blob| match blob {
+                BlobData::RINEX(rinex) => Some(rinex),
+                _ => None,
+            })
+            .ok_or(Error::ProductTypeMismatch)
+    }
+}
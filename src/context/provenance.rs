@@ -0,0 +1,85 @@
+//! Files provenance
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::{
+    error::Error,
+    prelude::{Epoch, ProductType, QcContext},
+};
+
+/// [QcFileProvenance] describes exactly which file was loaded
+/// into a [QcContext], for audit purposes.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QcFileProvenance {
+    /// File path, as loaded
+    pub path: PathBuf,
+    /// [ProductType] of this file
+    pub product: ProductType,
+    /// Station (marker) name, for station dependent products
+    pub station: Option<String>,
+    /// SHA-256 of the file content (as stored, possibly compressed),
+    /// computed while parsing. None when the data was not read from
+    /// the file by this library, for example when it was parsed externally.
+    pub sha256: Option<String>,
+    /// File size in bytes
+    pub size: Option<u64>,
+    /// Instant this file was loaded
+    pub loaded_at: Option<Epoch>,
+    /// Header summary, as detected
+    pub header: String,
+}
+
+impl QcContext {
+    /// Returns the [QcFileProvenance] of each file loaded in this [QcContext],
+    /// sorted by [ProductType], in loading order.
+    /// ```
+    /// use gnss_qc::prelude::QcContext;
+    ///
+    /// let mut context = QcContext::new();
+    ///
+    /// context.load_file("data/OBS/V2/AJAC3550.21O")
+    ///     .unwrap();
+    ///
+    /// let provenance = context.provenance();
+    /// assert_eq!(provenance.len(), 1);
+    ///
+    /// let sha256 = provenance[0].sha256.as_ref().unwrap();
+    /// assert_eq!(sha256.len(), 64);
+    /// ```
+    pub fn provenance(&self) -> Vec<QcFileProvenance> {
+        let mut provenance = Vec::new();
+
+        let mut products = self.files.keys().copied().collect::<Vec<_>>();
+        products.sort();
+
+        for product in products {
+            for path in self.files[&product].iter() {
+                if let Some(meta) = self.metadata.get(path) {
+                    provenance.push(QcFileProvenance {
+                        path: path.clone(),
+                        // binary streams are loaded as several products
+                        product,
+                        station: if product.is_station_dependent() {
+                            meta.station.clone()
+                        } else {
+                            None
+                        },
+                        sha256: meta.sha256.clone(),
+                        size: meta.size,
+                        loaded_at: meta.loaded_at,
+                        header: meta.header.clone(),
+                    });
+                }
+            }
+        }
+
+        provenance
+    }
+
+    /// Exports the provenance manifest (see [Self::provenance]) as JSON.
+    pub fn provenance_json(&self) -> Result<String, Error> {
+        let json = serde_json::to_string_pretty(&self.provenance())?;
+        Ok(json)
+    }
+}
@@ -0,0 +1,501 @@
+//! Incremental (real-time) context updates
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    path::Path,
+};
+
+use qc_traits::{Filter, FilterItem, MaskOperand, Merge};
+
+use rinex::{
+    observation::{ObsKey, Observations},
+    prelude::Observable,
+    record::Record,
+};
+
+use crate::{
+    context::{blob::station_name, BlobData, BlobKey},
+    error::Error,
+    prelude::{Constellation, Duration, Epoch, ProductType, QcContext, Rinex, SV},
+};
+
+impl QcContext {
+    /// Appends this [Rinex] chunk (new epochs of a file being written,
+    /// or a decoded stream) to current [QcContext], without reloading anything.
+    /// The first chunk of a given path is loaded like [Self::load_rinex],
+    /// following chunks are merged into the same product and the path is only registered once.
+    /// The time span of the file is extended. Chunks are not read from the file itself:
+    /// its checksum and size no longer describe the content and are cleared.
+    /// Chunks are preprocessed with the recorded [QcPipeline](crate::prelude::QcPipeline)
+    /// (see [Self::preprocessing]).
+    pub fn append_rinex<P: AsRef<Path>>(&mut self, path: P, chunk: Rinex) -> Result<(), Error> {
+        let path_buf = path.as_ref().to_path_buf();
+
+        if !self.metadata.contains_key(&path_buf) {
+            return self.load_rinex(path, chunk);
+        }
+
+        let span = chunk.first_epoch().zip(chunk.last_epoch());
+
+        let chunk = self.preprocessed_rinex(&path_buf, chunk)?;
+
+        let product = ProductType::from(chunk.header.rinex_type);
+
+        let key = BlobKey {
+            product,
+            station: if product.is_station_dependent() {
+                station_name(&chunk, &path_buf)
+            } else {
+                None
+            },
+        };
+
+        match self.blob.get_mut(&key) {
+            Some(inner) => {
+                inner
+                    .as_mut_rinex()
+                    .ok_or(Error::ProductTypeMismatch)?
+                    .merge_mut(&chunk)?;
+            }
+            None => {
+                self.blob.insert(key, BlobData::RINEX(chunk));
+            }
+        }
+
+        if let Some(meta) = self.metadata.get_mut(&path_buf) {
+            meta.sha256 = None;
+            meta.size = None;
+
+            meta.time_span = match (meta.time_span, span) {
+                (Some((first, last)), Some((t0, t1))) => Some((first.min(t0), last.max(t1))),
+                (time_span, span) => time_span.or(span),
+            };
+        }
+
+        let paths = self.files.entry(product).or_default();
+
+        if !paths.contains(&path_buf) {
+            paths.push(path_buf);
+        }
+
+        Ok(())
+    }
+
+    /// Appends one epoch of observations to the Observation RINEX of this station,
+    /// which must already be loaded (for example, from a first [Self::append_rinex] chunk).
+    /// Observables that were not described yet are added to the header.
+    /// Like any chunk, the epoch is preprocessed with the recorded
+    /// [QcPipeline](crate::prelude::QcPipeline) (see [Self::preprocessing]).
+    pub fn append_observation(
+        &mut self,
+        station: &str,
+        key: ObsKey,
+        observations: Observations,
+    ) -> Result<(), Error> {
+        let header = &self
+            .observation_of(station)
+            .ok_or(Error::UnknownStation(station.to_string()))?
+            .header;
+
+        let epochs = if self.preprocessing.steps.is_empty() {
+            vec![(key, observations)]
+        } else {
+            // single epoch chunk, described by the station header
+            let chunk = Rinex::new(
+                header.clone(),
+                Record::ObsRecord([(key, observations)].into_iter().collect()),
+            );
+
+            match self.preprocessed_rinex(Path::new(station), chunk)?.record {
+                Record::ObsRecord(record) => record.into_iter().collect(),
+                _ => return Err(Error::ProductTypeMismatch),
+            }
+        };
+
+        let rinex = self
+            .observation_of_mut(station)
+            .ok_or(Error::UnknownStation(station.to_string()))?;
+
+        for (key, observations) in epochs {
+            if let Some(obs) = &mut rinex.header.obs {
+                for signal in observations.signals.iter() {
+                    let codes = obs.codes.entry(signal.sv.constellation).or_default();
+
+                    if !codes.contains(&signal.observable) {
+                        codes.push(signal.observable.clone());
+                    }
+                }
+            }
+
+            let record = rinex
+                .record
+                .as_mut_obs()
+                .ok_or(Error::ProductTypeMismatch)?;
+
+            record.insert(key, observations);
+        }
+
+        Ok(())
+    }
+
+    /// Discards all time dependent data older than this [Epoch].
+    /// This keeps a long running [QcContext] bounded in memory,
+    /// typically with `t = latest epoch - window duration`.
+    pub fn discard_before_mut(&mut self, t: Epoch) {
+        self.filter_mut(&Filter::mask(
+            MaskOperand::GreaterEquals,
+            FilterItem::EpochItem(t),
+        ));
+    }
+
+    /// Creates a [QcSlidingWindow] of this duration, initialized with the
+    /// most recent observations of the primary station.
+    pub fn sliding_window(&self, duration: Duration) -> QcSlidingWindow {
+        let mut window = QcSlidingWindow::new(duration);
+
+        if let Some(rinex) = self.observation() {
+            window.push_rinex(rinex);
+        }
+
+        window
+    }
+}
+
+/// Content of one epoch, as tracked by the [QcSlidingWindow]
+#[derive(Debug, Clone)]
+struct WindowEpoch {
+    epoch: Epoch,
+    /// Epoch flag is not Ok
+    abnormal: bool,
+    /// Satellites in sight
+    satellites: Vec<SV>,
+    /// C/N0 [dB.Hz] observations
+    cn0: Vec<(Constellation, f64)>,
+}
+
+/// [QcSlidingWindow] maintains observation statistics over the most recent
+/// time period, as new epochs arrive. Statistics are updated incrementally:
+/// each push only accounts for the new epoch and the epochs that left the window,
+/// and the summary does not iterate the epochs.
+/// ```
+/// use gnss_qc::prelude::{Duration, QcContext, Rinex};
+///
+/// let mut context = QcContext::new();
+///
+/// let rinex = Rinex::from_gzip_file("data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+///     .unwrap();
+///
+/// // last 60' of observations
+/// let mut window = context.sliding_window(Duration::from_hours(1.0));
+///
+/// context.append_rinex("ESBC00DNK.crx", rinex.clone())
+///     .unwrap();
+///
+/// window.push_rinex(&rinex);
+///
+/// let summary = window.summary();
+///
+/// // 30s sampling
+/// assert_eq!(summary.epochs, 120);
+/// assert_eq!(summary.sampling_interval, Some(Duration::from_seconds(30.0)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct QcSlidingWindow {
+    /// Window duration
+    duration: Duration,
+    /// Epochs in the window, in chronological order
+    epochs: VecDeque<WindowEpoch>,
+    /// Number of occurrences of each interval between consecutive epochs
+    intervals: BTreeMap<Duration, usize>,
+    /// Number of abnormal epochs in the window
+    abnormal: usize,
+    /// Number of epochs each satellite is seen in the window
+    satellites: HashMap<SV, usize>,
+    /// C/N0 sum and number of samples per constellation
+    cn0: HashMap<Constellation, (f64, usize)>,
+}
+
+/// [QcWindowSummary] describes the observations of a [QcSlidingWindow]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QcWindowSummary {
+    /// First epoch in the window
+    pub first_epoch: Option<Epoch>,
+    /// Last epoch in the window
+    pub last_epoch: Option<Epoch>,
+    /// Number of epochs in the window
+    pub epochs: usize,
+    /// Number of epochs that were not flagged Ok
+    pub abnormal_epochs: usize,
+    /// Dominant sampling interval
+    pub sampling_interval: Option<Duration>,
+    /// Number of data gaps (larger than the dominant sampling interval)
+    pub gaps: usize,
+    /// Number of epochs each satellite was seen
+    pub satellites: BTreeMap<SV, usize>,
+    /// Average C/N0 [dB.Hz] per constellation
+    pub cn0_dbhz: BTreeMap<Constellation, f64>,
+}
+
+impl QcSlidingWindow {
+    /// Creates a new (empty) [QcSlidingWindow] of this duration
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            epochs: Default::default(),
+            intervals: Default::default(),
+            abnormal: 0,
+            satellites: Default::default(),
+            cn0: Default::default(),
+        }
+    }
+
+    /// Returns the duration of this [QcSlidingWindow]
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Accounts for this new epoch of observations. Epochs should be pushed in
+    /// chronological order: epochs already in the window are ignored,
+    /// and epochs older than the window are discarded.
+    pub fn push(&mut self, key: &ObsKey, observations: &Observations) {
+        if let Some(last) = self.epochs.back() {
+            if key.epoch <= last.epoch - self.duration {
+                return;
+            }
+        }
+
+        // chronological pushes are inserted at the back
+        let position = match self
+            .epochs
+            .binary_search_by(|entry| entry.epoch.cmp(&key.epoch))
+        {
+            Ok(_) => return,
+            Err(position) => position,
+        };
+
+        let mut satellites = observations
+            .signals
+            .iter()
+            .map(|signal| signal.sv)
+            .collect::<Vec<_>>();
+
+        satellites.sort();
+        satellites.dedup();
+
+        let cn0 = observations
+            .signals
+            .iter()
+            .filter(|signal| matches!(signal.observable, Observable::SSI(_)))
+            .map(|signal| (signal.sv.constellation, signal.value))
+            .collect::<Vec<_>>();
+
+        let entry = WindowEpoch {
+            epoch: key.epoch,
+            abnormal: !key.flag.is_ok(),
+            satellites,
+            cn0,
+        };
+
+        if entry.abnormal {
+            self.abnormal += 1;
+        }
+
+        for sv in entry.satellites.iter() {
+            *self.satellites.entry(*sv).or_default() += 1;
+        }
+
+        for (constellation, value) in entry.cn0.iter() {
+            let (sum, count) = self.cn0.entry(*constellation).or_default();
+            *sum += value;
+            *count += 1;
+        }
+
+        let prev = position.checked_sub(1).map(|pos| self.epochs[pos].epoch);
+        let next = self.epochs.get(position).map(|next| next.epoch);
+
+        if let (Some(prev), Some(next)) = (prev, next) {
+            self.remove_interval(next - prev);
+        }
+
+        if let Some(prev) = prev {
+            self.add_interval(entry.epoch - prev);
+        }
+
+        if let Some(next) = next {
+            self.add_interval(next - entry.epoch);
+        }
+
+        self.epochs.insert(position, entry);
+
+        self.evict();
+    }
+
+    fn add_interval(&mut self, dt: Duration) {
+        *self.intervals.entry(dt).or_default() += 1;
+    }
+
+    fn remove_interval(&mut self, dt: Duration) {
+        if let Some(count) = self.intervals.get_mut(&dt) {
+            *count -= 1;
+            if *count == 0 {
+                self.intervals.remove(&dt);
+            }
+        }
+    }
+
+    /// Accounts for all observations of this [Rinex] chunk
+    pub fn push_rinex(&mut self, rinex: &Rinex) {
+        if let Some(record) = rinex.record.as_obs() {
+            for (key, observations) in record.iter() {
+                self.push(key, observations);
+            }
+        }
+    }
+
+    /// Removes the epochs that left the window
+    fn evict(&mut self) {
+        let last = match self.epochs.back() {
+            Some(last) => last.epoch,
+            None => return,
+        };
+
+        while let Some(first) = self.epochs.front() {
+            if first.epoch > last - self.duration {
+                break;
+            }
+
+            let entry = self.epochs.pop_front().unwrap();
+
+            if let Some(next) = self.epochs.front() {
+                self.remove_interval(next.epoch - entry.epoch);
+            }
+
+            if entry.abnormal {
+                self.abnormal -= 1;
+            }
+
+            for sv in entry.satellites.iter() {
+                if let Some(count) = self.satellites.get_mut(sv) {
+                    *count -= 1;
+                    if *count == 0 {
+                        self.satellites.remove(sv);
+                    }
+                }
+            }
+
+            for (constellation, value) in entry.cn0.iter() {
+                if let Some((sum, count)) = self.cn0.get_mut(constellation) {
+                    *sum -= value;
+                    *count -= 1;
+                    if *count == 0 {
+                        self.cn0.remove(constellation);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the [QcWindowSummary] of the current window
+    pub fn summary(&self) -> QcWindowSummary {
+        let sampling_interval = self
+            .intervals
+            .iter()
+            .max_by(|(dt_a, n_a), (dt_b, n_b)| n_a.cmp(n_b).then(dt_b.cmp(dt_a)))
+            .map(|(dt, _)| *dt);
+
+        let gaps = match sampling_interval {
+            Some(sampling) => self
+                .intervals
+                .range(sampling..)
+                .skip(1)
+                .map(|(_, n)| *n)
+                .sum(),
+            None => 0,
+        };
+
+        QcWindowSummary {
+            first_epoch: self.epochs.front().map(|e| e.epoch),
+            last_epoch: self.epochs.back().map(|e| e.epoch),
+            epochs: self.epochs.len(),
+            abnormal_epochs: self.abnormal,
+            sampling_interval,
+            gaps,
+            satellites: self
+                .satellites
+                .iter()
+                .map(|(sv, count)| (*sv, *count))
+                .collect(),
+            cn0_dbhz: self
+                .cn0
+                .iter()
+                .map(|(constellation, (sum, count))| (*constellation, sum / *count as f64))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rinex::observation::{EpochFlag, SignalObservation};
+    use std::str::FromStr;
+
+    fn observations(sv: &str, cn0: f64) -> Observations {
+        Observations {
+            clock: None,
+            signals: vec![SignalObservation {
+                sv: SV::from_str(sv).unwrap(),
+                observable: Observable::from_str("S1C").unwrap(),
+                value: cn0,
+                lli: None,
+                snr: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn sliding_window() {
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+
+        let mut window = QcSlidingWindow::new(Duration::from_seconds(300.0));
+
+        for i in 0..20 {
+            // one data gap
+            if i == 12 {
+                continue;
+            }
+
+            let key = ObsKey {
+                epoch: t0 + Duration::from_seconds(30.0 * i as f64),
+                flag: EpochFlag::Ok,
+            };
+
+            if i < 10 {
+                window.push(&key, &observations("G01", 40.0 + i as f64));
+            } else {
+                window.push(&key, &observations("E05", 45.0));
+            }
+        }
+
+        let summary = window.summary();
+
+        // (t0 + 270s, t0 + 570s]
+        assert_eq!(summary.epochs, 9);
+        assert_eq!(
+            summary.first_epoch,
+            Some(t0 + Duration::from_seconds(300.0))
+        );
+        assert_eq!(summary.last_epoch, Some(t0 + Duration::from_seconds(570.0)));
+        assert_eq!(
+            summary.sampling_interval,
+            Some(Duration::from_seconds(30.0))
+        );
+        assert_eq!(summary.gaps, 1);
+        assert_eq!(summary.abnormal_epochs, 0);
+
+        // G01 left the window
+        assert_eq!(summary.satellites.len(), 1);
+        assert_eq!(summary.satellites[&SV::from_str("E05").unwrap()], 9);
+        assert_eq!(summary.cn0_dbhz.len(), 1);
+        assert_eq!(summary.cn0_dbhz[&Constellation::Galileo], 45.0);
+    }
+}
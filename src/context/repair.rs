@@ -0,0 +1,280 @@
+//! Context repair
+use std::collections::BTreeMap;
+
+use qc_traits::{Repair, RepairTrait};
+
+use rinex::{
+    navigation::{NavFrame, NavKey, OrbitItem},
+    prelude::Observable,
+};
+
+use crate::{
+    context::BlobData,
+    prelude::{Constellation, ProductType, QcContext, Rinex},
+};
+
+#[cfg(feature = "sp3")]
+use crate::prelude::SP3;
+
+/// SP3 placeholder, for missing or bad position [km] and clock [us] data
+#[cfg(feature = "sp3")]
+const SP3_PLACEHOLDER: f64 = 999999.0;
+
+/// [QcRepairSummary] describes what [QcContext::repair_mut] removed,
+/// per [ProductType]: signal observations (Observation), meteo observations (Meteo),
+/// clock profiles (Clock), orbit samples (SP3) or ephemerides (Navigation).
+/// SP3 samples with a bad clock are preserved, only their clock is invalidated.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QcRepairSummary {
+    /// Number of items removed per [ProductType]
+    pub removed: BTreeMap<ProductType, usize>,
+    /// Number of items invalidated (but preserved) per [ProductType]
+    pub invalidated: BTreeMap<ProductType, usize>,
+}
+
+impl QcRepairSummary {
+    /// Returns number of items removed from this [ProductType]
+    pub fn removed(&self, product: ProductType) -> usize {
+        self.removed.get(&product).copied().unwrap_or(0)
+    }
+
+    /// Returns number of items of this [ProductType] that were invalidated
+    pub fn invalidated(&self, product: ProductType) -> usize {
+        self.invalidated.get(&product).copied().unwrap_or(0)
+    }
+
+    /// Returns total number of items removed
+    pub fn total(&self) -> usize {
+        self.removed.values().sum()
+    }
+
+    fn account(&mut self, product: ProductType, removed: usize, invalidated: usize) {
+        *self.removed.entry(product).or_default() += removed;
+
+        if invalidated > 0 {
+            *self.invalidated.entry(product).or_default() += invalidated;
+        }
+    }
+}
+
+/// Returns true if this meteo observation is physically possible
+fn valid_meteo_observation(observable: &Observable, value: f64) -> bool {
+    if !value.is_finite() {
+        return false;
+    }
+
+    match observable {
+        // [hPa]
+        Observable::Pressure => value > 0.0 && value <= 1200.0,
+        // [°C]
+        Observable::Temperature => (-100.0..=70.0).contains(&value),
+        // [%]
+        Observable::HumidityRate => (0.0..=100.0).contains(&value),
+        // [°]
+        Observable::WindDirection => (0.0..=360.0).contains(&value),
+        // [m/s], [mm] and rain/hail indicators
+        _ => value >= 0.0,
+    }
+}
+
+/// [Repair::Zero]: removes physically impossible meteo observations
+fn repair_meteo(rinex: &mut Rinex, r: Repair) -> usize {
+    let record = match rinex.record.as_mut_meteo() {
+        Some(record) => record,
+        None => return 0,
+    };
+
+    let size = record.len();
+
+    match r {
+        Repair::Zero => {
+            record.retain(|k, value| valid_meteo_observation(&k.observable, *value));
+        }
+    }
+
+    size - record.len()
+}
+
+/// [Repair::Zero]: removes zero or NaN clock biases
+fn repair_clock(rinex: &mut Rinex, r: Repair) -> usize {
+    let record = match rinex.record.as_mut_clock() {
+        Some(record) => record,
+        None => return 0,
+    };
+
+    let mut removed = 0;
+
+    for profiles in record.values_mut() {
+        let size = profiles.len();
+
+        match r {
+            Repair::Zero => {
+                profiles.retain(|_, profile| profile.bias.is_finite() && profile.bias != 0.0);
+            }
+        }
+
+        removed += size - profiles.len();
+    }
+
+    record.retain(|_, profiles| !profiles.is_empty());
+    removed
+}
+
+/// Returns true if this navigation frame is not a malformed ephemeris:
+/// invalid clock or orbital parameters
+fn valid_ephemeris(k: &NavKey, frame: &NavFrame) -> bool {
+    let eph = match frame.as_ephemeris() {
+        Some(eph) => eph,
+        None => return true,
+    };
+
+    let clock_ok = [eph.clock_bias, eph.clock_drift, eph.clock_drift_rate]
+        .iter()
+        .all(|value| value.is_finite());
+
+    let orbits_ok = eph
+        .orbits
+        .values()
+        .all(|item| !matches!(item, OrbitItem::F64(value) if !value.is_finite()));
+
+    // GLONASS and SBAS broadcast state vectors, others broadcast Keplerian parameters
+    let keplerian_ok =
+        if k.sv.constellation == Constellation::Glonass || k.sv.constellation.is_sbas() {
+            true
+        } else {
+            let sqrt_a = eph.get_orbit_f64("sqrta").unwrap_or(0.0);
+            let e = eph.get_orbit_f64("e").unwrap_or(-1.0);
+            sqrt_a > 0.0 && (0.0..1.0).contains(&e)
+        };
+
+    clock_ok && orbits_ok && keplerian_ok
+}
+
+/// [Repair::Zero]: drops malformed ephemerides
+fn repair_navigation(rinex: &mut Rinex, r: Repair) -> usize {
+    let record = match rinex.record.as_mut_nav() {
+        Some(record) => record,
+        None => return 0,
+    };
+
+    let size = record.len();
+
+    match r {
+        Repair::Zero => record.retain(|k, frame| valid_ephemeris(k, frame)),
+    }
+
+    size - record.len()
+}
+
+/// [Repair::Zero]: removes null positions and 999999 placeholders, and invalidates bad clocks.
+/// Returns the number of samples removed and the number of clocks invalidated.
+#[cfg(feature = "sp3")]
+fn repair_sp3(sp3: &mut SP3, r: Repair) -> (usize, usize) {
+    let size = sp3.data.len();
+    let mut invalidated = 0;
+
+    match r {
+        Repair::Zero => {
+            sp3.data.retain(|_, entry| {
+                let (x, y, z) = entry.position_km;
+                [x, y, z].iter().all(|value| value.is_finite())
+                    && (x, y, z) != (0.0, 0.0, 0.0)
+                    && [x, y, z].iter().all(|value| value.abs() < SP3_PLACEHOLDER)
+            });
+
+            for entry in sp3.data.values_mut() {
+                if let Some(clock_us) = entry.clock_us {
+                    if !clock_us.is_finite() || clock_us.abs() >= SP3_PLACEHOLDER {
+                        entry.clock_us = None;
+                        entry.clock_drift_ns = None;
+                        invalidated += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    (size - sp3.data.len(), invalidated)
+}
+
+impl QcContext {
+    /// Fix given [Repair] condition on all products, and returns
+    /// a [QcRepairSummary] of what was removed or invalidated.
+    /// Observation RINEX are repaired with this [Repair] condition, and with [Repair::Zero]:
+    /// - physically impossible Meteo observations are removed
+    /// - zero or NaN Clock RINEX biases are removed
+    /// - SP3 null positions and 999999 placeholders are removed, bad clocks are invalidated
+    /// - malformed broadcast ephemerides are dropped
+    /// ```
+    /// use gnss_qc::prelude::{QcContext, ProductType, Repair};
+    ///
+    /// let mut context = QcContext::new();
+    ///
+    /// context.load_rinex_file("data/OBS/V2/AJAC3550.21O")
+    ///     .unwrap();
+    ///
+    /// let summary = context.repair_mut(Repair::Zero);
+    ///
+    /// assert_eq!(summary.total(), summary.removed(ProductType::Observation));
+    /// ```
+    pub fn repair_mut(&mut self, r: Repair) -> QcRepairSummary {
+        let mut summary = QcRepairSummary::default();
+
+        self.invalidate_orbits();
+
+        for (key, data) in self.blob.iter_mut() {
+            let (removed, invalidated) = match (key.product, data) {
+                (ProductType::Observation, BlobData::RINEX(rinex)) => {
+                    let size = rinex.signal_observations_iter().count();
+                    rinex.repair_mut(r);
+                    (size - rinex.signal_observations_iter().count(), 0)
+                }
+                (ProductType::MeteoObservation, BlobData::RINEX(rinex)) => {
+                    (repair_meteo(rinex, r), 0)
+                }
+                (ProductType::HighPrecisionClock, BlobData::RINEX(rinex)) => {
+                    (repair_clock(rinex, r), 0)
+                }
+                (ProductType::BroadcastNavigation, BlobData::RINEX(rinex)) => {
+                    (repair_navigation(rinex, r), 0)
+                }
+                #[cfg(feature = "sp3")]
+                (ProductType::HighPrecisionOrbit, BlobData::SP3(sp3)) => repair_sp3(sp3, r),
+                _ => continue,
+            };
+
+            summary.account(key.product, removed, invalidated);
+        }
+
+        summary
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::valid_meteo_observation;
+    use rinex::prelude::Observable;
+
+    #[test]
+    fn meteo_physical_bounds() {
+        for (observable, value, valid) in [
+            (Observable::Pressure, 1013.25, true),
+            (Observable::Pressure, 0.0, false),
+            (Observable::Pressure, 9999.9, false),
+            (Observable::Temperature, -20.0, true),
+            (Observable::Temperature, 999.9, false),
+            (Observable::HumidityRate, 45.0, true),
+            (Observable::HumidityRate, 120.0, false),
+            (Observable::WindSpeed, -1.0, false),
+            (Observable::ZenithTotalDelay, f64::NAN, false),
+        ] {
+            assert_eq!(
+                valid_meteo_observation(&observable, value),
+                valid,
+                "{} {}",
+                observable,
+                value
+            );
+        }
+    }
+}
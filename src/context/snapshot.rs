@@ -0,0 +1,222 @@
+//! QcContext snapshots
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use bincode::{DefaultOptions, Options};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    context::{BlobData, BlobKey, FileMetadata},
+    error::Error,
+    prelude::{ProductType, QcContext, QcPipeline},
+};
+
+#[cfg(feature = "navigation")]
+use crate::prelude::{Almanac, Frame};
+
+/// Snapshot file identifier
+const SNAPSHOT_MAGIC: [u8; 4] = *b"GQCS";
+
+/// Snapshot layout revision. Increment on any modification of the snapshot content,
+/// including the [FileMetadata] fields and the [BlobData] variants.
+pub(crate) const SNAPSHOT_REVISION: u32 = 7;
+
+/// Maximal size of the [SnapshotHeader], in bytes
+const SNAPSHOT_HEADER_LIMIT: u64 = 128;
+
+/// Snapshot encoding: fixed size integers. Decoding is always limited in size,
+/// so variable length fields of invalid files cannot trigger huge allocations.
+fn codec() -> impl Options {
+    DefaultOptions::new()
+        .with_fixint_encoding()
+        .allow_trailing_bytes()
+}
+
+/// Snapshot header, decoded first so stale snapshots are rejected
+/// before decoding their content.
+#[derive(Serialize, Deserialize)]
+struct SnapshotHeader {
+    magic: [u8; 4],
+    revision: u32,
+    version: String,
+}
+
+impl SnapshotHeader {
+    fn current() -> Self {
+        Self {
+            magic: SNAPSHOT_MAGIC,
+            revision: SNAPSHOT_REVISION,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if self.magic != SNAPSHOT_MAGIC {
+            return Err(Error::NotASnapshot);
+        }
+
+        // data layout depends on the exact library versions
+        if self.revision != SNAPSHOT_REVISION || self.version != env!("CARGO_PKG_VERSION") {
+            return Err(Error::OutdatedSnapshot(self.revision, self.version.clone()));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct SnapshotRef<'a> {
+    files: &'a HashMap<ProductType, Vec<PathBuf>>,
+    metadata: &'a HashMap<PathBuf, FileMetadata>,
+    blob: &'a HashMap<BlobKey, BlobData>,
+    preprocessing: &'a QcPipeline,
+    /// ECEF frame (ephemeris ID, orientation ID),
+    /// always present so the layout does not depend on the navigation feature
+    frame: Option<(i32, i32)>,
+}
+
+#[derive(Deserialize)]
+struct Snapshot {
+    files: HashMap<ProductType, Vec<PathBuf>>,
+    metadata: HashMap<PathBuf, FileMetadata>,
+    blob: HashMap<BlobKey, BlobData>,
+    preprocessing: QcPipeline,
+    #[cfg_attr(not(feature = "navigation"), allow(dead_code))]
+    frame: Option<(i32, i32)>,
+}
+
+impl QcContext {
+    /// Saves current [QcContext] as a snapshot file, that may be restored
+    /// with [Self::from_snapshot] much faster than parsing all files again.
+    /// The snapshot contains the files list, the merged (and possibly preprocessed) data
+    /// and the preprocessing steps that were applied.
+    /// The navigation `Almanac` itself is not stored, only the ECEF `Frame` selection is.
+    /// ```
+    /// use gnss_qc::prelude::QcContext;
+    ///
+    /// let mut context = QcContext::new();
+    ///
+    /// context.load_file("data/OBS/V2/AJAC3550.21O")
+    ///     .unwrap();
+    ///
+    /// let snapshot = std::env::temp_dir()
+    ///     .join(format!("ajac3550-{}.qcs", std::process::id()));
+    ///
+    /// context.save_snapshot(&snapshot)
+    ///     .unwrap();
+    ///
+    /// let restored = QcContext::from_snapshot(&snapshot)
+    ///     .unwrap();
+    ///
+    /// assert!(restored.has_observation());
+    ///
+    /// # std::fs::remove_file(&snapshot).unwrap();
+    /// ```
+    pub fn save_snapshot<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let mut writer = BufWriter::new(File::create(path)?);
+
+        #[cfg(feature = "navigation")]
+        let frame = Some((self.earth_cef.ephemeris_id, self.earth_cef.orientation_id));
+
+        #[cfg(not(feature = "navigation"))]
+        let frame = None;
+
+        codec().serialize_into(&mut writer, &SnapshotHeader::current())?;
+
+        codec().serialize_into(
+            &mut writer,
+            &SnapshotRef {
+                files: &self.files,
+                metadata: &self.metadata,
+                blob: &self.blob,
+                preprocessing: &self.preprocessing,
+                frame,
+            },
+        )?;
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Decodes a snapshot file, rejecting snapshots produced by another
+    /// version of this library.
+    fn read_snapshot(path: &Path) -> Result<Snapshot, Error> {
+        let fd = File::open(path)?;
+
+        // content may not be larger than the file itself
+        let size = fd.metadata()?.len();
+
+        let mut reader = BufReader::new(fd);
+
+        let header: SnapshotHeader = codec()
+            .with_limit(SNAPSHOT_HEADER_LIMIT)
+            .deserialize_from(&mut reader)
+            .map_err(|_| Error::NotASnapshot)?;
+
+        header.validate()?;
+
+        let snapshot = codec().with_limit(size).deserialize_from(&mut reader)?;
+        Ok(snapshot)
+    }
+
+    /// Restores a [QcContext] from a snapshot file, previously saved with [Self::save_snapshot].
+    /// Snapshots produced by another version of this library are rejected:
+    /// simply load the original files again.
+    /// When the `navigation` feature is activated, the `Almanac` is obtained
+    /// like in [Self::new], and the ECEF `Frame` selection is restored.
+    pub fn from_snapshot<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let snapshot = Self::read_snapshot(path.as_ref())?;
+
+        #[cfg(feature = "navigation")]
+        {
+            let (almanac, _) = Self::default_almanac_frame();
+            return Ok(Self::from_snapshot_data(snapshot, almanac));
+        }
+
+        #[cfg(not(feature = "navigation"))]
+        Ok(Self {
+            files: snapshot.files,
+            metadata: snapshot.metadata,
+            blob: snapshot.blob,
+            preprocessing: snapshot.preprocessing,
+        })
+    }
+
+    /// Restores a [QcContext] from a snapshot file, previously saved with [Self::save_snapshot],
+    /// using your own [Almanac] (obtained externally). The ECEF [Frame] selection is restored.
+    #[cfg(feature = "navigation")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "navigation")))]
+    pub fn from_snapshot_almanac<P: AsRef<Path>>(path: P, almanac: Almanac) -> Result<Self, Error> {
+        let snapshot = Self::read_snapshot(path.as_ref())?;
+        Ok(Self::from_snapshot_data(snapshot, almanac))
+    }
+
+    #[cfg(feature = "navigation")]
+    fn from_snapshot_data(snapshot: Snapshot, almanac: Almanac) -> Self {
+        let default_frame = Self::default_frame(&almanac);
+
+        let earth_cef = match snapshot.frame {
+            Some((ephemeris_id, orientation_id)) => almanac
+                .frame_from_uid(Frame::new(ephemeris_id, orientation_id))
+                .unwrap_or_else(|e| {
+                    error!("failed to restore snapshot frame: {}", e);
+                    default_frame
+                }),
+            None => default_frame,
+        };
+
+        Self {
+            files: snapshot.files,
+            metadata: snapshot.metadata,
+            blob: snapshot.blob,
+            preprocessing: snapshot.preprocessing,
+            almanac,
+            earth_cef,
+            orbit_samples: Default::default(),
+        }
+    }
+}
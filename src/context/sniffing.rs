@@ -0,0 +1,251 @@
+//! Input product identification
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read, Seek},
+    path::Path,
+    str::FromStr,
+};
+
+use rinex::prod::ProductionAttributes;
+
+#[cfg(feature = "flate2")]
+use flate2::read::GzDecoder;
+
+use crate::{
+    context::{BlobData, FileDigest, HashingReader},
+    error::Error,
+    prelude::{
+        BiasSolutions, EarthOrientation, EopFormat, ProductType, QcContext, Rinex, StationSolutions,
+    },
+};
+
+#[cfg(feature = "sp3")]
+use crate::prelude::SP3;
+
+/// Gzip magic bytes
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Number of header lines we inspect at most,
+/// before giving up on the identification.
+const MAX_SNIFFED_LINES: usize = 8;
+
+/// [FileFormat] as identified by content inspection
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum FileFormat {
+    /// Readable RINEX
+    RINEX,
+    /// Compact RINEX (CRINEX)
+    CRINEX,
+    #[cfg(feature = "sp3")]
+    /// SP3
+    SP3,
+    /// Bias-SINEX
+    BiasSINEX,
+    /// IERS Earth Orientation Parameters
+    EOP,
+    /// SINEX station coordinates
+    SINEX,
+}
+
+/// Result of the content inspection
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct Sniffed {
+    /// Identified [FileFormat]
+    pub format: FileFormat,
+    /// True when Gzip compressed
+    pub gzip: bool,
+}
+
+/// Returns true if this (first) line is an SP3 descriptor:
+/// revisions a, b, c and d (#a / #b / #c / #d), as supported by the SP3 parser.
+#[cfg(feature = "sp3")]
+fn is_sp3_descriptor(line: &str) -> bool {
+    let mut chars = line.chars();
+    chars.next() == Some('#') && matches!(chars.next(), Some('a' | 'b' | 'c' | 'd'))
+}
+
+/// Returns true if the RINEX type (column 21 of the
+/// RINEX VERSION / TYPE line) is supported.
+fn is_supported_rinex_type(line: &str) -> bool {
+    matches!(
+        line.get(20..21),
+        Some("O" | "N" | "G" | "H" | "M" | "C" | "D")
+    )
+}
+
+/// Identifies the [FileFormat] from the first lines of a readable stream.
+/// Binary (non UTF-8) content is reported as [Error::BinaryContent],
+/// decompression failures as [Error::CorruptGzip].
+fn sniff_header<R: BufRead>(reader: R, gzip: bool) -> Result<FileFormat, Error> {
+    for (nth, line) in reader.lines().take(MAX_SNIFFED_LINES).enumerate() {
+        let line = line.map_err(|e| match e.kind() {
+            std::io::ErrorKind::InvalidData => Error::BinaryContent,
+            _ if gzip => Error::CorruptGzip,
+            _ => Error::IO(e),
+        })?;
+
+        #[cfg(feature = "sp3")]
+        if nth == 0 && is_sp3_descriptor(&line) {
+            return Ok(FileFormat::SP3);
+        }
+
+        if nth == 0 && line.starts_with("%=BIA") {
+            return Ok(FileFormat::BiasSINEX);
+        }
+
+        if nth == 0 && line.starts_with("%=SNX") {
+            return Ok(FileFormat::SINEX);
+        }
+
+        if EopFormat::sniff(nth, &line).is_some() {
+            return Ok(FileFormat::EOP);
+        }
+
+        if line.contains("CRINEX VERS") {
+            return Ok(FileFormat::CRINEX);
+        }
+
+        if line.contains("RINEX VERSION / TYPE") {
+            if is_supported_rinex_type(&line) {
+                return Ok(FileFormat::RINEX);
+            } else {
+                return Err(Error::NonSupportedFileFormat);
+            }
+        }
+
+        if line.contains("ANTEX VERSION / SYST") {
+            return Ok(FileFormat::RINEX);
+        }
+
+        if line.contains("IONEX VERSION / TYPE") {
+            return Ok(FileFormat::RINEX);
+        }
+    }
+
+    Err(Error::NonSupportedFileFormat)
+}
+
+/// Identifies the content of this readable file.
+pub(crate) fn sniff_file<P: AsRef<Path>>(path: P) -> Result<Sniffed, Error> {
+    let mut fd = File::open(&path)?;
+
+    let mut magic = [0u8; 2];
+    let gzip = fd.read_exact(&mut magic).is_ok() && magic == GZIP_MAGIC;
+
+    fd.rewind()?;
+
+    if gzip {
+        #[cfg(feature = "flate2")]
+        {
+            let format = sniff_header(BufReader::new(GzDecoder::new(fd)), gzip)?;
+            return Ok(Sniffed { format, gzip });
+        }
+
+        #[cfg(not(feature = "flate2"))]
+        return Err(Error::NonSupportedFileFormat);
+    }
+
+    let format = sniff_header(BufReader::new(fd), gzip)?;
+    Ok(Sniffed { format, gzip })
+}
+
+/// Parses this readable [Read]er as this [FileFormat].
+fn parse_reader<R: Read>(path: &Path, format: FileFormat, reader: R) -> Result<BlobData, Error> {
+    let mut reader = BufReader::new(reader);
+
+    match format {
+        FileFormat::RINEX | FileFormat::CRINEX => {
+            let mut rinex = Rinex::parse(&mut reader)?;
+
+            // like Rinex::from_file: production attributes from the file name
+            rinex.prod_attr = path
+                .file_name()
+                .and_then(|name| ProductionAttributes::from_str(&name.to_string_lossy()).ok());
+
+            Ok(BlobData::RINEX(rinex))
+        }
+        #[cfg(feature = "sp3")]
+        FileFormat::SP3 => Ok(BlobData::SP3(SP3::from_reader(&mut reader)?)),
+        FileFormat::BiasSINEX => Ok(BlobData::BIAS(BiasSolutions::parse(reader)?)),
+        FileFormat::EOP => Ok(BlobData::EOP(EarthOrientation::parse(reader)?)),
+        FileFormat::SINEX => Ok(BlobData::SNX(StationSolutions::parse(reader)?)),
+    }
+}
+
+/// Parses this readable file as this [FileFormat]. The file is read only once:
+/// its [FileDigest] is computed from the very content that was parsed.
+pub(crate) fn parse_file<P: AsRef<Path>>(
+    path: P,
+    format: FileFormat,
+    gzip: bool,
+) -> Result<(BlobData, FileDigest), Error> {
+    let path = path.as_ref();
+    let mut fd = HashingReader::new(File::open(path)?);
+
+    if gzip {
+        #[cfg(feature = "flate2")]
+        {
+            let blob = parse_reader(path, format, GzDecoder::new(&mut fd))?;
+            return Ok((blob, fd.digest()?));
+        }
+
+        #[cfg(not(feature = "flate2"))]
+        return Err(Error::NonSupportedFileFormat);
+    }
+
+    let blob = parse_reader(path, format, &mut fd)?;
+    Ok((blob, fd.digest()?))
+}
+
+impl QcContext {
+    /// Load any supported file into this [QcContext].
+    /// The file format is identified by inspecting its content
+    /// (Gzip compression, CRINEX, RINEX, IONEX, SP3, SINEX, Bias-SINEX or EOP header), not its name.
+    /// Returns the [ProductType] that was identified and loaded.
+    ///
+    /// With the "rtcm", "ubx" and "binex" features, recorded RTCM3 streams, UBX logs
+    /// and BINEX files are supported as well.
+    /// They usually describe both Observation and Broadcast Navigation: the Observation prevails
+    /// in the returned [ProductType].
+    /// ```
+    /// use gnss_qc::prelude::{QcContext, ProductType};
+    ///
+    /// let mut context = QcContext::new();
+    ///
+    /// let product = context.load_file("data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(product, ProductType::Observation);
+    ///
+    /// let product = context.load_file("data/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(product, ProductType::BroadcastNavigation);
+    /// ```
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> Result<ProductType, Error> {
+        let (blob, digest) = match Self::read_file(&path) {
+            Ok(parsed) => parsed,
+            #[cfg(any(feature = "rtcm", feature = "ubx", feature = "binex"))]
+            Err(e @ (Error::NonSupportedFileFormat | Error::BinaryContent)) => {
+                // binary streams describe several products: Observations prevail
+                let stream = Self::read_stream(&path)?.ok_or(e)?;
+                let products = self.load_stream(&path, stream)?;
+                return products
+                    .last()
+                    .copied()
+                    .ok_or(Error::NonSupportedFileFormat);
+            }
+            Err(e) => return Err(e),
+        };
+
+        let product = blob.product_type();
+        self.load_parsed(path, blob, digest)?;
+        Ok(product)
+    }
+
+    /// Identifies and parses this readable file, without loading it.
+    pub(crate) fn read_file<P: AsRef<Path>>(path: P) -> Result<(BlobData, FileDigest), Error> {
+        let sniffed = sniff_file(&path)?;
+        parse_file(path, sniffed.format, sniffed.gzip)
+    }
+}
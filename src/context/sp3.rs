@@ -1,5 +1,5 @@
 use crate::{
-    context::BlobData,
+    context::{parse_file, BlobData, BlobKey, FileFormat, FileMetadata},
     error::Error,
     prelude::{ProductType, QcContext, SP3},
 };
@@ -12,39 +12,47 @@ impl QcContext {
     /// Add this [SP3] into current [QcContext].
     /// File revision must be supported and must be correctly formatted
     /// for this operation to be effective.
+    /// Like the data already loaded, it is preprocessed with the recorded
+    /// [QcPipeline](crate::prelude::QcPipeline) (see [Self::preprocessing]).
     pub fn load_sp3<P: AsRef<Path>>(&mut self, path: P, sp3: SP3) -> Result<(), Error> {
+        if !self.preprocessing.steps.is_empty() {
+            return self.load_preprocessed(path.as_ref(), BlobData::SP3(sp3));
+        }
+
         let prod_type = ProductType::HighPrecisionOrbit;
 
         let path_buf = path.as_ref().to_path_buf();
 
+        let key = BlobKey::from_product(prod_type);
+
+        let meta = FileMetadata::from_sp3(&sp3);
+
         // extend context blob
-        if let Some(paths) = self
-            .files
-            .iter_mut()
-            .filter_map(|(prod, files)| {
-                if *prod == prod_type {
-                    Some(files)
-                } else {
-                    None
-                }
-            })
-            .reduce(|k, _| k)
-        {
-            if let Some(inner) = self.blob.get_mut(&prod_type).and_then(|k| k.as_mut_sp3()) {
+        if let Some(inner) = self.blob.get_mut(&key) {
+            if let Some(inner) = inner.as_mut_sp3() {
                 inner.merge_mut(&sp3)?;
-                paths.push(path_buf);
             }
         } else {
-            self.blob.insert(prod_type, BlobData::SP3(sp3));
+            self.blob.insert(key, BlobData::SP3(sp3));
+        }
+
+        self.invalidate_orbits();
+
+        self.metadata.insert(path_buf.clone(), meta);
+
+        if let Some(paths) = self.files.get_mut(&prod_type) {
+            paths.push(path_buf);
+        } else {
             self.files.insert(prod_type, vec![path_buf]);
         }
+
         Ok(())
     }
 
     /// Load readable [SP3] file into this [QcContext].
     pub fn load_sp3_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
-        let sp3 = SP3::from_file(&path)?;
-        self.load_sp3(path, sp3)
+        let (blob, digest) = parse_file(&path, FileFormat::SP3, false)?;
+        self.load_parsed(path, blob, digest)
     }
 
     /// Returns true if [ProductType::HighPrecisionOrbit] are present in current [QcContext]
@@ -68,6 +76,7 @@ impl QcContext {
 
     /// Returns mutable reference to inner [ProductType::HighPrecisionOrbit] data
     pub fn sp3_mut(&mut self) -> Option<&mut SP3> {
+        self.invalidate_orbits();
         self.data_mut(ProductType::HighPrecisionOrbit)?.as_mut_sp3()
     }
 
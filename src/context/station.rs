@@ -0,0 +1,67 @@
+use crate::{
+    context::{parse_file, BlobData, BlobKey, FileFormat, FileMetadata},
+    error::Error,
+    prelude::{ProductType, QcContext, StationSolutions},
+};
+
+use std::path::Path;
+
+impl QcContext {
+    /// Add these [StationSolutions] into current [QcContext].
+    pub fn load_station_solutions<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        solutions: StationSolutions,
+    ) -> Result<(), Error> {
+        let prod_type = ProductType::StationCoordinates;
+
+        let path_buf = path.as_ref().to_path_buf();
+
+        let key = BlobKey::from_product(prod_type);
+
+        self.metadata.insert(
+            path_buf.clone(),
+            FileMetadata::from_station_solutions(&solutions),
+        );
+
+        // extend context blob
+        if let Some(inner) = self.blob.get_mut(&key) {
+            if let Some(inner) = inner.as_mut_station_solutions() {
+                inner.merge_mut(&solutions);
+            }
+        } else {
+            self.blob.insert(key, BlobData::SNX(solutions));
+        }
+
+        if let Some(paths) = self.files.get_mut(&prod_type) {
+            paths.push(path_buf);
+        } else {
+            self.files.insert(prod_type, vec![path_buf]);
+        }
+
+        Ok(())
+    }
+
+    /// Load a readable SINEX (station coordinates) file into this [QcContext].
+    pub fn load_station_sinex_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
+        let (blob, digest) = parse_file(&path, FileFormat::SINEX, false)?;
+        self.load_parsed(path, blob, digest)
+    }
+
+    /// Returns reference to inner [ProductType::StationCoordinates] data
+    pub fn station_solutions(&self) -> Option<&StationSolutions> {
+        self.data(ProductType::StationCoordinates)?
+            .as_station_solutions()
+    }
+
+    /// Returns mutable reference to inner [ProductType::StationCoordinates] data
+    pub fn station_solutions_mut(&mut self) -> Option<&mut StationSolutions> {
+        self.data_mut(ProductType::StationCoordinates)?
+            .as_mut_station_solutions()
+    }
+
+    /// Returns true if [ProductType::StationCoordinates] are present in Self
+    pub fn has_station_solutions(&self) -> bool {
+        self.station_solutions().is_some()
+    }
+}
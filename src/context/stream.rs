@@ -0,0 +1,323 @@
+//! Binary streams loading
+use std::{
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+#[cfg(feature = "flate2")]
+use flate2::read::GzDecoder;
+
+use crate::{
+    context::{BlobData, FileDigest},
+    error::Error,
+    prelude::{ProductType, QcContext},
+    stream::RinexCollector,
+};
+
+#[cfg(any(feature = "rtcm", feature = "ubx"))]
+use crate::prelude::{Duration, Epoch, TimeScale};
+
+#[cfg(feature = "rtcm")]
+use crate::stream::rtcm;
+
+#[cfg(feature = "ubx")]
+use crate::stream::ubx;
+
+#[cfg(feature = "binex")]
+use crate::stream::binex;
+
+/// Gzip magic bytes
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Products decoded from one binary stream
+pub(crate) struct DecodedStream {
+    /// Streaming protocol
+    pub protocol: &'static str,
+    /// Decoded products: Broadcast Navigation first, then Observation
+    pub blobs: Vec<BlobData>,
+    /// [FileDigest] of the stream file
+    pub digest: FileDigest,
+}
+
+impl DecodedStream {
+    fn new(protocol: &'static str, collector: RinexCollector, digest: FileDigest) -> Self {
+        let blobs = [collector.navigation(), collector.observation()]
+            .into_iter()
+            .flatten()
+            .map(BlobData::RINEX)
+            .collect();
+
+        Self {
+            protocol,
+            blobs,
+            digest,
+        }
+    }
+}
+
+/// Reads this (possibly Gzip compressed) binary file entirely.
+/// Returns its (inflated) content and the [FileDigest] of the file.
+fn read_bytes(path: &Path) -> Result<(Vec<u8>, FileDigest), Error> {
+    let mut buf = Vec::new();
+    File::open(path)?.read_to_end(&mut buf)?;
+
+    let digest = FileDigest::from_bytes(&buf);
+
+    if buf.starts_with(&GZIP_MAGIC) {
+        #[cfg(feature = "flate2")]
+        {
+            let mut inflated = Vec::new();
+            GzDecoder::new(&buf[..]).read_to_end(&mut inflated)?;
+            return Ok((inflated, digest));
+        }
+
+        #[cfg(not(feature = "flate2"))]
+        return Err(Error::NonSupportedFileFormat);
+    }
+
+    Ok((buf, digest))
+}
+
+/// Returns the [Epoch] the streamed time of week may be resolved against,
+/// when the stream does not describe the week number itself: the production
+/// date of the file name, either V3 long ("ESBC00DNK_S_20201770000_01D_MO.rtcm3", YYYYDDDHHMM)
+/// or V2 short ("esbc1770.20_", DDD and YY).
+#[cfg(any(feature = "rtcm", feature = "ubx"))]
+fn file_name_epoch(path: &Path) -> Option<Epoch> {
+    let name = path.file_name()?.to_string_lossy().to_string();
+
+    let (year, doy, hours, minutes) = if name.contains('_') {
+        let date = name.split('_').nth(2)?;
+
+        if date.len() != 11 || !date.bytes().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+
+        (
+            date[0..4].parse::<i32>().ok()?,
+            date[4..7].parse::<u16>().ok()?,
+            date[7..9].parse::<u8>().ok()?,
+            date[9..11].parse::<u8>().ok()?,
+        )
+    } else {
+        let (stem, extension) = name.split_once('.')?;
+
+        if stem.len() != 8 || extension.len() < 2 {
+            return None;
+        }
+
+        let (doy, yy) = (stem.get(4..7)?, extension.get(0..2)?);
+
+        if !doy.bytes().chain(yy.bytes()).all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+
+        let yy = yy.parse::<i32>().ok()?;
+        let year = if yy < 80 { 2000 + yy } else { 1900 + yy };
+
+        (year, doy.parse::<u16>().ok()?, 0, 0)
+    };
+
+    let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+
+    if doy == 0 || doy > 365 + leap as u16 || hours > 23 || minutes > 59 {
+        return None;
+    }
+
+    Some(
+        Epoch::from_gregorian(year, 1, 1, hours, minutes, 0, 0, TimeScale::GPST)
+            + Duration::from_days((doy - 1) as f64),
+    )
+}
+
+impl QcContext {
+    #[cfg(feature = "rtcm")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rtcm")))]
+    /// Load a recorded RTCM3 stream into this [QcContext].
+    ///
+    /// MSM4, MSM5 and MSM7 observations form [ProductType::Observation] data, named after the file.
+    /// GPS (1019), GLONASS (1020), BeiDou (1042) and Galileo (1045/1046) ephemerides
+    /// form [ProductType::BroadcastNavigation] data.
+    /// Station coordinates (1005/1006) define the receiver position.
+    ///
+    /// RTCM3 only streams the time of week. It is resolved against the first GPS, Galileo
+    /// or BeiDou ephemeris, otherwise against the production date of the (V3 long or V2 short)
+    /// file name, which must then be within 3 days of the recording.
+    /// Use [Self::load_rtcm_file_at] when the file is not named after its production date.
+    /// Fails with [StreamError::NoReferenceEpoch](crate::prelude::StreamError::NoReferenceEpoch)
+    /// when the time of week may not be resolved.
+    ///
+    /// Returns the [ProductType]s that were loaded.
+    pub fn load_rtcm_file<P: AsRef<Path>>(&mut self, path: P) -> Result<Vec<ProductType>, Error> {
+        let (buf, digest) = read_bytes(path.as_ref())?;
+        let collector = rtcm::Decoder::decode(&buf, file_name_epoch(path.as_ref()))?;
+        self.load_stream(path, DecodedStream::new("RTCM3", collector, digest))
+    }
+
+    #[cfg(feature = "rtcm")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rtcm")))]
+    /// Load a recorded RTCM3 stream into this [QcContext], like [Self::load_rtcm_file],
+    /// resolving the streamed time of week against this `reference` [Epoch]
+    /// (within 3 days of the recording) when the stream does not contain any ephemeris.
+    pub fn load_rtcm_file_at<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        reference: Epoch,
+    ) -> Result<Vec<ProductType>, Error> {
+        let (buf, digest) = read_bytes(path.as_ref())?;
+        let collector = rtcm::Decoder::decode(&buf, Some(reference))?;
+        self.load_stream(path, DecodedStream::new("RTCM3", collector, digest))
+    }
+
+    #[cfg(feature = "ubx")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ubx")))]
+    /// Load a u-blox UBX log into this [QcContext].
+    ///
+    /// RXM-RAWX measurements form [ProductType::Observation] data, named after the file.
+    /// RXM-SFRBX GPS / QZSS LNAV subframes and Galileo I/NAV words are decoded into
+    /// [ProductType::BroadcastNavigation] data. Other navigation messages are not decoded yet.
+    ///
+    /// Subframes only stream truncated week numbers. They are resolved against the first
+    /// RXM-RAWX epoch, otherwise against the production date of the (V3 long or V2 short)
+    /// file name. Use [Self::load_ubx_file_at] when the file is not named after its production date.
+    ///
+    /// Returns the [ProductType]s that were loaded.
+    pub fn load_ubx_file<P: AsRef<Path>>(&mut self, path: P) -> Result<Vec<ProductType>, Error> {
+        let (buf, digest) = read_bytes(path.as_ref())?;
+        let collector = ubx::Decoder::decode(&buf, file_name_epoch(path.as_ref()))?;
+        self.load_stream(path, DecodedStream::new("UBX", collector, digest))
+    }
+
+    #[cfg(feature = "ubx")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ubx")))]
+    /// Load a u-blox UBX log into this [QcContext], like [Self::load_ubx_file],
+    /// resolving truncated week numbers against this `reference` [Epoch]
+    /// when the log does not contain any RXM-RAWX measurement.
+    pub fn load_ubx_file_at<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        reference: Epoch,
+    ) -> Result<Vec<ProductType>, Error> {
+        let (buf, digest) = read_bytes(path.as_ref())?;
+        let collector = ubx::Decoder::decode(&buf, Some(reference))?;
+        self.load_stream(path, DecodedStream::new("UBX", collector, digest))
+    }
+
+    #[cfg(feature = "binex")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "binex")))]
+    /// Load a BINEX file into this [QcContext].
+    ///
+    /// GNSS observation records (0x7f-05) form [ProductType::Observation] data.
+    /// GPS, GLONASS and Galileo ephemeris records (0x01-01, 0x01-02 and 0x01-04)
+    /// form [ProductType::BroadcastNavigation] data.
+    /// Site metadata records (0x00) describe the marker, receiver, antenna and
+    /// production of the Observation header: the station is named after the marker,
+    /// or after the file when the marker is not described.
+    ///
+    /// Only forward readable, big endian BINEX is supported.
+    ///
+    /// Returns the [ProductType]s that were loaded.
+    pub fn load_binex_file<P: AsRef<Path>>(&mut self, path: P) -> Result<Vec<ProductType>, Error> {
+        let (buf, digest) = read_bytes(path.as_ref())?;
+        let collector = binex::Decoder::decode(&buf)?;
+        self.load_stream(path, DecodedStream::new("BINEX", collector, digest))
+    }
+
+    /// Identifies and decodes this binary stream, without loading it.
+    /// Returns None when this is not a supported binary stream.
+    pub(crate) fn read_stream<P: AsRef<Path>>(path: P) -> Result<Option<DecodedStream>, Error> {
+        let path = path.as_ref();
+        let (buf, digest) = read_bytes(path)?;
+
+        #[cfg(feature = "rtcm")]
+        if rtcm::is_rtcm3(&buf) {
+            let collector = rtcm::Decoder::decode(&buf, file_name_epoch(path))?;
+            return Ok(Some(DecodedStream::new("RTCM3", collector, digest)));
+        }
+
+        #[cfg(feature = "ubx")]
+        if ubx::is_ubx(&buf) {
+            let collector = ubx::Decoder::decode(&buf, file_name_epoch(path))?;
+            return Ok(Some(DecodedStream::new("UBX", collector, digest)));
+        }
+
+        #[cfg(feature = "binex")]
+        if binex::is_binex(&buf) {
+            let collector = binex::Decoder::decode(&buf)?;
+            return Ok(Some(DecodedStream::new("BINEX", collector, digest)));
+        }
+
+        Ok(None)
+    }
+
+    /// Loads all products of this [DecodedStream].
+    /// Self is not modified on failure: either all products are loaded, or none.
+    /// Returns the [ProductType]s that were loaded.
+    pub(crate) fn load_stream<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        stream: DecodedStream,
+    ) -> Result<Vec<ProductType>, Error> {
+        let path_buf: PathBuf = path.as_ref().to_path_buf();
+
+        let mut products = Vec::with_capacity(stream.blobs.len());
+
+        // Observations are loaded last: their metadata (and station) describe this file
+        let mut fresh = self.empty_clone();
+
+        for blob in stream.blobs {
+            products.push(blob.product_type());
+            fresh.load_blob(&path_buf, blob)?;
+        }
+
+        // Self is not modified on failure
+        self.extend_loaded(fresh)?;
+
+        if let Some(meta) = self.metadata.get_mut(&path_buf) {
+            meta.set_digest(stream.digest);
+            meta.header = format!(
+                "{} stream ({})",
+                stream.protocol,
+                products
+                    .iter()
+                    .map(|product| product.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+
+        Ok(products)
+    }
+}
+
+#[cfg(all(test, any(feature = "rtcm", feature = "ubx")))]
+mod test {
+    use super::file_name_epoch;
+    use crate::prelude::Epoch;
+    use std::{path::Path, str::FromStr};
+
+    #[test]
+    fn file_name_epochs() {
+        for (name, expected) in [
+            (
+                "MLVL00FRA_R_20240070200_01H_MO.rtcm3",
+                Some("2024-01-07T02:00:00 GPST"),
+            ),
+            (
+                "ESBC00DNK_S_20201771230_15M_MO.ubx.gz",
+                Some("2020-06-25T12:30:00 GPST"),
+            ),
+            ("esbc1770.20_", Some("2020-06-25T00:00:00 GPST")),
+            ("mlvl0070.99u", Some("1999-01-07T00:00:00 GPST")),
+            ("MLVL00FRA_R_2024007_01H_MO.rtcm3", None),
+            ("MLVL00FRA_R_20243670000_01H_MO.rtcm3", None),
+            ("MLVL00FRA_R_20233660000_01H_MO.rtcm3", None),
+            ("capture.rtcm3", None),
+            ("capture", None),
+        ] {
+            let expected = expected.map(|t| Epoch::from_str(t).unwrap());
+            assert_eq!(file_name_epoch(Path::new(name)), expected, "\"{}\"", name);
+        }
+    }
+}
@@ -1,32 +1,53 @@
-use crate::prelude::{ProductType, QcContext, TimeScale};
+use crate::{
+    context::BlobData,
+    error::Error,
+    prelude::{Epoch, ProductType, QcContext, Rinex, TimeScale},
+};
 
-use qc_traits::{Merge, TimeCorrectionError, TimeCorrectionsDB, Timeshift};
+use qc_traits::{TimeCorrectionsDB, Timeshift};
 
-impl QcContext {
-    /// Collect a [TimeCorrectionDB] from this [QcContext], that you can then
-    /// use for precise temporal correction. The database will contain
-    /// all time corrections available and described by this dataset.
-    /// This requires both navigation feature and navigation compatibility to truly be effective.
-    pub fn time_corrections_database(&self) -> Option<TimeCorrectionsDB> {
-        let mut db = Option::<TimeCorrectionsDB>::None;
+/// Expresses the Meteo or Clock [Rinex] epochs with this converter,
+/// which returns None when one epoch may not be converted.
+/// Returns None, leaving the [Rinex] partially converted, in that case.
+fn transpose_rinex<F: Fn(Epoch) -> Option<Epoch>>(
+    rinex: &mut Rinex,
+    timescale: TimeScale,
+    convert: F,
+) -> Option<()> {
+    if let Some(record) = rinex.record.as_mut_meteo() {
+        *record = std::mem::take(record)
+            .into_iter()
+            .map(|(mut k, v)| {
+                k.epoch = convert(k.epoch)?;
+                Some((k, v))
+            })
+            .collect::<Option<_>>()?;
+    } else if let Some(record) = rinex.record.as_mut_clock() {
+        *record = std::mem::take(record)
+            .into_iter()
+            .map(|(t, v)| Some((convert(t)?, v)))
+            .collect::<Option<_>>()?;
 
-        if let Some(brdc) = self.brdc_navigation() {
-            if let Some(nav_db) = brdc.time_corrections_database() {
-                if let Some(db) = &mut db {
-                    db.merge_mut(&nav_db).unwrap(); // infaillble
-                } else {
-                    db = Some(nav_db);
-                }
-            }
+        if let Some(clock) = &mut rinex.header.clock {
+            clock.timescale = Some(timescale);
         }
-
-        db
     }
 
+    Some(())
+}
+
+impl QcContext {
     /// Infaillible transposition of the temporal products to desired [TimeScale].
-    /// This only applies to the following products:
+    /// This applies to the following products:
     /// - Observation RINEX
+    /// - Meteo RINEX
+    /// - Clock RINEX
     /// - SP3
+    ///
+    /// Navigation RINEX are preserved: the ToC and ToE of each frame are expressed in the
+    /// timescale of its constellation, as defined by their ICD, and remain valid in any context.
+    ///
+    /// Use [QcContext::timescale_conflicts] to verify the result.
     /// ```
     /// use gnss_qc::prelude::{QcContext, TimeScale};
     ///
@@ -42,14 +63,23 @@ impl QcContext {
     ///
     /// // convert both to GST
     /// context.timescale_transposition_mut(TimeScale::GST);
+    ///
+    /// assert_eq!(context.timescale(), Some(TimeScale::GST));
+    /// assert!(context.timescale_conflicts().is_empty());
     /// ```
     pub fn timescale_transposition_mut(&mut self, timescale: TimeScale) {
-        for (product_type, data) in self.blob.iter_mut() {
-            match product_type {
+        self.invalidate_orbits();
+
+        for (key, data) in self.blob.iter_mut() {
+            match key.product {
                 ProductType::Observation => {
                     let rinex = data.as_mut_rinex().unwrap();
                     rinex.timeshift_mut(timescale);
                 }
+                ProductType::MeteoObservation | ProductType::HighPrecisionClock => {
+                    let rinex = data.as_mut_rinex().unwrap();
+                    transpose_rinex(rinex, timescale, |t| Some(t.to_time_scale(timescale)));
+                }
                 #[cfg(feature = "sp3")]
                 ProductType::HighPrecisionOrbit => {
                     let sp3 = data.as_mut_sp3().unwrap();
@@ -64,7 +94,8 @@ impl QcContext {
     /// NB: transposition might not be feasible for some components, therefore
     /// you should double check the newly obtained [QcContext].
     ///
-    /// This may apply to your [SP3] products, if feature is activated.
+    /// This applies to all products that [Self::timescale_transposition_mut] supports,
+    /// and each of them requires a correction for each epoch. Self is not modified on failure.
     ///
     /// Example (1): precise RINEX transpositions
     /// ```
@@ -104,16 +135,45 @@ impl QcContext {
         &mut self,
         db: &TimeCorrectionsDB,
         timescale: TimeScale,
-    ) -> Result<(), TimeCorrectionError> {
-        if let Some(observations) = self.observation_mut() {
-            observations.precise_correction_mut(db, timescale)?;
-        }
+    ) -> Result<(), Error> {
+        // all blobs are corrected first: Self is not modified on failure
+        let mut corrected = Vec::with_capacity(self.blob.len());
+
+        for (key, data) in self.blob.iter() {
+            let mut data = data.clone();
+
+            match (key.product, &mut data) {
+                (ProductType::Observation, BlobData::RINEX(rinex)) => {
+                    rinex
+                        .precise_correction_mut(db, timescale)
+                        .map_err(Error::TimeCorrection)?;
+                }
+                (
+                    ProductType::MeteoObservation | ProductType::HighPrecisionClock,
+                    BlobData::RINEX(rinex),
+                ) => {
+                    transpose_rinex(rinex, timescale, |t| {
+                        if t.time_scale == timescale {
+                            Some(t)
+                        } else {
+                            db.precise_epoch_correction(t, timescale)
+                        }
+                    })
+                    .ok_or(Error::NoTimeCorrection(key.product, timescale))?;
+                }
+                #[cfg(feature = "sp3")]
+                (ProductType::HighPrecisionOrbit, BlobData::SP3(sp3)) => {
+                    sp3.precise_correction_mut(db, timescale)
+                        .map_err(Error::TimeCorrection)?;
+                }
+                _ => continue,
+            }
 
-        #[cfg(feature = "sp3")]
-        if let Some(sp3) = self.sp3_mut() {
-            sp3.precise_correction_mut(db, timescale)?;
+            corrected.push((key.clone(), data));
         }
 
+        self.blob.extend(corrected);
+        self.invalidate_orbits();
         Ok(())
     }
 }
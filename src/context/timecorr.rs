@@ -0,0 +1,349 @@
+//! Time corrections database, with provenance
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::Path,
+    str::FromStr,
+};
+
+use hifitime::Polynomial;
+use qc_traits::{TimeCorrection, TimeCorrectionsDB};
+
+use rinex::navigation::NavFrame;
+
+use crate::{
+    context::BlobData,
+    error::Error,
+    prelude::{Duration, Epoch, ProductType, QcContext, Rinex, TimeScale},
+};
+
+/// [QcTimeCorrectionSource] describes where a [QcTimeCorrection] comes from
+#[derive(Debug, Clone, PartialEq)]
+pub enum QcTimeCorrectionSource {
+    /// Broadcast navigation RINEX header (V3 TIME SYSTEM CORR)
+    NavigationHeader,
+    /// Broadcast navigation RINEX V4 System Time Offset frame
+    SystemTimeOffset,
+    /// Clock RINEX header: the product [TimeScale] is tied to UTC
+    ClockHeader,
+    /// User supplied table (for example, derived from a BIPM Circular T or a UTC(k) file),
+    /// named after its file
+    UserTable(String),
+}
+
+impl std::fmt::Display for QcTimeCorrectionSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::NavigationHeader => write!(f, "Navigation header"),
+            Self::SystemTimeOffset => write!(f, "STO frame"),
+            Self::ClockHeader => write!(f, "Clock header"),
+            Self::UserTable(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// [QcTimeCorrection] is one polynomial offset between two [TimeScale]s:
+/// lhs - rhs = a0 + a1 * dt + a2 * dt², with dt = t - reference.
+/// The polynomial is the residual offset: leap seconds, and any other constant
+/// offset between both [TimeScale]s, are accounted for by the [TimeScale] conversion itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QcTimeCorrection {
+    /// Where this correction comes from
+    pub source: QcTimeCorrectionSource,
+    /// Left hand side [TimeScale]
+    pub lhs: TimeScale,
+    /// Right hand side [TimeScale]
+    pub rhs: TimeScale,
+    /// Reference [Epoch] of the polynomial
+    pub reference: Epoch,
+    /// Validity period, from the reference [Epoch]
+    pub validity: Duration,
+    /// Polynomial terms, in [s], [s/s] and [s/s²]
+    pub polynomial: (f64, f64, f64),
+}
+
+impl QcTimeCorrection {
+    /// Converts to [TimeCorrection]
+    fn to_time_correction(&self) -> TimeCorrection {
+        let (a0, a1, a2) = self.polynomial;
+        TimeCorrection::from_reference_epoch(
+            self.reference.to_time_scale(self.lhs),
+            self.validity,
+            self.rhs,
+            Polynomial {
+                constant: Duration::from_seconds(a0),
+                rate: Duration::from_seconds(a1),
+                accel: Duration::from_seconds(a2),
+            },
+        )
+    }
+}
+
+/// [QcTimeCorrectionsDB] gathers [QcTimeCorrection]s from several sources,
+/// keeping track of where each of them comes from. Use [Self::database] to
+/// obtain the [TimeCorrectionsDB] required by precise time corrections.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QcTimeCorrectionsDB {
+    /// [QcTimeCorrection]s, in order of collection
+    pub corrections: Vec<QcTimeCorrection>,
+}
+
+/// Returns the [TimeScale] of this RINEX V4 STO system identifier (2 letters)
+fn sto_timescale(code: &str) -> Option<TimeScale> {
+    match code {
+        "GP" => Some(TimeScale::GPST),
+        "QZ" => Some(TimeScale::QZSST),
+        "GA" => Some(TimeScale::GST),
+        "BD" => Some(TimeScale::BDT),
+        "UT" => Some(TimeScale::UTC),
+        _ => None,
+    }
+}
+
+impl QcTimeCorrectionsDB {
+    /// Returns true if no correction was collected
+    pub fn is_empty(&self) -> bool {
+        self.corrections.is_empty()
+    }
+
+    /// Adds this [QcTimeCorrection]
+    pub fn add(&mut self, correction: QcTimeCorrection) {
+        self.corrections.push(correction);
+    }
+
+    /// Returns all [QcTimeCorrection]s from this [QcTimeCorrectionSource]
+    pub fn source_iter<'a>(
+        &'a self,
+        source: &'a QcTimeCorrectionSource,
+    ) -> Box<dyn Iterator<Item = &'a QcTimeCorrection> + 'a> {
+        Box::new(self.corrections.iter().filter(move |c| c.source == *source))
+    }
+
+    /// Collects the corrections described by this broadcast navigation [Rinex]:
+    /// header time system corrections and V4 System Time Offset frames.
+    pub fn collect_navigation(&mut self, rinex: &Rinex) {
+        if let Some(nav) = &rinex.header.nav {
+            for offset in nav.time_offsets.iter() {
+                let (week, nanos) = offset.t_ref;
+                self.add(QcTimeCorrection {
+                    source: QcTimeCorrectionSource::NavigationHeader,
+                    lhs: offset.lhs,
+                    rhs: offset.rhs,
+                    reference: Epoch::from_time_of_week(week, nanos, offset.lhs),
+                    validity: Duration::from_days(7.0),
+                    polynomial: offset.polynomial,
+                });
+            }
+        }
+
+        if let Some(record) = rinex.record.as_nav() {
+            for (k, frame) in record.iter() {
+                if let NavFrame::STO(sto) = frame {
+                    let system = sto.system.trim();
+
+                    let (lhs, rhs) = match (
+                        system.get(0..2).and_then(sto_timescale),
+                        system.get(2..4).and_then(sto_timescale),
+                    ) {
+                        (Some(lhs), Some(rhs)) => (lhs, rhs),
+                        _ => continue,
+                    };
+
+                    self.add(QcTimeCorrection {
+                        source: QcTimeCorrectionSource::SystemTimeOffset,
+                        lhs,
+                        rhs,
+                        reference: k.epoch,
+                        validity: Duration::from_days(1.0),
+                        polynomial: sto.a,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Collects the correction described by this Clock [Rinex] header:
+    /// the product [TimeScale] is tied to UTC by the leap seconds it describes,
+    /// valid over the product time span. Leap seconds are accounted for by the
+    /// [TimeScale] conversion itself: the residual offset is null.
+    pub fn collect_clock(&mut self, rinex: &Rinex) {
+        let timescale = match rinex.header.clock.as_ref().and_then(|clk| clk.timescale) {
+            Some(timescale) => timescale,
+            None => return,
+        };
+
+        if rinex.header.leap.is_none()
+            || !matches!(
+                timescale,
+                TimeScale::GPST | TimeScale::GST | TimeScale::QZSST | TimeScale::BDT
+            )
+        {
+            return;
+        }
+
+        let (first, last) = match (rinex.first_epoch(), rinex.last_epoch()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return,
+        };
+
+        self.add(QcTimeCorrection {
+            source: QcTimeCorrectionSource::ClockHeader,
+            lhs: timescale,
+            rhs: TimeScale::UTC,
+            reference: first,
+            validity: last - first + Duration::from_days(1.0),
+            polynomial: (0.0, 0.0, 0.0),
+        });
+    }
+
+    /// Parses a user supplied table, one correction per line:
+    /// `reference epoch, lhs, rhs, offset [ns], drift [ns/s] (optional), validity [s] (optional)`.
+    /// Offsets are residuals (see [QcTimeCorrection]), for example UTC(k) - UTC.
+    /// Lines starting with '#' are comments. The default validity is one day.
+    /// ```
+    /// use gnss_qc::prelude::{QcTimeCorrectionsDB, QcTimeCorrectionSource, TimeScale};
+    ///
+    /// let table = "# Circular T derived offsets
+    /// 2020-06-25T00:00:00 UTC, UTC, GPST, -1.2
+    /// 2020-06-26T00:00:00 UTC, UTC, GPST, -0.8, 0.0, 86400";
+    ///
+    /// let mut db = QcTimeCorrectionsDB::default();
+    ///
+    /// db.parse_table("circular-t", table.as_bytes())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(db.corrections.len(), 2);
+    /// assert_eq!(db.corrections[0].lhs, TimeScale::UTC);
+    /// assert_eq!(db.corrections[0].source, QcTimeCorrectionSource::UserTable("circular-t".to_string()));
+    /// ```
+    pub fn parse_table<R: Read>(&mut self, name: &str, reader: R) -> Result<(), Error> {
+        for (index, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let items = line.split(',').map(|item| item.trim()).collect::<Vec<_>>();
+
+            if items.len() < 4 {
+                return Err(Error::TimeCorrectionsTable(index + 1));
+            }
+
+            let parse = || -> Option<QcTimeCorrection> {
+                let reference = Epoch::from_str(items[0]).ok()?;
+                let lhs = TimeScale::from_str(items[1]).ok()?;
+                let rhs = TimeScale::from_str(items[2]).ok()?;
+                let offset_ns = f64::from_str(items[3]).ok()?;
+
+                let drift_ns_s = match items.get(4) {
+                    Some(item) => f64::from_str(item).ok()?,
+                    None => 0.0,
+                };
+
+                let validity = match items.get(5) {
+                    Some(item) => Duration::from_seconds(f64::from_str(item).ok()?),
+                    None => Duration::from_days(1.0),
+                };
+
+                Some(QcTimeCorrection {
+                    source: QcTimeCorrectionSource::UserTable(name.to_string()),
+                    lhs,
+                    rhs,
+                    reference,
+                    validity,
+                    polynomial: (offset_ns * 1.0E-9, drift_ns_s * 1.0E-9, 0.0),
+                })
+            };
+
+            let correction = parse().ok_or(Error::TimeCorrectionsTable(index + 1))?;
+            self.add(correction);
+        }
+
+        Ok(())
+    }
+
+    /// Loads a user supplied table file (see [Self::parse_table]),
+    /// named after its file name.
+    pub fn load_table<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
+        let name = path
+            .as_ref()
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .ok_or(Error::FileNameDetermination)?;
+
+        self.parse_table(&name, File::open(path)?)
+    }
+
+    /// Forms the [TimeCorrectionsDB] to be used in precise time corrections
+    pub fn database(&self) -> TimeCorrectionsDB {
+        let mut db = TimeCorrectionsDB::default();
+
+        for correction in self.corrections.iter() {
+            db.add(correction.to_time_correction());
+        }
+
+        db
+    }
+}
+
+impl QcContext {
+    /// Collects all [QcTimeCorrection]s described by this [QcContext]:
+    /// broadcast navigation headers and System Time Offset frames,
+    /// and Clock RINEX headers. Stack your own tables with [QcTimeCorrectionsDB::load_table]
+    /// and apply them with [QcContext::precise_time_correction_mut]:
+    /// ```
+    /// use gnss_qc::prelude::{QcContext, QcTimeCorrectionSource, TimeScale};
+    ///
+    /// let mut context = QcContext::new();
+    ///
+    /// context.load_rinex_file("data/OBS/V2/AJAC3550.21O")
+    ///     .unwrap();
+    ///
+    /// // no navigation data
+    /// assert!(context.time_corrections().is_empty());
+    ///
+    /// let mut corrections = context.time_corrections();
+    ///
+    /// corrections.parse_table(
+    ///     "utc-k",
+    ///     "2021-12-21T00:00:00 GPST, GPST, UTC, 12.0, 0.0, 864000".as_bytes(),
+    /// )
+    /// .unwrap();
+    ///
+    /// context.precise_time_correction_mut(&corrections.database(), TimeScale::UTC)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(context.timescale(), Some(TimeScale::UTC));
+    /// ```
+    pub fn time_corrections(&self) -> QcTimeCorrectionsDB {
+        let mut db = QcTimeCorrectionsDB::default();
+
+        for (key, data) in self.blob.iter() {
+            if let BlobData::RINEX(rinex) = data {
+                match key.product {
+                    ProductType::BroadcastNavigation => db.collect_navigation(rinex),
+                    ProductType::HighPrecisionClock => db.collect_clock(rinex),
+                    _ => {}
+                }
+            }
+        }
+
+        db
+    }
+
+    /// Collect a [TimeCorrectionsDB] from this [QcContext], that you can then
+    /// use for precise temporal correction. The database will contain
+    /// all time corrections available and described by this dataset
+    /// (see [Self::time_corrections]), so it may be obtained without navigation data.
+    pub fn time_corrections_database(&self) -> Option<TimeCorrectionsDB> {
+        let corrections = self.time_corrections();
+
+        if corrections.is_empty() {
+            None
+        } else {
+            Some(corrections.database())
+        }
+    }
+}
@@ -0,0 +1,188 @@
+//! Files removal and replacement
+use std::{collections::HashMap, path::Path};
+
+use crate::{
+    context::{BlobData, BlobKey},
+    error::Error,
+    prelude::{ProductType, QcContext},
+};
+
+impl QcContext {
+    /// Returns all [ProductType]s this file was loaded as.
+    /// Binary streams describe several products.
+    fn file_products(&self, path: &Path) -> Vec<ProductType> {
+        let mut products = self
+            .files
+            .iter()
+            .filter_map(|(product, paths)| {
+                if paths.iter().any(|p| p == path) {
+                    Some(*product)
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        products.sort();
+        products
+    }
+
+    /// Returns the [BlobData] this file contributed to, once its contribution is removed
+    /// (None when no data remains). Self is not modified.
+    fn unloaded_blobs(&self, path: &Path) -> Result<Vec<(BlobKey, Option<BlobData>)>, Error> {
+        let meta = self.metadata.get(path).ok_or(Error::FileNotLoaded)?;
+
+        self.file_products(path)
+            .into_iter()
+            .map(|product| {
+                let key = BlobKey {
+                    product,
+                    station: if product.is_station_dependent() {
+                        meta.station.clone()
+                    } else {
+                        None
+                    },
+                };
+
+                let data = self.restricted_blob(&key, |p, _| p != path)?;
+                Ok((key, data))
+            })
+            .collect()
+    }
+
+    /// Commits these [BlobData] (None removes the blob)
+    fn commit_blobs<I: IntoIterator<Item = (BlobKey, Option<BlobData>)>>(&mut self, blobs: I) {
+        self.invalidate_orbits();
+
+        for (key, data) in blobs {
+            if let Some(data) = data {
+                self.blob.insert(key, data);
+            } else {
+                self.blob.remove(&key);
+            }
+        }
+    }
+
+    /// Removes this file from current [QcContext].
+    /// Its contribution is removed from the data currently loaded, so any previous
+    /// preprocessing is preserved, and this works for data loaded from memory or streams.
+    /// When it was merged with files that cover the same time span, those files are reloaded
+    /// and the recorded [QcPipeline](crate::prelude::QcPipeline) is applied again,
+    /// which requires them to still be readable. Self is not modified on failure.
+    /// ```
+    /// use gnss_qc::prelude::{QcContext, ProductType};
+    ///
+    /// let mut context = QcContext::new();
+    ///
+    /// context.load_rinex_file("data/OBS/V2/AJAC3550.21O")
+    ///     .unwrap();
+    ///
+    /// context.load_gzip_rinex_file("data/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+    ///     .unwrap();
+    ///
+    /// context.unload_file("data/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+    ///     .unwrap();
+    ///
+    /// assert!(context.has_observation());
+    /// assert!(!context.has_brdc_navigation());
+    /// assert!(context.files(ProductType::BroadcastNavigation).is_none());
+    /// ```
+    pub fn unload_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
+        let path = path.as_ref();
+
+        let blobs = self.unloaded_blobs(path)?;
+
+        self.commit_blobs(blobs);
+
+        self.files.retain(|_, paths| {
+            paths.retain(|p| p != path);
+            !paths.is_empty()
+        });
+
+        self.metadata.remove(path);
+        Ok(())
+    }
+
+    /// Replaces a file previously loaded, by a new one.
+    /// The new file is preprocessed with the recorded [QcPipeline](crate::prelude::QcPipeline),
+    /// like the rest of the data, and takes the place of the previous file in the files list.
+    /// The previous contribution is removed like [Self::unload_file] does.
+    /// Self is not modified on failure, even when the [ProductType] changes.
+    /// The new file may not be loaded already, unless it replaces itself (reloading it).
+    /// Returns the [ProductType] of the new file.
+    pub fn replace_file<P: AsRef<Path>, Q: AsRef<Path>>(
+        &mut self,
+        path: P,
+        new: Q,
+    ) -> Result<ProductType, Error> {
+        let (path, new) = (path.as_ref(), new.as_ref());
+
+        if !self.metadata.contains_key(path) {
+            return Err(Error::FileNotLoaded);
+        }
+
+        // its data would be merged twice
+        if new != path && self.metadata.contains_key(new) {
+            return Err(Error::FileAlreadyLoaded);
+        }
+
+        // parse and preprocess the new file, like the rest of the data
+        let mut fresh = self.empty_clone();
+        fresh.preprocessing = Default::default();
+
+        let product = fresh.load_file(new)?;
+        fresh.apply_pipeline(&self.preprocessing)?;
+
+        // all blobs are computed first: Self is not modified on failure
+        let mut blobs = self
+            .unloaded_blobs(path)?
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+
+        for (key, data) in fresh.blob.iter() {
+            let base = blobs
+                .get(key)
+                .cloned()
+                .unwrap_or_else(|| self.blob.get(key).cloned());
+
+            let merged = match base {
+                Some(mut base) => {
+                    base.merge_mut(data)?;
+                    base
+                }
+                None => data.clone(),
+            };
+
+            blobs.insert(key.clone(), Some(merged));
+        }
+
+        self.commit_blobs(blobs);
+
+        // new file takes the place of the previous one
+        for (product, paths) in self.files.iter_mut() {
+            if fresh.files.contains_key(product) {
+                for p in paths.iter_mut() {
+                    if p.as_path() == path {
+                        *p = new.to_path_buf();
+                    }
+                }
+            } else {
+                paths.retain(|p| p != path);
+            }
+        }
+
+        for product in fresh.files.keys() {
+            let paths = self.files.entry(*product).or_default();
+            if !paths.iter().any(|p| p == new) {
+                paths.push(new.to_path_buf());
+            }
+        }
+
+        self.files.retain(|_, paths| !paths.is_empty());
+
+        self.metadata.remove(path);
+        self.metadata.extend(fresh.metadata);
+
+        Ok(product)
+    }
+}
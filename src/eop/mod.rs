@@ -0,0 +1,535 @@
+//! IERS Earth Orientation Parameters
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+#[cfg(feature = "flate2")]
+use flate2::read::GzDecoder;
+
+use thiserror::Error;
+
+use hifitime::prelude::{Duration, Epoch};
+
+#[cfg(feature = "snapshot")]
+use serde::{Deserialize, Serialize};
+
+/// Number of lines we inspect to determine the [EopFormat]
+const MAX_SNIFFED_LINES: usize = 8;
+
+/// Earth rotation angle rate, in revolutions per UT1 day (IERS 2010)
+const ERA_RATE: f64 = 1.002_737_811_911_354_5;
+
+/// Earth rotation angle at J2000.0 (UT1), in revolutions (IERS 2010)
+const ERA_J2000: f64 = 0.779_057_273_264;
+
+/// 3D vector (position or velocity)
+pub type Vector3 = (f64, f64, f64);
+
+/// EOP parsing errors
+#[derive(Debug, Error)]
+pub enum EopError {
+    #[error("I/O error: {0}")]
+    IO(#[from] std::io::Error),
+    #[error("unknown EOP format")]
+    UnknownFormat,
+    #[error("no EOP values found")]
+    NoValues,
+}
+
+/// Supported Earth Orientation Parameters file formats
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "snapshot", derive(Serialize, Deserialize))]
+pub enum EopFormat {
+    /// IERS Rapid Service / Prediction Center `finals2000A` (fixed columns)
+    Finals2000A,
+    /// IERS EOP C04 series (14 C04 or 20 C04)
+    C04,
+    /// IGS Earth Rotation Parameters (ERP version 2)
+    IgsErp,
+}
+
+impl std::fmt::Display for EopFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Finals2000A => write!(f, "IERS finals2000A"),
+            Self::C04 => write!(f, "IERS C04"),
+            Self::IgsErp => write!(f, "IGS ERP"),
+        }
+    }
+}
+
+impl EopFormat {
+    /// Identifies the [EopFormat] from the nth line of a file, if possible.
+    pub(crate) fn sniff(nth: usize, line: &str) -> Option<Self> {
+        if nth == 0 && line.trim().to_lowercase().starts_with("version 2") {
+            return Some(Self::IgsErp);
+        }
+
+        if line.contains("EOP") && line.contains("C04") {
+            return Some(Self::C04);
+        }
+
+        if nth == 0 && Self::parse_finals2000a(line).is_some() {
+            return Some(Self::Finals2000A);
+        }
+
+        None
+    }
+
+    /// Parses one line in this [EopFormat]. Returns None on header lines.
+    fn parse(&self, line: &str) -> Option<EopSample> {
+        match self {
+            Self::Finals2000A => Self::parse_finals2000a(line),
+            Self::C04 => Self::parse_c04(line),
+            Self::IgsErp => Self::parse_igs_erp(line),
+        }
+    }
+
+    fn parse_finals2000a(line: &str) -> Option<EopSample> {
+        let mjd = line.get(7..15)?.trim().parse::<f64>().ok()?;
+
+        let pm_flag = line.get(16..17)?;
+        if pm_flag != "I" && pm_flag != "P" {
+            return None;
+        }
+
+        let x_arcsec = line.get(18..27)?.trim().parse::<f64>().ok()?;
+        let y_arcsec = line.get(37..46)?.trim().parse::<f64>().ok()?;
+
+        let ut1_flag = line.get(57..58)?;
+        let ut1_utc_s = line.get(58..68)?.trim().parse::<f64>().ok()?;
+
+        // LOD [ms]
+        let lod_s = line
+            .get(79..86)
+            .and_then(|lod| lod.trim().parse::<f64>().ok())
+            .map(|lod_ms| lod_ms * 1.0E-3);
+
+        Some(EopSample {
+            epoch: Epoch::from_mjd_utc(mjd),
+            x_arcsec,
+            y_arcsec,
+            ut1_utc_s,
+            lod_s,
+            predicted: pm_flag == "P" || ut1_flag == "P",
+        })
+    }
+
+    fn parse_c04(line: &str) -> Option<EopSample> {
+        if line.starts_with('#') {
+            return None;
+        }
+
+        let items = line
+            .split_ascii_whitespace()
+            .map(|item| item.parse::<f64>())
+            .collect::<Result<Vec<_>, _>>()
+            .ok()?;
+
+        // 20 C04: YR MM DD HH MJD x y UT1-UTC dX dY xrt yrt LOD
+        // 14 C04: YR MM DD MJD x y UT1-UTC LOD dX dY
+        let (mjd, x, y, ut1, lod) = if items.len() >= 13 && items[3] < 24.0 && items[4] > 30_000.0 {
+            (items[4], items[5], items[6], items[7], items.get(12))
+        } else if items.len() >= 8 && items[3] > 30_000.0 {
+            (items[3], items[4], items[5], items[6], items.get(7))
+        } else {
+            return None;
+        };
+
+        Some(EopSample {
+            epoch: Epoch::from_mjd_utc(mjd),
+            x_arcsec: x,
+            y_arcsec: y,
+            ut1_utc_s: ut1,
+            lod_s: lod.copied(),
+            predicted: false,
+        })
+    }
+
+    fn parse_igs_erp(line: &str) -> Option<EopSample> {
+        let items = line
+            .split_ascii_whitespace()
+            .take(5)
+            .map(|item| item.parse::<f64>())
+            .collect::<Result<Vec<_>, _>>()
+            .ok()?;
+
+        if items.len() < 4 || items[0] < 30_000.0 {
+            return None;
+        }
+
+        // pole [1E-6 arcsec], UT1-UTC [1E-7 s], LOD [1E-7 s/d]
+        Some(EopSample {
+            epoch: Epoch::from_mjd_utc(items[0]),
+            x_arcsec: items[1] * 1.0E-6,
+            y_arcsec: items[2] * 1.0E-6,
+            ut1_utc_s: items[3] * 1.0E-7,
+            lod_s: items.get(4).map(|lod| lod * 1.0E-7),
+            predicted: false,
+        })
+    }
+}
+
+/// [EopSample] describes the Earth orientation at one [Epoch]
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "snapshot", derive(Serialize, Deserialize))]
+pub struct EopSample {
+    /// [Epoch] (UTC)
+    pub epoch: Epoch,
+    /// Pole coordinate X, in arcseconds
+    pub x_arcsec: f64,
+    /// Pole coordinate Y, in arcseconds
+    pub y_arcsec: f64,
+    /// UT1-UTC, in seconds
+    pub ut1_utc_s: f64,
+    /// Excess length of day, in seconds
+    pub lod_s: Option<f64>,
+    /// True when this is a prediction
+    pub predicted: bool,
+}
+
+impl EopSample {
+    /// Nominal [EopSample] at this [Epoch] (UTC), when no parameters are known:
+    /// UT1 = UTC and no polar motion.
+    pub fn nominal(epoch: Epoch) -> Self {
+        Self {
+            epoch,
+            x_arcsec: 0.0,
+            y_arcsec: 0.0,
+            ut1_utc_s: 0.0,
+            lod_s: None,
+            predicted: false,
+        }
+    }
+
+    /// Earth Rotation Angle (IERS 2010) at this [EopSample] [Epoch], in radians,
+    /// obtained from UT1.
+    pub fn earth_rotation_angle_rad(&self) -> f64 {
+        // UT1 days since J2000.0
+        let tu = self.epoch.to_mjd_utc_days() - 51_544.5 + self.ut1_utc_s / 86_400.0;
+        let revolutions = tu.fract() + ERA_J2000 + (ERA_RATE - 1.0) * tu;
+        revolutions.rem_euclid(1.0) * std::f64::consts::TAU
+    }
+
+    /// Earth angular velocity, in radians per second, corrected for the excess length of day
+    fn angular_velocity_rad_s(&self) -> f64 {
+        ERA_RATE * std::f64::consts::TAU / 86_400.0 * (1.0 - self.lod_s.unwrap_or(0.0) / 86_400.0)
+    }
+
+    /// Polar motion angles (xp, yp), in radians
+    fn pole_rad(&self) -> (f64, f64) {
+        (
+            (self.x_arcsec / 3600.0).to_radians(),
+            (self.y_arcsec / 3600.0).to_radians(),
+        )
+    }
+
+    /// Rotates this Earth fixed state (ITRS position and velocity, any unit)
+    /// to the Celestial Intermediate Reference System, at this [EopSample] [Epoch]:
+    /// polar motion W = R2(xp) R1(yp) first (small angles), then the Earth rotation R3(-ERA).
+    pub fn itrs_to_cirs(&self, position: Vector3, velocity: Vector3) -> (Vector3, Vector3) {
+        let (xp, yp) = self.pole_rad();
+        let w = |(x, y, z): Vector3| (x - xp * z, y + yp * z, xp * x - yp * y + z);
+
+        let (r, v) = (w(position), w(velocity));
+
+        // Earth rotation, seen from the inertial frame
+        let omega = self.angular_velocity_rad_s();
+        let v = (v.0 - omega * r.1, v.1 + omega * r.0, v.2);
+
+        let (sin, cos) = self.earth_rotation_angle_rad().sin_cos();
+        let r3 = |(x, y, z): Vector3| (cos * x - sin * y, sin * x + cos * y, z);
+
+        (r3(r), r3(v))
+    }
+
+    /// Rotates this Celestial Intermediate Reference System state (position and velocity,
+    /// any unit) to the Earth fixed ITRS, at this [EopSample] [Epoch].
+    /// Inverse of [Self::itrs_to_cirs].
+    pub fn cirs_to_itrs(&self, position: Vector3, velocity: Vector3) -> (Vector3, Vector3) {
+        let (sin, cos) = self.earth_rotation_angle_rad().sin_cos();
+        let r3 = |(x, y, z): Vector3| (cos * x + sin * y, -sin * x + cos * y, z);
+
+        let (r, v) = (r3(position), r3(velocity));
+
+        let omega = self.angular_velocity_rad_s();
+        let v = (v.0 + omega * r.1, v.1 - omega * r.0, v.2);
+
+        let (xp, yp) = self.pole_rad();
+        let w_t = |(x, y, z): Vector3| (x + xp * z, y - yp * z, -xp * x + yp * y + z);
+
+        (w_t(r), w_t(v))
+    }
+}
+
+/// [EarthOrientation] gathers IERS Earth Orientation Parameters
+/// from one or several files, sorted in chronological order.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "snapshot", derive(Serialize, Deserialize))]
+pub struct EarthOrientation {
+    /// [EopFormat] of the first file
+    pub format: EopFormat,
+    /// All [EopSample]s, in chronological order
+    pub samples: Vec<EopSample>,
+}
+
+impl EarthOrientation {
+    /// Parses [EarthOrientation] from this readable file
+    /// (finals2000A, C04 or IGS ERP).
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, EopError> {
+        let fd = File::open(path)?;
+        Self::parse(BufReader::new(fd))
+    }
+
+    /// Parses [EarthOrientation] from this Gzip compressed file
+    #[cfg(feature = "flate2")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "flate2")))]
+    pub fn from_gzip_file<P: AsRef<Path>>(path: P) -> Result<Self, EopError> {
+        let fd = File::open(path)?;
+        Self::parse(BufReader::new(GzDecoder::new(fd)))
+    }
+
+    /// Parses [EarthOrientation] from this readable stream
+    pub fn parse<R: BufRead>(reader: R) -> Result<Self, EopError> {
+        let lines = reader.lines().collect::<Result<Vec<_>, _>>()?;
+
+        let format = lines
+            .iter()
+            .take(MAX_SNIFFED_LINES)
+            .enumerate()
+            .find_map(|(nth, line)| EopFormat::sniff(nth, line))
+            .ok_or(EopError::UnknownFormat)?;
+
+        let mut samples = lines
+            .iter()
+            .filter_map(|line| format.parse(line))
+            .collect::<Vec<_>>();
+
+        if samples.is_empty() {
+            return Err(EopError::NoValues);
+        }
+
+        samples.sort_by(|a, b| a.epoch.cmp(&b.epoch));
+        samples.dedup_by(|a, b| a.epoch == b.epoch);
+
+        Ok(Self { format, samples })
+    }
+
+    /// Merges rhs [EarthOrientation] into mutable Self.
+    /// Observed values are preferred over predictions.
+    pub fn merge_mut(&mut self, rhs: &Self) {
+        for sample in rhs.samples.iter() {
+            match self
+                .samples
+                .binary_search_by(|probe| probe.epoch.cmp(&sample.epoch))
+            {
+                Ok(index) => {
+                    if self.samples[index].predicted && !sample.predicted {
+                        self.samples[index] = *sample;
+                    }
+                }
+                Err(index) => self.samples.insert(index, *sample),
+            }
+        }
+    }
+
+    /// Returns first and last [Epoch] described
+    pub fn time_frame(&self) -> Option<(Epoch, Epoch)> {
+        Some((self.samples.first()?.epoch, self.samples.last()?.epoch))
+    }
+
+    /// Interpolates the Earth orientation at this [Epoch], which
+    /// must lie within the time frame. UT1-UTC is interpolated continuously
+    /// across leap seconds.
+    pub fn interpolate(&self, t: Epoch) -> Option<EopSample> {
+        let index = self.samples.partition_point(|sample| sample.epoch <= t);
+
+        if index == 0 {
+            return None;
+        }
+
+        let before = self.samples[index - 1];
+
+        if before.epoch == t {
+            return Some(before);
+        }
+
+        let after = self.samples.get(index)?;
+
+        let dt = (after.epoch - before.epoch).to_seconds();
+        let alpha = (t - before.epoch).to_seconds() / dt;
+
+        let lerp = |a: f64, b: f64| a + alpha * (b - a);
+
+        // UT1-TAI is continuous, UT1-UTC is not
+        let leap = |t: Epoch| t.leap_seconds(true).unwrap_or(0.0);
+
+        let ut1_tai = lerp(
+            before.ut1_utc_s - leap(before.epoch),
+            after.ut1_utc_s - leap(after.epoch),
+        );
+
+        Some(EopSample {
+            epoch: t,
+            x_arcsec: lerp(before.x_arcsec, after.x_arcsec),
+            y_arcsec: lerp(before.y_arcsec, after.y_arcsec),
+            ut1_utc_s: ut1_tai + leap(t),
+            lod_s: match (before.lod_s, after.lod_s) {
+                (Some(a), Some(b)) => Some(lerp(a, b)),
+                _ => None,
+            },
+            predicted: before.predicted || after.predicted,
+        })
+    }
+
+    /// Returns UT1 [Epoch] corresponding to this (UTC) [Epoch], when it can be interpolated.
+    pub fn ut1(&self, t: Epoch) -> Option<Epoch> {
+        let sample = self.interpolate(t)?;
+        Some(t + Duration::from_seconds(sample.ut1_utc_s))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{EarthOrientation, EopFormat, EopSample, ERA_RATE};
+    use hifitime::prelude::{Duration, Epoch, TimeScale};
+    use std::f64::consts::{PI, TAU};
+
+    const FINALS: &str = "\
+20 1 1 58849.00 I  0.076577 0.000029  0.282336 0.000029  I-0.1772547 0.0000091  0.3986 0.0069  I    -0.124    0.160    -0.209    0.160
+20 1 2 58850.00 I  0.074812 0.000029  0.282858 0.000029  I-0.1775776 0.0000098  0.2507 0.0071  I    -0.116    0.160    -0.214    0.160
+20 1 3 58851.00 P  0.073000 0.000029  0.283400 0.000029  P-0.1778000 0.0000098  0.2000 0.0071  P    -0.116    0.160    -0.214    0.160
+";
+
+    const C04: &str = "\
+# EOP (IERS) 20 C04 TIME SERIES
+# YR  MM  DD  HH       MJD        x(\")        y(\")  UT1-UTC(s)       dX(\")      dY(\")       xrt(\"/d)    yrt(\"/d)      LOD(s)
+2020  01  01  00  58849.00   0.076577   0.282336  -0.1772547   0.000123  -0.000087   0.000000   0.000000   0.0003986
+2020  01  02  00  58850.00   0.074812   0.282858  -0.1775776   0.000125  -0.000084   0.000000   0.000000   0.0002507
+";
+
+    const ERP: &str = "\
+version 2
+  MJD     Xpole   Ypole  UT1-UTC    LOD  Xsig  Ysig   UTsig LODsig  Nr Nf Nt     Xrt    Yrt  Xrtsig Yrtsig
+        (10**-6\")       (0.1 usec)    (10**-6\")     (0.1 usec)              (10**-6\"/d)    (10**-6\"/d)
+58849.50   76577  282336 -1772547   3986     10     10     10     10  0  0  0       0      0      0      0
+58850.50   74812  282858 -1775776   2507     10     10     10     10  0  0  0       0      0      0      0
+";
+
+    #[test]
+    fn eop_formats() {
+        for (content, format, first_mjd) in [
+            (FINALS, EopFormat::Finals2000A, 58849.0),
+            (C04, EopFormat::C04, 58849.0),
+            (ERP, EopFormat::IgsErp, 58849.5),
+        ] {
+            let eop = EarthOrientation::parse(content.as_bytes()).unwrap();
+
+            assert_eq!(eop.format, format);
+            assert!(eop.samples.len() >= 2);
+
+            let first = eop.samples[0];
+            assert_eq!(first.epoch, Epoch::from_mjd_utc(first_mjd));
+            assert!((first.x_arcsec - 0.076577).abs() < 1E-9);
+            assert!((first.y_arcsec - 0.282336).abs() < 1E-9);
+            assert!((first.ut1_utc_s + 0.1772547).abs() < 1E-9);
+            assert!((first.lod_s.unwrap() - 0.0003986).abs() < 1E-9);
+        }
+
+        let eop = EarthOrientation::parse(FINALS.as_bytes()).unwrap();
+        assert!(!eop.samples[1].predicted);
+        assert!(eop.samples[2].predicted);
+    }
+
+    #[test]
+    fn eop_interpolation() {
+        let eop = EarthOrientation::parse(C04.as_bytes()).unwrap();
+
+        let t = Epoch::from_mjd_utc(58849.5);
+        let sample = eop.interpolate(t).unwrap();
+
+        assert_eq!(sample.epoch, t);
+        assert!((sample.x_arcsec - (0.076577 + 0.074812) / 2.0).abs() < 1E-9);
+        assert!((sample.ut1_utc_s - (-0.1772547 - 0.1775776) / 2.0).abs() < 1E-9);
+
+        // out of time frame
+        assert!(eop.interpolate(Epoch::from_mjd_utc(58848.0)).is_none());
+        assert!(eop.interpolate(Epoch::from_mjd_utc(58851.0)).is_none());
+
+        // predictions are replaced by observations
+        let mut predicted = EarthOrientation::parse(FINALS.as_bytes()).unwrap();
+        let mut observed = predicted.clone();
+        observed.samples[2].predicted = false;
+        observed.samples[2].x_arcsec = 0.0;
+
+        predicted.merge_mut(&observed);
+        assert!(!predicted.samples[2].predicted);
+        assert_eq!(predicted.samples[2].x_arcsec, 0.0);
+    }
+
+    #[test]
+    fn earth_rotation_angle() {
+        // J2000.0 (UT1)
+        let t = Epoch::from_gregorian(2000, 1, 1, 12, 0, 0, 0, TimeScale::UTC);
+
+        let mut sample = EopSample::nominal(t);
+        sample.ut1_utc_s = 0.3;
+
+        let expected = 280.460_618_375_04 + 0.3 * 360.985_612_288_808 / 86_400.0;
+        assert!((sample.earth_rotation_angle_rad().to_degrees() - expected).abs() < 1E-9);
+
+        // one UT1 day later: 360.9856° further
+        sample.epoch = t + Duration::from_days(1.0);
+        let expected = (expected + 360.985_612_288_808) % 360.0;
+        assert!((sample.earth_rotation_angle_rad().to_degrees() - expected).abs() < 1E-8);
+    }
+
+    #[test]
+    fn itrs_cirs_rotation() {
+        let t = Epoch::from_mjd_utc(58849.25);
+
+        let sample = EopSample {
+            epoch: t,
+            x_arcsec: 0.076577,
+            y_arcsec: 0.282336,
+            ut1_utc_s: -0.1772547,
+            lod_s: Some(0.0003986),
+            predicted: false,
+        };
+
+        // Earth fixed station: moves at ~0.46 km/s in the inertial frame, at the equator
+        let station = ((6378.137, 0.0, 0.0), (0.0, 0.0, 0.0));
+        let (r, v) = sample.itrs_to_cirs(station.0, station.1);
+
+        let radius = (r.0.powi(2) + r.1.powi(2) + r.2.powi(2)).sqrt();
+        let speed = (v.0.powi(2) + v.1.powi(2) + v.2.powi(2)).sqrt();
+
+        assert!((radius - 6378.137).abs() < 1E-6);
+        assert!((speed - 0.4651).abs() < 1E-3);
+
+        // round trip
+        let (r, v) = sample.cirs_to_itrs(r, v);
+
+        for (a, b) in [
+            (r.0, station.0 .0),
+            (r.1, station.0 .1),
+            (r.2, station.0 .2),
+        ] {
+            assert!((a - b).abs() < 1E-6);
+        }
+
+        for value in [v.0, v.1, v.2] {
+            assert!(value.abs() < 1E-9);
+        }
+
+        // UT1-UTC: rotation about the pole, by the Earth rotation angle difference
+        let nominal = EopSample::nominal(t);
+        let delta = sample.earth_rotation_angle_rad() - nominal.earth_rotation_angle_rad();
+        let delta = (delta + PI).rem_euclid(TAU) - PI;
+
+        let expected = -0.1772547 * ERA_RATE * TAU / 86_400.0;
+        assert!((delta - expected).abs() < 1E-10);
+    }
+}
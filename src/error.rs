@@ -3,27 +3,89 @@ use thiserror::Error;
 
 use qc_traits::MergeError;
 
-use rinex::error::ParsingError as RinexParsingError;
+use rinex::error::{FormattingError as RinexFormattingError, ParsingError as RinexParsingError};
+
+use crate::{context::QcMergeConflict, eop::EopError, sinex::SinexError};
+
+#[cfg(feature = "navigation")]
+use crate::prelude::{ProductType, TimeScale};
+
+#[cfg(feature = "navigation")]
+use qc_traits::TimeCorrectionError;
 
 #[cfg(feature = "sp3")]
-use sp3::prelude::Error as SP3Error;
+use sp3::prelude::{Error as SP3Error, FormattingError as SP3FormattingError};
+
+#[cfg(any(feature = "rtcm", feature = "ubx", feature = "binex"))]
+use crate::stream::StreamError;
 
 /// Context Error
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("non supported file format")]
     NonSupportedFileFormat,
+    #[error("binary (non UTF-8) content")]
+    BinaryContent,
+    #[error("corrupt gzip stream")]
+    CorruptGzip,
     #[error("failed to determine filename")]
     FileNameDetermination,
+    #[error("exported products would share file name \"{0}\"")]
+    FileNameCollision(String),
     #[error("failed to extend context")]
     Merge(#[from] MergeError),
+    #[error("merge conflict: {0}")]
+    MergeConflict(QcMergeConflict),
     #[error("unknown / non supported product type")]
     UnknownProductType,
     #[error("invalid nav filter")]
     InvalidNavFilter,
+    #[error("invalid preprocessing step \"{0}\"")]
+    InvalidPipelineStep(String),
+    #[error("invalid time corrections table (line {0})")]
+    TimeCorrectionsTable(usize),
+    #[cfg(feature = "navigation")]
+    #[error("time correction error: {0:?}")]
+    TimeCorrection(TimeCorrectionError),
+    #[cfg(feature = "navigation")]
+    #[error("no time correction to express {0} in {1}")]
+    NoTimeCorrection(ProductType, TimeScale),
+    #[error("I/O error: {0}")]
+    IO(#[from] std::io::Error),
+    #[error("file is not loaded in this context")]
+    FileNotLoaded,
+    #[error("file is already loaded in this context")]
+    FileAlreadyLoaded,
+    #[error("product type mismatch")]
+    ProductTypeMismatch,
+    #[error("no observations loaded for station \"{0}\"")]
+    UnknownStation(String),
+    #[error("JSON error: {0}")]
+    JSON(#[from] serde_json::Error),
     #[error("RINEX parsing error: {0}")]
     RinexParsing(#[from] RinexParsingError),
+    #[error("RINEX formatting error: {0}")]
+    RinexFormatting(#[from] RinexFormattingError),
     #[cfg(feature = "sp3")]
     #[error("SP3 parsing error: {0}")]
     SP3Parsing(#[from] SP3Error),
+    #[cfg(feature = "sp3")]
+    #[error("SP3 formatting error: {0}")]
+    SP3Formatting(#[from] SP3FormattingError),
+    #[error("SINEX parsing error: {0}")]
+    SinexParsing(#[from] SinexError),
+    #[error("EOP parsing error: {0}")]
+    EopParsing(#[from] EopError),
+    #[cfg(any(feature = "rtcm", feature = "ubx", feature = "binex"))]
+    #[error("stream decoding error: {0}")]
+    StreamDecoding(#[from] StreamError),
+    #[cfg(feature = "snapshot")]
+    #[error("snapshot encoding error: {0}")]
+    SnapshotEncoding(#[from] bincode::Error),
+    #[cfg(feature = "snapshot")]
+    #[error("not a QcContext snapshot")]
+    NotASnapshot,
+    #[cfg(feature = "snapshot")]
+    #[error("outdated snapshot (revision {0}, gnss-qc v{1})")]
+    OutdatedSnapshot(u32, String),
 }
@@ -25,8 +25,19 @@ extern crate gnss_rs as gnss;
 
 mod cfg;
 mod context;
+mod eop;
+mod pipeline;
 mod product;
 mod report;
+mod scope;
+mod sinex;
+
+#[cfg(any(feature = "rtcm", feature = "ubx", feature = "binex"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "rtcm", feature = "ubx", feature = "binex")))
+)]
+mod stream;
 
 #[cfg(feature = "navigation")]
 #[cfg_attr(docsrs, doc(cfg(feature = "navigation")))]
@@ -41,17 +52,38 @@ mod tests;
 pub mod prelude {
     pub use crate::{
         cfg::{QcConfig, QcReportType},
-        context::QcContext,
+        context::{
+            QcBatchOptions, QcContext, QcDirectoryOptions, QcExportOptions, QcFileProvenance,
+            QcLoadingOutcome, QcMergeConflict, QcMergeConflictType, QcMergePolicies, QcMergePolicy,
+            QcRepairSummary, QcSlidingWindow, QcWindowSummary,
+        },
+        eop::{EarthOrientation, EopFormat, EopSample},
         error::Error,
+        pipeline::{QcPipeline, QcPipelineStep},
         product::ProductType,
         report::{QcExtraPage, QcReport},
+        scope::{QcScope, QcScopeType},
+        sinex::{
+            BiasSolution, BiasSolutions, BiasType, BiasUnit, StationCoordinates, StationSolutions,
+        },
     };
 
     pub use gnss::prelude::{Constellation, COSPAR, SV};
     pub use hifitime::prelude::{Duration, Epoch, TimeScale};
 
+    #[cfg(any(feature = "rtcm", feature = "ubx", feature = "binex"))]
+    pub use crate::stream::StreamError;
+
     #[cfg(feature = "navigation")]
-    pub use crate::navigation::{NavFilter, NavFilterType, ReferenceEcefPosition};
+    pub use crate::context::{
+        QcOrbitInterpolator, QcOrbitOptions, QcOrbitSource, QcSatelliteState, QcTimeCorrection,
+        QcTimeCorrectionSource, QcTimeCorrectionsDB,
+    };
+
+    #[cfg(feature = "navigation")]
+    pub use crate::navigation::{
+        NavFilter, NavFilterType, ReferenceEcefPosition, ReferencePositionSource,
+    };
 
     pub use crate::plot::{Marker, MarkerSymbol, Mode, Plot};
 
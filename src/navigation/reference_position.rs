@@ -1,21 +1,92 @@
-use crate::prelude::{Epoch, Frame, Orbit};
+use crate::prelude::{Epoch, Frame, Orbit, StationCoordinates};
+
+/// [ReferencePositionSource] describes where a [ReferenceEcefPosition] comes from
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReferencePositionSource {
+    /// Defined by the user
+    User,
+    /// Picked from the Observation RINEX header
+    RinexHeader,
+    /// Station coordinates solution of a SINEX file
+    SINEX {
+        /// Production agency
+        agency: Option<String>,
+        /// 4 character site code
+        code: String,
+        /// DOMES number
+        domes: Option<String>,
+    },
+}
+
+impl std::fmt::Display for ReferencePositionSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::User => write!(f, "User defined"),
+            Self::RinexHeader => write!(f, "RINEX header"),
+            Self::SINEX {
+                agency,
+                code,
+                domes,
+            } => {
+                write!(f, "SINEX {}", code)?;
+                if let Some(domes) = domes {
+                    write!(f, " ({})", domes)?;
+                }
+                if let Some(agency) = agency {
+                    write!(f, " by {}", agency)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
 
 pub struct ReferenceEcefPosition {
     /// Ecef coordinates in meters
     pub ecef_m: (f64, f64, f64),
+    /// Ecef velocity in meters per year, when known
+    pub velocity_m_yr: Option<(f64, f64, f64)>,
+    /// [ReferencePositionSource]
+    pub source: ReferencePositionSource,
 }
 
 impl ReferenceEcefPosition {
     /// Define new [ReferenceEcefPosition] from ECEF coordinates
     pub fn new(ecef_m: (f64, f64, f64)) -> Self {
-        Self { ecef_m }
+        Self {
+            ecef_m,
+            velocity_m_yr: None,
+            source: ReferencePositionSource::User,
+        }
     }
 
     /// Create a new [ReferenceEcefPosition] from an [Orbit]
     pub fn from_orbit(orbit: &Orbit) -> Self {
         let posvel_m = orbit.to_cartesian_pos_vel() * 1.0E3;
         let ecef_m = (posvel_m[0], posvel_m[1], posvel_m[2]);
-        Self { ecef_m }
+        Self {
+            ecef_m,
+            velocity_m_yr: None,
+            source: ReferencePositionSource::RinexHeader,
+        }
+    }
+
+    /// Create a new [ReferenceEcefPosition] from SINEX [StationCoordinates],
+    /// propagated to this [Epoch] using the site velocity.
+    pub fn from_station_coordinates(
+        station: &StationCoordinates,
+        agency: Option<String>,
+        t: Epoch,
+    ) -> Self {
+        Self {
+            ecef_m: station.ecef_m_at(t),
+            velocity_m_yr: station.velocity_m_yr,
+            source: ReferencePositionSource::SINEX {
+                agency,
+                code: station.code.clone(),
+                domes: station.domes.clone(),
+            },
+        }
     }
 
     /// Express this [ReferenceEcefPosition] as an [Orbit]
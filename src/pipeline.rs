@@ -0,0 +1,151 @@
+//! Declarative preprocessing pipelines
+use std::{fmt::Display, str::FromStr};
+
+use maud::{html, Markup, Render};
+use serde::{Deserialize, Serialize};
+
+use qc_traits::{Filter, Repair};
+
+use crate::error::Error;
+
+#[cfg(feature = "navigation")]
+use crate::navigation::NavFilter;
+
+/// One [QcPipelineStep], described the way it is written
+/// in configuration files, so pipelines can be stored and replayed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QcPipelineStep {
+    /// [Filter] (mask or decimation), described with the [Filter] syntax.
+    /// For example: "GPS", "G08,G09", ">2020-06-25T12:00:00 GPST", "decim:4".
+    Filter(String),
+    /// [Repair] operation. Only "zero" (zero values repair) is supported.
+    Repair(String),
+    /// [NavFilter], described with the [NavFilter] syntax.
+    /// For example: "healthy", "gps,gal:healthy".
+    #[cfg(feature = "navigation")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "navigation")))]
+    NavFilter(String),
+}
+
+impl Display for QcPipelineStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Filter(filter) => write!(f, "filter: {}", filter),
+            Self::Repair(repair) => write!(f, "repair: {}", repair),
+            #[cfg(feature = "navigation")]
+            Self::NavFilter(filter) => write!(f, "nav filter: {}", filter),
+        }
+    }
+}
+
+/// Parsed [QcPipelineStep], ready to be applied
+pub(crate) enum QcPipelineOperation {
+    Filter(Filter),
+    Repair(Repair),
+    #[cfg(feature = "navigation")]
+    NavFilter(NavFilter),
+}
+
+impl QcPipelineStep {
+    /// Parses this [QcPipelineStep]
+    pub(crate) fn operation(&self) -> Result<QcPipelineOperation, Error> {
+        match self {
+            Self::Filter(filter) => Filter::from_str(filter)
+                .map(QcPipelineOperation::Filter)
+                .map_err(|_| Error::InvalidPipelineStep(self.to_string())),
+            Self::Repair(repair) => match repair.trim().to_lowercase().as_str() {
+                "zero" => Ok(QcPipelineOperation::Repair(Repair::Zero)),
+                _ => Err(Error::InvalidPipelineStep(self.to_string())),
+            },
+            #[cfg(feature = "navigation")]
+            Self::NavFilter(filter) => NavFilter::from_str(filter)
+                .map(QcPipelineOperation::NavFilter)
+                .map_err(|_| Error::InvalidPipelineStep(self.to_string())),
+        }
+    }
+}
+
+/// [QcPipeline] is an ordered list of [QcPipelineStep]s, applied in a
+/// single call with [QcContext::apply_pipeline](crate::prelude::QcContext::apply_pipeline).
+/// It is usually part of the [QcConfig](crate::prelude::QcConfig):
+/// ```
+/// use gnss_qc::prelude::{QcConfig, QcPipelineStep};
+///
+/// let cfg: QcConfig = serde_json::from_str(
+///     r#"{
+///         "pipeline": {
+///             "steps": [
+///                 { "filter": "GPS" },
+///                 { "filter": "decim:2" },
+///                 { "repair": "zero" }
+///             ]
+///         }
+///     }"#,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(cfg.pipeline.steps.len(), 3);
+/// assert_eq!(cfg.pipeline.steps[1], QcPipelineStep::Filter("decim:2".to_string()));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QcPipeline {
+    /// Ordered [QcPipelineStep]s
+    #[serde(default)]
+    pub steps: Vec<QcPipelineStep>,
+}
+
+impl QcPipeline {
+    /// Copies and returns [QcPipeline] with one more [QcPipelineStep]
+    pub fn with_step(&self, step: QcPipelineStep) -> Self {
+        let mut s = self.clone();
+        s.steps.push(step);
+        s
+    }
+
+    /// Verifies that all [QcPipelineStep]s may be parsed
+    pub fn validate(&self) -> Result<(), Error> {
+        for step in self.steps.iter() {
+            step.operation()?;
+        }
+        Ok(())
+    }
+}
+
+impl Render for QcPipeline {
+    fn render(&self) -> Markup {
+        html! {
+            ol {
+                @for step in self.steps.iter() {
+                    li {
+                        (step.to_string())
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{QcPipeline, QcPipelineStep};
+
+    #[test]
+    fn pipeline_validation() {
+        let pipeline = QcPipeline::default()
+            .with_step(QcPipelineStep::Filter("GPS".to_string()))
+            .with_step(QcPipelineStep::Repair("zero".to_string()));
+
+        assert!(pipeline.validate().is_ok());
+
+        let pipeline = pipeline.with_step(QcPipelineStep::Repair("unknown".to_string()));
+        assert!(pipeline.validate().is_err());
+
+        let serialized = serde_json::to_string(&pipeline).unwrap();
+
+        assert_eq!(
+            serialized,
+            r#"{"steps":[{"filter":"GPS"},{"repair":"zero"},{"repair":"unknown"}]}"#
+        );
+    }
+}
@@ -2,7 +2,9 @@
 use crate::error::Error;
 use rinex::prelude::RinexType;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum ProductType {
     /// GNSS carrier signal observation in the form
     /// of Observation RINEX data.
@@ -11,8 +13,9 @@ pub enum ProductType {
     /// Meteo sensors data wrapped as Meteo RINEX files.
     MeteoObservation,
 
-    // /// DORIS measurements wrapped as special RINEX observation file.
-    // DORIS,
+    /// DORIS measurements wrapped as special RINEX observation file.
+    DORIS,
+
     /// Broadcast Navigation message as contained in
     /// Navigation RINEX files.
     BroadcastNavigation,
@@ -23,20 +26,45 @@ pub enum ProductType {
     /// Antenna calibration information wrapped in ANTEX special RINEX files.
     ANTEX,
 
-    // /// Precise Ionosphere state wrapped in IONEX special RINEX files.
-    // IONEX,
+    /// Precise Ionosphere state wrapped in IONEX special RINEX files.
+    IONEX,
+
+    /// Differential (DSB) and Observable Specific (OSB) code biases,
+    /// wrapped in Bias-SINEX files.
+    CodeBias,
+
+    /// IERS Earth Orientation Parameters (polar motion, UT1-UTC),
+    /// wrapped in finals2000A, C04 or IGS ERP files.
+    EarthOrientation,
+
+    /// Station coordinates (and velocities) solutions,
+    /// wrapped in SINEX files.
+    StationCoordinates,
+
     #[cfg(feature = "sp3")]
     #[cfg_attr(docsrs, doc(cfg(feature = "sp3")))]
     /// High precision clock data wrapped in SP3 files.
     HighPrecisionOrbit,
 }
 
+impl ProductType {
+    /// Returns true if this [ProductType] is specific to one station (or marker).
+    /// Such products are stored per station in the [QcContext](crate::prelude::QcContext)
+    /// and never merged with data from other stations.
+    pub fn is_station_dependent(&self) -> bool {
+        matches!(self, Self::Observation | Self::MeteoObservation)
+    }
+}
+
 impl std::fmt::Display for ProductType {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             Self::ANTEX => write!(f, "ANTEX"),
-            // Self::IONEX => write!(f, "IONEX"),
-            // Self::DORIS => write!(f, "DORIS RINEX"),
+            Self::IONEX => write!(f, "IONEX"),
+            Self::CodeBias => write!(f, "Code Biases (Bias-SINEX)"),
+            Self::EarthOrientation => write!(f, "Earth Orientation Parameters (EOP)"),
+            Self::StationCoordinates => write!(f, "Station Coordinates (SINEX)"),
+            Self::DORIS => write!(f, "DORIS RINEX"),
             Self::Observation => write!(f, "Observation"),
             Self::MeteoObservation => write!(f, "Meteo"),
             Self::HighPrecisionClock => write!(f, "High Precision Clock"),
@@ -54,8 +82,11 @@ impl std::str::FromStr for ProductType {
         let lowered = trimmed.to_lowercase();
         match lowered.as_str() {
             "atx" | "antex" => Ok(Self::ANTEX),
-            // "ionex" => Ok(Self::IONEX),
-            // "doris" => Ok(Self::DORIS),
+            "inx" | "ionex" => Ok(Self::IONEX),
+            "bia" | "bias" | "dcb" | "osb" => Ok(Self::CodeBias),
+            "eop" | "erp" | "finals" | "c04" => Ok(Self::EarthOrientation),
+            "snx" | "sinex" | "crd" => Ok(Self::StationCoordinates),
+            "doris" => Ok(Self::DORIS),
             "obs" | "observation" => Ok(Self::Observation),
             "met" | "meteo" => Ok(Self::MeteoObservation),
             "nav" | "brdc" | "navigation" => Ok(Self::BroadcastNavigation),
@@ -74,9 +105,9 @@ impl From<RinexType> for ProductType {
             RinexType::NavigationData => Self::BroadcastNavigation,
             RinexType::MeteoData => Self::MeteoObservation,
             RinexType::ClockData => Self::HighPrecisionClock,
-            // RinexType::IonosphereMaps => Self::IONEX,
+            RinexType::IonosphereMaps => Self::IONEX,
             RinexType::AntennaData => Self::ANTEX,
-            // RinexType::DORIS => Self::DORIS,
+            RinexType::DORIS => Self::DORIS,
         }
     }
 }
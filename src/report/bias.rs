@@ -0,0 +1,343 @@
+use itertools::Itertools;
+use maud::{html, Markup, Render};
+use std::str::FromStr;
+
+use rinex::prelude::{Constellation, Epoch, Observable, Rinex, SV};
+
+use crate::prelude::{BiasSolutions, BiasType};
+
+/// Broadcast group delay we may compare to the Bias-SINEX DCBs
+pub(crate) struct BroadcastGroupDelay {
+    pub constellation: Constellation,
+    /// DCB(obs1 - obs2) the group delay derives from
+    pub obs1: &'static str,
+    pub obs2: &'static str,
+    pub label: &'static str,
+    /// First and second frequency [MHz] of the dual frequency combination
+    /// the group delay refers to. None when the group delay is the DCB itself.
+    pub frequencies_mhz: Option<(f64, f64)>,
+}
+
+impl BroadcastGroupDelay {
+    /// DCB to broadcast group delay scaling:
+    /// TGD = DCB(obs1 - obs2) / (1 - (f1/f2)²)
+    pub fn scaling(&self) -> f64 {
+        match self.frequencies_mhz {
+            Some((f1_mhz, f2_mhz)) => 1.0 / (1.0 - (f1_mhz / f2_mhz).powi(2)),
+            None => 1.0,
+        }
+    }
+}
+
+pub(crate) const BROADCAST_GROUP_DELAYS: [BroadcastGroupDelay; 3] = [
+    BroadcastGroupDelay {
+        constellation: Constellation::GPS,
+        obs1: "C1W",
+        obs2: "C2W",
+        label: "TGD",
+        frequencies_mhz: Some((1575.42, 1227.60)),
+    },
+    BroadcastGroupDelay {
+        constellation: Constellation::Galileo,
+        obs1: "C1C",
+        obs2: "C5Q",
+        label: "BGD E1/E5a",
+        frequencies_mhz: Some((1575.42, 1176.45)),
+    },
+    // BeiDou TGD1 is directly the B1I/B3I DCB
+    BroadcastGroupDelay {
+        constellation: Constellation::BeiDou,
+        obs1: "C2I",
+        obs2: "C6I",
+        label: "TGD1",
+        frequencies_mhz: None,
+    },
+];
+
+/// One [SV] comparison between Bias-SINEX and broadcast group delay
+pub(crate) struct GroupDelayComparison {
+    pub sv: SV,
+    pub label: String,
+    pub signals: String,
+    /// DCB [ns], averaged over the compared ephemerides
+    pub dcb_ns: f64,
+    /// Group delay derived from DCB [ns]
+    pub derived_ns: f64,
+    /// Broadcast group delay [ns], averaged over the ephemerides
+    /// published within the DCB validity period
+    pub broadcast_ns: Option<f64>,
+    /// Number of compared ephemerides
+    pub ephemerides: usize,
+}
+
+/// Returns broadcast group delays of this [SV], in nanoseconds,
+/// at the [Epoch] of each ephemeris.
+fn broadcast_group_delays(brdc: &Rinex, sv: SV) -> Vec<(Epoch, f64)> {
+    let Some(record) = brdc.record.as_nav() else {
+        return Vec::new();
+    };
+
+    record
+        .iter()
+        .filter(|(k, _)| k.sv == sv)
+        .filter_map(|(k, frame)| {
+            let tgd = frame.as_ephemeris()?.tgd()?;
+            Some((k.epoch, tgd.to_seconds() * 1.0E9))
+        })
+        .collect()
+}
+
+/// Bias-SINEX report
+pub struct BiasReport {
+    agency: Option<String>,
+    time_frame: (Option<Epoch>, Option<Epoch>),
+    satellites: Vec<SV>,
+    nb_dsb: usize,
+    nb_osb: usize,
+    pub(crate) comparisons: Vec<GroupDelayComparison>,
+}
+
+impl BiasReport {
+    pub fn new(bias: &BiasSolutions, brdc: Option<&Rinex>) -> Self {
+        let satellites = bias.sv_iter().collect::<Vec<_>>();
+
+        let mut comparisons = Vec::new();
+
+        for sv in satellites.iter() {
+            for group_delay in BROADCAST_GROUP_DELAYS.iter() {
+                if sv.constellation != group_delay.constellation {
+                    continue;
+                }
+
+                let (obs1, obs2) = match (
+                    Observable::from_str(group_delay.obs1),
+                    Observable::from_str(group_delay.obs2),
+                ) {
+                    (Ok(obs1), Ok(obs2)) => (obs1, obs2),
+                    _ => continue,
+                };
+
+                // each ephemeris is compared to the DCB valid at its epoch
+                let matched = brdc
+                    .map(|brdc| broadcast_group_delays(brdc, *sv))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|(t, tgd_ns)| {
+                        let dcb_ns = bias.differential_code_bias(*sv, &obs1, &obs2, t)?;
+                        Some((dcb_ns, tgd_ns))
+                    })
+                    .collect::<Vec<_>>();
+
+                let (dcb_ns, broadcast_ns) = if matched.is_empty() {
+                    // nothing to compare to: DCB at the start of the validity period
+                    let Some(t) = bias.time_frame().0 else {
+                        continue;
+                    };
+
+                    let Some(dcb_ns) = bias.differential_code_bias(*sv, &obs1, &obs2, t) else {
+                        continue;
+                    };
+
+                    (dcb_ns, None)
+                } else {
+                    let n = matched.len() as f64;
+                    (
+                        matched.iter().map(|(dcb_ns, _)| dcb_ns).sum::<f64>() / n,
+                        Some(matched.iter().map(|(_, tgd_ns)| tgd_ns).sum::<f64>() / n),
+                    )
+                };
+
+                comparisons.push(GroupDelayComparison {
+                    sv: *sv,
+                    label: group_delay.label.to_string(),
+                    signals: format!("{}-{}", obs1, obs2),
+                    dcb_ns,
+                    derived_ns: dcb_ns * group_delay.scaling(),
+                    broadcast_ns,
+                    ephemerides: matched.len(),
+                });
+            }
+        }
+
+        Self {
+            agency: bias.agency.clone(),
+            time_frame: bias.time_frame(),
+            nb_dsb: bias
+                .solutions
+                .iter()
+                .filter(|sol| sol.bias_type == BiasType::DSB)
+                .count(),
+            nb_osb: bias
+                .solutions
+                .iter()
+                .filter(|sol| sol.bias_type == BiasType::OSB)
+                .count(),
+            satellites,
+            comparisons,
+        }
+    }
+
+    pub fn html_inline_menu_bar(&self) -> Markup {
+        html! {
+            a id="menu:bias" {
+                span class="icon" {
+                    i class="fa-solid fa-scale-unbalanced" {}
+                }
+                "Code Biases"
+            }
+        }
+    }
+}
+
+impl Render for BiasReport {
+    fn render(&self) -> Markup {
+        html! {
+            div class="table-container" {
+                table class="table is-bordered" {
+                    tbody {
+                        @if let Some(agency) = &self.agency {
+                            tr {
+                                th class="is-info" {
+                                    "Agency"
+                                }
+                                td {
+                                    (agency)
+                                }
+                            }
+                        }
+                        @if let Some(start) = self.time_frame.0 {
+                            tr {
+                                th class="is-info" {
+                                    "Start of validity"
+                                }
+                                td {
+                                    (start.to_string())
+                                }
+                            }
+                        }
+                        @if let Some(end) = self.time_frame.1 {
+                            tr {
+                                th class="is-info" {
+                                    "End of validity"
+                                }
+                                td {
+                                    (end.to_string())
+                                }
+                            }
+                        }
+                        tr {
+                            th class="is-info" {
+                                button aria-label="Differential Signal Biases" data-balloon-pos="right" {
+                                    "DSB"
+                                }
+                            }
+                            td {
+                                (self.nb_dsb)
+                            }
+                        }
+                        tr {
+                            th class="is-info" {
+                                button aria-label="Observable Specific Signal Biases" data-balloon-pos="right" {
+                                    "OSB"
+                                }
+                            }
+                            td {
+                                (self.nb_osb)
+                            }
+                        }
+                        tr {
+                            th class="is-info" {
+                                "Satellites"
+                            }
+                            td {
+                                (self.satellites.iter().join(", "))
+                            }
+                        }
+                    }
+                }
+            }
+            @if !self.comparisons.is_empty() {
+                div class="table-container" {
+                    table class="table is-bordered" {
+                        thead {
+                            tr {
+                                th class="is-info" {
+                                    "SV"
+                                }
+                                th class="is-info" {
+                                    "Group delay"
+                                }
+                                th class="is-info" {
+                                    "Signals"
+                                }
+                                th class="is-info" {
+                                    "DCB [ns]"
+                                }
+                                th class="is-info" {
+                                    button aria-label="Group delay, derived from the Bias-SINEX DCB" data-balloon-pos="up" {
+                                        "Derived [ns]"
+                                    }
+                                }
+                                th class="is-info" {
+                                    button aria-label="Group delay, averaged over the broadcast ephemerides within the DCB validity period" data-balloon-pos="up" {
+                                        "Broadcast [ns]"
+                                    }
+                                }
+                                th class="is-info" {
+                                    "Ephemerides"
+                                }
+                                th class="is-info" {
+                                    "Difference [ns]"
+                                }
+                            }
+                        }
+                        tbody {
+                            @for comparison in self.comparisons.iter() {
+                                tr {
+                                    td {
+                                        (comparison.sv.to_string())
+                                    }
+                                    td {
+                                        (comparison.label)
+                                    }
+                                    td {
+                                        (comparison.signals)
+                                    }
+                                    td {
+                                        (format!("{:.3}", comparison.dcb_ns))
+                                    }
+                                    td {
+                                        (format!("{:.3}", comparison.derived_ns))
+                                    }
+                                    @if let Some(broadcast_ns) = comparison.broadcast_ns {
+                                        td {
+                                            (format!("{:.3}", broadcast_ns))
+                                        }
+                                        td {
+                                            (format!("{:.3}", comparison.derived_ns - broadcast_ns))
+                                        }
+                                        td {
+                                            (comparison.ephemerides)
+                                        }
+                                    } @else {
+                                        td {
+                                            button aria-label="No broadcast ephemeris of this satellite within the DCB validity period" data-balloon-pos="up" {
+                                                "Unknown"
+                                            }
+                                        }
+                                        td {
+                                            "Unknown"
+                                        }
+                                        td {
+                                            (comparison.ephemerides)
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use maud::{html, Markup, Render};
+
+use crate::{
+    plot::{MarkerSymbol, Mode, Plot},
+    prelude::{EarthOrientation, EopFormat, Epoch},
+};
+
+/// IERS Earth Orientation Parameters report
+pub struct EopReport {
+    format: EopFormat,
+    time_frame: Option<(Epoch, Epoch)>,
+    nb_samples: usize,
+    nb_predicted: usize,
+    polar_motion_plot: Plot,
+    ut1_plot: Plot,
+    lod_plot: Option<Plot>,
+}
+
+impl EopReport {
+    pub fn new(eop: &EarthOrientation) -> Self {
+        let t = eop
+            .samples
+            .iter()
+            .map(|sample| sample.epoch)
+            .collect::<Vec<_>>();
+
+        let lod = eop
+            .samples
+            .iter()
+            .filter_map(|sample| Some((sample.epoch, sample.lod_s? * 1.0E3)))
+            .collect::<Vec<_>>();
+
+        Self {
+            format: eop.format,
+            time_frame: eop.time_frame(),
+            nb_samples: eop.samples.len(),
+            nb_predicted: eop.samples.iter().filter(|sample| sample.predicted).count(),
+            polar_motion_plot: {
+                let mut plot = Plot::timedomain_plot(
+                    "eop_polar_motion",
+                    "Polar Motion",
+                    "Pole [arcsec]",
+                    true,
+                );
+
+                let x = eop
+                    .samples
+                    .iter()
+                    .map(|sample| sample.x_arcsec)
+                    .collect::<Vec<_>>();
+
+                let y = eop
+                    .samples
+                    .iter()
+                    .map(|sample| sample.y_arcsec)
+                    .collect::<Vec<_>>();
+
+                for (name, values) in [("x", x), ("y", y)] {
+                    let trace = Plot::timedomain_chart(
+                        name,
+                        Mode::LinesMarkers,
+                        MarkerSymbol::Circle,
+                        &t,
+                        values,
+                        true,
+                    );
+                    plot.add_trace(trace);
+                }
+                plot
+            },
+            ut1_plot: {
+                let mut plot = Plot::timedomain_plot("eop_ut1_utc", "UT1-UTC", "UT1-UTC [s]", true);
+
+                let ut1 = eop
+                    .samples
+                    .iter()
+                    .map(|sample| sample.ut1_utc_s)
+                    .collect::<Vec<_>>();
+
+                let trace = Plot::timedomain_chart(
+                    "UT1-UTC",
+                    Mode::LinesMarkers,
+                    MarkerSymbol::Circle,
+                    &t,
+                    ut1,
+                    true,
+                );
+                plot.add_trace(trace);
+                plot
+            },
+            lod_plot: if lod.is_empty() {
+                None
+            } else {
+                let mut plot =
+                    Plot::timedomain_plot("eop_lod", "Length of Day", "Excess LOD [ms]", true);
+
+                let trace = Plot::timedomain_chart(
+                    "LOD",
+                    Mode::LinesMarkers,
+                    MarkerSymbol::Circle,
+                    &lod.iter().map(|(t, _)| *t).collect(),
+                    lod.iter().map(|(_, lod)| *lod).collect(),
+                    true,
+                );
+                plot.add_trace(trace);
+                Some(plot)
+            },
+        }
+    }
+
+    pub fn html_inline_menu_bar(&self) -> Markup {
+        html! {
+            a id="menu:eop" {
+                span class="icon" {
+                    i class="fa-solid fa-earth-europe" {}
+                }
+                "Earth Orientation"
+            }
+        }
+    }
+}
+
+impl Render for EopReport {
+    fn render(&self) -> Markup {
+        html! {
+            div class="table-container" {
+                table class="table is-bordered" {
+                    tbody {
+                        tr {
+                            th class="is-info" {
+                                "Format"
+                            }
+                            td {
+                                (self.format.to_string())
+                            }
+                        }
+                        @if let Some((start, end)) = self.time_frame {
+                            tr {
+                                th class="is-info" {
+                                    "Start"
+                                }
+                                td {
+                                    (start.to_string())
+                                }
+                            }
+                            tr {
+                                th class="is-info" {
+                                    "End"
+                                }
+                                td {
+                                    (end.to_string())
+                                }
+                            }
+                        }
+                        tr {
+                            th class="is-info" {
+                                "Values"
+                            }
+                            td {
+                                (self.nb_samples)
+                            }
+                        }
+                        tr {
+                            th class="is-info" {
+                                button aria-label="Predicted values are replaced by observations when merged" data-balloon-pos="right" {
+                                    "Predictions"
+                                }
+                            }
+                            td {
+                                (self.nb_predicted)
+                            }
+                        }
+                        tr {
+                            th class="is-info" {
+                                "Polar motion"
+                            }
+                            td {
+                                (self.polar_motion_plot.render())
+                            }
+                        }
+                        tr {
+                            th class="is-info" {
+                                "UT1-UTC"
+                            }
+                            td {
+                                (self.ut1_plot.render())
+                            }
+                        }
+                        @if let Some(lod_plot) = &self.lod_plot {
+                            tr {
+                                th class="is-info" {
+                                    "Length of Day"
+                                }
+                                td {
+                                    (lod_plot.render())
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -4,7 +4,7 @@ use maud::{html, Markup, PreEscaped, Render, DOCTYPE};
 use std::collections::HashMap;
 use thiserror::Error;
 
-use crate::prelude::{ProductType, QcConfig, QcContext, QcReportType};
+use crate::prelude::{ProductType, QcConfig, QcContext, QcReportType, QcScope, QcScopeType};
 
 // shared analysis, that may apply to several products
 mod shared;
@@ -15,6 +15,12 @@ use summary::QcSummary;
 mod rinex;
 use rinex::RINEXReport;
 
+pub(crate) mod bias;
+use bias::BiasReport;
+
+mod eop;
+use eop::EopReport;
+
 #[cfg(feature = "navigation")]
 mod orbital;
 
@@ -46,6 +52,10 @@ enum ProductReport {
     #[cfg(feature = "sp3")]
     /// SP3 product report
     SP3(SP3Report),
+    /// Bias-SINEX product report
+    BIAS(BiasReport),
+    /// IERS EOP product report
+    EOP(EopReport),
 }
 
 impl ProductReport {
@@ -54,14 +64,19 @@ impl ProductReport {
             #[cfg(feature = "sp3")]
             Self::SP3(report) => report.html_inline_menu_bar(),
             Self::RINEX(report) => report.html_inline_menu_bar(),
+            Self::BIAS(report) => report.html_inline_menu_bar(),
+            Self::EOP(report) => report.html_inline_menu_bar(),
         }
     }
 }
 
 fn html_id(product: &ProductType) -> &str {
     match product {
-        // ProductType::IONEX => "ionex",
-        // ProductType::DORIS => "doris",
+        ProductType::IONEX => "ionex",
+        ProductType::CodeBias => "bias",
+        ProductType::EarthOrientation => "eop",
+        ProductType::StationCoordinates => "snx",
+        ProductType::DORIS => "doris",
         ProductType::ANTEX => "antex",
         ProductType::Observation => "obs",
         ProductType::BroadcastNavigation => "brdc",
@@ -83,20 +98,20 @@ impl Render for ProductReport {
                         }
                     }
                 }
-                // RINEXReport::Doris(report) => {
-                //     html! {
-                //         div class="section" {
-                //             (report.render())
-                //         }
-                //     }
-                // }
-                // RINEXReport::Ionex(report) => {
-                //     html! {
-                //         div class="section" {
-                //             (report.render())
-                //         }
-                //     }
-                // }
+                RINEXReport::Doris(report) => {
+                    html! {
+                        div class="section" {
+                            (report.render())
+                        }
+                    }
+                }
+                RINEXReport::Ionex(report) => {
+                    html! {
+                        div class="section" {
+                            (report.render())
+                        }
+                    }
+                }
                 RINEXReport::Nav(report) => {
                     html! {
                         div class="section" {
@@ -127,6 +142,20 @@ impl Render for ProductReport {
                     }
                 }
             }
+            Self::BIAS(report) => {
+                html! {
+                    div class="section" {
+                        (report.render())
+                    }
+                }
+            }
+            Self::EOP(report) => {
+                html! {
+                    div class="section" {
+                        (report.render())
+                    }
+                }
+            }
         }
     }
 }
@@ -155,8 +184,28 @@ pub struct QcReport {
 }
 
 impl QcReport {
-    /// Builds a new GNSS report, ready to be rendered
+    /// Builds a new GNSS report, ready to be rendered.
+    /// The [QcConfig] preprocessing pipeline is applied to a copy of the [QcContext]
+    /// first, unless these steps were already applied to it
+    /// (see [QcContext::apply_pipeline]). An invalid pipeline is not applied:
+    /// the summary only lists the steps that were actually applied.
     pub fn new(context: &QcContext, cfg: QcConfig) -> Self {
+        let steps = &cfg.pipeline.steps;
+
+        if steps.is_empty() || context.preprocessing().steps.ends_with(steps) {
+            return Self::from_context(context, cfg);
+        }
+
+        let mut preprocessed = context.clone();
+
+        match preprocessed.apply_pipeline(&cfg.pipeline) {
+            Ok(_) => Self::from_context(&preprocessed, cfg),
+            Err(_) => Self::from_context(context, cfg),
+        }
+    }
+
+    /// Builds a new GNSS report, from this (preprocessed) [QcContext]
+    fn from_context(context: &QcContext, cfg: QcConfig) -> Self {
         let summary = QcSummary::new(&context, &cfg);
         let summary_only = cfg.report == QcReportType::Summary;
         Self {
@@ -178,11 +227,11 @@ impl QcReport {
                     // one tab per RINEX product
                     for product in [
                         ProductType::Observation,
-                        // ProductType::DORIS,
+                        ProductType::DORIS,
                         ProductType::MeteoObservation,
                         ProductType::BroadcastNavigation,
                         ProductType::HighPrecisionClock,
-                        // ProductType::IONEX,
+                        ProductType::IONEX,
                         ProductType::ANTEX,
                     ] {
                         if let Some(rinex) = context.rinex(product) {
@@ -199,12 +248,45 @@ impl QcReport {
                             ProductReport::SP3(SP3Report::new(sp3)),
                         );
                     }
+                    // one tab for code biases, compared to BRDC group delays
+                    if let Some(bias) = context.bias() {
+                        items.insert(
+                            ProductType::CodeBias,
+                            ProductReport::BIAS(BiasReport::new(bias, context.brdc_navigation())),
+                        );
+                    }
+                    // one tab for Earth orientation
+                    if let Some(eop) = context.eop() {
+                        items.insert(
+                            ProductType::EarthOrientation,
+                            ProductReport::EOP(EopReport::new(eop)),
+                        );
+                    }
                 }
                 items
             },
             summary,
         }
     }
+    /// Builds one GNSS report per [QcScope] of this [QcScopeType],
+    /// for example one report per agency or per original file.
+    /// See [QcContext::partition] for more information.
+    pub fn partitioned(
+        context: &QcContext,
+        cfg: QcConfig,
+        scope_type: QcScopeType,
+    ) -> Result<Vec<(QcScope, Self)>, crate::error::Error> {
+        let partitions = context.partition(scope_type)?;
+        Ok(partitions
+            .into_iter()
+            .map(|(scope, ctx)| {
+                let mut report = Self::new(&ctx, cfg.clone());
+                report.summary.scope = Some(scope.clone());
+                (scope, report)
+            })
+            .collect())
+    }
+
     /// Add a custom chapter to the report
     pub fn add_chapter(&mut self, chapter: QcExtraPage) {
         self.custom_chapters.push(chapter);
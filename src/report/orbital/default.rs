@@ -3,7 +3,7 @@ use std::collections::{BTreeMap, HashMap};
 
 use crate::{
     plot::{MapboxStyle, MarkerSymbol, Mode},
-    prelude::{html, Markup, Plot, QcContext, Render},
+    prelude::{html, Markup, Plot, QcContext, QcOrbitOptions, Render},
 };
 
 /// [OrbitalReport] without SP3 support.
@@ -21,46 +21,51 @@ impl OrbitalReport {
         let mut sp3_lat_ddeg = BTreeMap::<SV, Vec<f64>>::new();
         let mut sp3_long_ddeg = BTreeMap::<SV, Vec<f64>>::new();
 
-        #[cfg(feature = "sp3")]
-        if let Some(sp3) = ctx.sp3() {
-            if let Some(rx_orbit) = reference {
-                for (t, sp3_sv, sp3_orbit) in sp3.satellites_orbit_iter(ctx.earth_cef) {
-                    if let Ok(az_el_range) = ctx
-                        .almanac
-                        .azimuth_elevation_range_sez(sp3_orbit, rx_orbit, None, None)
+        if let Some(rx_orbit) = reference {
+            if let Some(observation) = ctx.observation() {
+                // without SP3, states are resolved from broadcast ephemerides
+                let interpolator = ctx.orbit_interpolator(&QcOrbitOptions::default());
+
+                // both states are Earth fixed (ITRF):
+                // the Earth orientation does not affect azimuth and elevation
+                for (t, state) in interpolator.observation_states_iter(observation) {
+                    if let Ok(az_el_range) =
+                        ctx.almanac
+                            .azimuth_elevation_range_sez(state.orbit, rx_orbit, None, None)
                     {
-                        let (lat_ddeg, long_ddeg, _) = sp3_orbit
+                        let (lat_ddeg, long_ddeg, _) = state
+                            .orbit
                             .latlongalt()
                             .unwrap_or_else(|e| panic!("laglongalt: physical error: {}", e));
 
-                        if let Some(t_sp3) = t_sp3.get_mut(&sp3_sv) {
+                        if let Some(t_sp3) = t_sp3.get_mut(&state.sv) {
                             t_sp3.push(t);
                         } else {
-                            t_sp3.insert(sp3_sv, vec![t]);
+                            t_sp3.insert(state.sv, vec![t]);
                         }
 
-                        if let Some(e) = elev_sp3.get_mut(&sp3_sv) {
+                        if let Some(e) = elev_sp3.get_mut(&state.sv) {
                             e.push(az_el_range.elevation_deg);
                         } else {
-                            elev_sp3.insert(sp3_sv, vec![az_el_range.elevation_deg]);
+                            elev_sp3.insert(state.sv, vec![az_el_range.elevation_deg]);
                         }
 
-                        if let Some(a) = azim_sp3.get_mut(&sp3_sv) {
+                        if let Some(a) = azim_sp3.get_mut(&state.sv) {
                             a.push(az_el_range.azimuth_deg);
                         } else {
-                            azim_sp3.insert(sp3_sv, vec![az_el_range.azimuth_deg]);
+                            azim_sp3.insert(state.sv, vec![az_el_range.azimuth_deg]);
                         }
 
-                        if let Some(lat) = sp3_lat_ddeg.get_mut(&sp3_sv) {
+                        if let Some(lat) = sp3_lat_ddeg.get_mut(&state.sv) {
                             lat.push(lat_ddeg);
                         } else {
-                            sp3_lat_ddeg.insert(sp3_sv, vec![lat_ddeg]);
+                            sp3_lat_ddeg.insert(state.sv, vec![lat_ddeg]);
                         }
 
-                        if let Some(lon) = sp3_long_ddeg.get_mut(&sp3_sv) {
+                        if let Some(lon) = sp3_long_ddeg.get_mut(&state.sv) {
                             lon.push(long_ddeg);
                         } else {
-                            sp3_long_ddeg.insert(sp3_sv, vec![long_ddeg]);
+                            sp3_long_ddeg.insert(state.sv, vec![long_ddeg]);
                         }
                     }
                 }
@@ -112,36 +117,21 @@ impl OrbitalReport {
                     true,
                 );
 
-                #[cfg(feature = "sp3")]
-                if let Some(sp3) = ctx.sp3() {
-                    for (sv_index, sv) in sp3.satellites_iter().enumerate() {
-                        let lat_ddeg = sp3_lat_ddeg
-                            .iter()
-                            .filter_map(
-                                |(svnn, v)| if *svnn == sv { Some(v.clone()) } else { None },
-                            )
-                            .collect::<Vec<_>>();
-
-                        let long_ddeg = sp3_long_ddeg
-                            .iter()
-                            .filter_map(
-                                |(svnn, v)| if *svnn == sv { Some(v.clone()) } else { None },
-                            )
-                            .collect::<Vec<_>>();
+                for (sv_index, (sv, lat_ddeg)) in sp3_lat_ddeg.iter().enumerate() {
+                    let long_ddeg = sp3_long_ddeg.get(sv).unwrap();
 
-                        let map = Plot::mapbox(
-                            lat_ddeg,
-                            long_ddeg,
-                            &sv.to_string(),
-                            5,
-                            MarkerSymbol::Circle,
-                            None,
-                            1.0,
-                            sv_index < 2,
-                        );
+                    let map = Plot::mapbox(
+                        lat_ddeg.to_vec(),
+                        long_ddeg.to_vec(),
+                        &sv.to_string(),
+                        5,
+                        MarkerSymbol::Circle,
+                        None,
+                        1.0,
+                        sv_index < 2,
+                    );
 
-                        map_proj.add_trace(map);
-                    }
+                    map_proj.add_trace(map);
                 }
                 map_proj
             },
@@ -1,11 +1,11 @@
-use rinex::prelude::{nav::Orbit, Constellation, Epoch, Rinex, SV};
+use rinex::prelude::{nav::Orbit, Constellation, Epoch, SV};
 use std::collections::{BTreeMap, HashMap};
 
 use qc_traits::{Filter, Preprocessing};
 
 use crate::{
     plot::{MapboxStyle, MarkerSymbol, Mode},
-    prelude::{html, Markup, Plot, QcContext, Render},
+    prelude::{html, Markup, Plot, QcContext, QcOrbitInterpolator, QcOrbitOptions, Render},
 };
 
 #[cfg(feature = "sp3")]
@@ -20,13 +20,13 @@ struct BrdcSp3Report {
 
 #[cfg(feature = "sp3")]
 impl BrdcSp3Report {
-    fn new(sp3: &SP3, brdc: &Rinex) -> Self {
+    fn new(sp3: &SP3, interpolator: &QcOrbitInterpolator) -> Self {
         let mut errors = BTreeMap::<SV, Vec<(Epoch, f64, f64, f64)>>::new();
         for (t_sp3, sv_sp3, _, _, (sp3_x_km, sp3_y_km, sp3_z_km)) in
             sp3.satellites_position_km_iter()
         {
-            if let Some(brdc_orb) = brdc.sv_orbit(sv_sp3, t_sp3) {
-                let brdc_state = brdc_orb.to_cartesian_pos_vel();
+            if let Some(brdc_state) = interpolator.brdc_state(sv_sp3, t_sp3) {
+                let brdc_state = brdc_state.orbit.to_cartesian_pos_vel();
                 let (nav_x_km, nav_y_km, nav_z_km) = (brdc_state[0], brdc_state[1], brdc_state[2]);
 
                 let (err_x_m, err_y_m, err_z_m) = (
@@ -166,50 +166,55 @@ impl OrbitReport {
         let mut sp3_lat_ddeg = BTreeMap::<SV, Vec<f64>>::new();
         let mut sp3_long_ddeg = BTreeMap::<SV, Vec<f64>>::new();
 
-        #[cfg(feature = "sp3")]
-        if let Some(sp3) = ctx.sp3() {
-            if let Some(rx_orbit) = reference {
-                for sp3_state in sp3.satellites_orbit_iter(ctx.earth_cef) {
-                    if let Ok(az_el_range) = ctx.almanac.azimuth_elevation_range_sez(
-                        sp3_state.orbit,
-                        rx_orbit,
-                        None,
-                        None,
-                    ) {
-                        let (lat_ddeg, long_ddeg, _) = sp3_state
-                            .orbit
-                            .latlongalt()
-                            .unwrap_or_else(|e| panic!("laglongalt: physical error: {}", e));
+        // high precision orbits are preferred, broadcast ephemerides are the fallback
+        let interpolator = ctx.orbit_interpolator(&QcOrbitOptions::default());
 
-                        if let Some(t_sp3) = t_sp3.get_mut(&sp3_state.satellite) {
-                            t_sp3.push(sp3_state.epoch);
-                        } else {
-                            t_sp3.insert(sp3_state.satellite, vec![sp3_state.epoch]);
-                        }
+        if let Some(rx_orbit) = reference {
+            let states = match ctx.observation() {
+                Some(observation) => interpolator.observation_states_iter(observation),
+                None => interpolator.sp3_states_iter(),
+            };
 
-                        if let Some(e) = elev_sp3.get_mut(&sp3_state.satellite) {
-                            e.push(az_el_range.elevation_deg);
-                        } else {
-                            elev_sp3.insert(sp3_state.satellite, vec![az_el_range.elevation_deg]);
-                        }
+            // both states are Earth fixed (ITRF):
+            // the Earth orientation does not affect azimuth and elevation
+            for (t, state) in states {
+                if let Ok(az_el_range) =
+                    ctx.almanac
+                        .azimuth_elevation_range_sez(state.orbit, rx_orbit, None, None)
+                {
+                    let (lat_ddeg, long_ddeg, _) = state
+                        .orbit
+                        .latlongalt()
+                        .unwrap_or_else(|e| panic!("laglongalt: physical error: {}", e));
 
-                        if let Some(a) = azim_sp3.get_mut(&sp3_state.satellite) {
-                            a.push(az_el_range.azimuth_deg);
-                        } else {
-                            azim_sp3.insert(sp3_state.satellite, vec![az_el_range.azimuth_deg]);
-                        }
+                    if let Some(t_sp3) = t_sp3.get_mut(&state.sv) {
+                        t_sp3.push(t);
+                    } else {
+                        t_sp3.insert(state.sv, vec![t]);
+                    }
 
-                        if let Some(lat) = sp3_lat_ddeg.get_mut(&sp3_state.satellite) {
-                            lat.push(lat_ddeg);
-                        } else {
-                            sp3_lat_ddeg.insert(sp3_state.satellite, vec![lat_ddeg]);
-                        }
+                    if let Some(e) = elev_sp3.get_mut(&state.sv) {
+                        e.push(az_el_range.elevation_deg);
+                    } else {
+                        elev_sp3.insert(state.sv, vec![az_el_range.elevation_deg]);
+                    }
 
-                        if let Some(lon) = sp3_long_ddeg.get_mut(&sp3_state.satellite) {
-                            lon.push(long_ddeg);
-                        } else {
-                            sp3_long_ddeg.insert(sp3_state.satellite, vec![long_ddeg]);
-                        }
+                    if let Some(a) = azim_sp3.get_mut(&state.sv) {
+                        a.push(az_el_range.azimuth_deg);
+                    } else {
+                        azim_sp3.insert(state.sv, vec![az_el_range.azimuth_deg]);
+                    }
+
+                    if let Some(lat) = sp3_lat_ddeg.get_mut(&state.sv) {
+                        lat.push(lat_ddeg);
+                    } else {
+                        sp3_lat_ddeg.insert(state.sv, vec![lat_ddeg]);
+                    }
+
+                    if let Some(lon) = sp3_long_ddeg.get_mut(&state.sv) {
+                        lon.push(long_ddeg);
+                    } else {
+                        sp3_long_ddeg.insert(state.sv, vec![long_ddeg]);
                     }
                 }
             }
@@ -260,36 +265,21 @@ impl OrbitReport {
                     true,
                 );
 
-                #[cfg(feature = "sp3")]
-                if let Some(sp3) = ctx.sp3() {
-                    for (sv_index, sv) in sp3.satellites_iter().enumerate() {
-                        let lat_ddeg = sp3_lat_ddeg
-                            .iter()
-                            .filter_map(
-                                |(svnn, v)| if *svnn == sv { Some(v.clone()) } else { None },
-                            )
-                            .collect::<Vec<_>>();
-
-                        let long_ddeg = sp3_long_ddeg
-                            .iter()
-                            .filter_map(
-                                |(svnn, v)| if *svnn == sv { Some(v.clone()) } else { None },
-                            )
-                            .collect::<Vec<_>>();
+                for (sv_index, (sv, lat_ddeg)) in sp3_lat_ddeg.iter().enumerate() {
+                    let long_ddeg = sp3_long_ddeg.get(sv).unwrap();
 
-                        let map = Plot::mapbox(
-                            lat_ddeg,
-                            long_ddeg,
-                            &sv.to_string(),
-                            5,
-                            MarkerSymbol::Circle,
-                            None,
-                            1.0,
-                            sv_index < 2,
-                        );
+                    let map = Plot::mapbox(
+                        lat_ddeg.to_vec(),
+                        long_ddeg.to_vec(),
+                        &sv.to_string(),
+                        5,
+                        MarkerSymbol::Circle,
+                        None,
+                        1.0,
+                        sv_index < 2,
+                    );
 
-                        map_proj.add_trace(map);
-                    }
+                    map_proj.add_trace(map);
                 }
                 map_proj
             },
@@ -306,10 +296,9 @@ impl OrbitReport {
                             {
                                 let filter = Filter::equals(&constellation.to_string()).unwrap();
                                 let focused_sp3 = sp3.filter(&filter);
-                                let focused_nav = nav.filter(&filter);
                                 reports.insert(
                                     constellation,
-                                    BrdcSp3Report::new(&focused_sp3, &focused_nav),
+                                    BrdcSp3Report::new(&focused_sp3, &interpolator),
                                 );
                             }
                         }
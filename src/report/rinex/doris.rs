@@ -3,20 +3,99 @@ use itertools::Itertools;
 use maud::{html, Markup, Render};
 use std::collections::HashMap;
 
-use crate::plot::Plot;
+use crate::plot::{MarkerSymbol, Mode, Plot};
 
 use rinex::{
     carrier::Carrier,
-    prelude::{Observable, Rinex},
+    prelude::{Epoch, Observable, Rinex},
 };
 
+/// Returns the DORIS [Carrier] this [Observable] is measured on.
+/// Only phase, pseudo-range and power measurements are carrier dependent:
+/// their code holds the frequency band ("L1", "C2", "W1"..).
+/// Meteo and frequency observables are not.
+fn doris_carrier(observable: &Observable) -> Option<Carrier> {
+    let code = match observable {
+        Observable::PhaseRange(code) | Observable::PseudoRange(code) | Observable::Power(code) => {
+            code
+        }
+        _ => return None,
+    };
+
+    match code.chars().nth(1)? {
+        '1' => Some(Carrier::S1),
+        '2' => Some(Carrier::U2),
+        _ => None,
+    }
+}
+
 struct SignalPage {
-    /// Sampling
+    /// Sampling of this carrier
     sampling: SamplingReport,
     /// one plot per physics
     raw_plots: HashMap<Observable, Plot>,
 }
 
+impl SignalPage {
+    /// Builds a new [SignalPage] for this [Carrier]: one plot per [Observable],
+    /// one trace per station (beacon). The sampling only considers the epochs
+    /// this [Carrier] was observed at. Returns None when it was never observed.
+    fn new(rinex: &Rinex, carrier: Carrier) -> Option<Self> {
+        let mut raw_plots = HashMap::<Observable, Plot>::new();
+        let mut epochs = Vec::<Epoch>::new();
+
+        if let Some(record) = rinex.record.as_doris() {
+            // (observable, station) => (t, y)
+            let mut series = HashMap::<(Observable, String), (Vec<Epoch>, Vec<f64>)>::new();
+
+            for (k, observations) in record.iter() {
+                for (signal, observation) in observations.signals.iter() {
+                    if doris_carrier(&signal.observable) != Some(carrier) {
+                        continue;
+                    }
+
+                    let (t, y) = series
+                        .entry((signal.observable.clone(), signal.station.label.clone()))
+                        .or_default();
+
+                    t.push(k.epoch);
+                    y.push(observation.value);
+
+                    if epochs.last() != Some(&k.epoch) {
+                        epochs.push(k.epoch);
+                    }
+                }
+            }
+
+            for ((observable, station), (t, y)) in
+                series.into_iter().sorted_by(|(a, _), (b, _)| a.cmp(b))
+            {
+                let plot = raw_plots.entry(observable.clone()).or_insert_with(|| {
+                    let html_id = format!("doris:{:?}:{}", carrier, observable);
+                    let title = format!("{} Observations", observable);
+                    Plot::timedomain_plot(&html_id, &title, &observable.to_string(), true)
+                });
+
+                let trace = Plot::timedomain_chart(
+                    &station,
+                    Mode::Markers,
+                    MarkerSymbol::Cross,
+                    &t,
+                    y,
+                    true,
+                );
+
+                plot.add_trace(trace);
+            }
+        }
+
+        Some(Self {
+            sampling: SamplingReport::from_epochs(&epochs)?,
+            raw_plots,
+        })
+    }
+}
+
 impl Render for SignalPage {
     fn render(&self) -> Markup {
         html! {
@@ -31,11 +110,13 @@ impl Render for SignalPage {
                 }
                 @for observable in self.raw_plots.keys().sorted() {
                     @if let Some(plot) = self.raw_plots.get(observable) {
-                        th class="is-info" {
-                            (format!("{} Observations", observable))
-                        }
-                        td {
-                            (plot.render())
+                        tr {
+                            th class="is-info" {
+                                (format!("{} Observations", observable))
+                            }
+                            td {
+                                (plot.render())
+                            }
                         }
                     }
                 }
@@ -56,6 +137,19 @@ impl DorisReport {
             sampling: SamplingReport::from_rinex(rinex),
             signals: {
                 let mut signals = HashMap::<Carrier, SignalPage>::new();
+
+                let carriers = rinex
+                    .observables_iter()
+                    .filter_map(doris_carrier)
+                    .unique()
+                    .collect::<Vec<_>>();
+
+                for carrier in carriers {
+                    if let Some(page) = SignalPage::new(rinex, carrier) {
+                        signals.insert(carrier, page);
+                    }
+                }
+
                 signals
             },
         }
@@ -87,6 +181,9 @@ impl Render for DorisReport {
                 @for signal in self.signals.keys().sorted() {
                     @if let Some(page) = self.signals.get(signal) {
                         tr {
+                            th class="is-info" {
+                                (format!("{:?}", signal))
+                            }
                             td {
                                 (page.render())
                             }
@@ -97,3 +194,67 @@ impl Render for DorisReport {
         }
     }
 }
+
+#[cfg(all(test, feature = "flate2"))]
+mod test {
+    use super::{doris_carrier, DorisReport};
+    use rinex::{
+        carrier::Carrier,
+        prelude::{Observable, Rinex},
+    };
+
+    #[test]
+    fn doris_carriers() {
+        for (observable, carrier) in [
+            (Observable::PhaseRange("L1".to_string()), Some(Carrier::S1)),
+            (Observable::PseudoRange("C2".to_string()), Some(Carrier::U2)),
+            (Observable::Power("W1".to_string()), Some(Carrier::S1)),
+            (Observable::Pressure, None),
+            (Observable::Temperature, None),
+            (Observable::HumidityRate, None),
+        ] {
+            assert_eq!(doris_carrier(&observable), carrier, "{}", observable);
+        }
+    }
+
+    #[test]
+    fn doris_per_carrier_sampling() {
+        let rinex = Rinex::from_gzip_file("data/DOR/V3/cs2rx18164.gz").unwrap();
+        let record = rinex.record.as_doris().unwrap();
+
+        let report = DorisReport::new(&rinex);
+
+        // both carriers are observed in this file
+        assert_eq!(report.signals.len(), 2);
+        assert_eq!(report.sampling.total, rinex.epoch_iter().count());
+
+        for carrier in [Carrier::S1, Carrier::U2] {
+            let expected = record
+                .iter()
+                .filter(|(_, observations)| {
+                    observations
+                        .signals
+                        .keys()
+                        .any(|signal| doris_carrier(&signal.observable) == Some(carrier))
+                })
+                .map(|(k, _)| k.epoch)
+                .collect::<Vec<_>>();
+
+            let Some(page) = report.signals.get(&carrier) else {
+                assert!(expected.is_empty(), "{:?} not reported", carrier);
+                continue;
+            };
+
+            // only the epochs this carrier was observed at
+            assert_eq!(page.sampling.total, expected.len(), "{:?}", carrier);
+            assert_eq!(page.sampling.first_epoch, expected[0], "{:?}", carrier);
+            assert_eq!(
+                page.sampling.last_epoch,
+                expected[expected.len() - 1],
+                "{:?}",
+                carrier
+            );
+            assert!(page.sampling.total <= report.sampling.total);
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use crate::report::Error;
 use maud::{html, Markup, Render};
 use rinex::ionex::{MappingFunction, RefSystem as Reference};
@@ -45,60 +47,26 @@ impl IonexReport {
                     true,
                 );
 
+                // Collect each map once: (lat, long, tecu) per Epoch
+                let mut maps = BTreeMap::<Epoch, (Vec<f64>, Vec<f64>, Vec<f64>)>::new();
+
+                for (t, tecu, lat, long, _) in rnx.ionex_tecu_latlong_ddeg_alt_km_iter() {
+                    let map = maps.entry(t).or_default();
+                    map.0.push(lat);
+                    map.1.push(long);
+                    map.2.push(tecu);
+                }
+
                 // Build one trace (1 map) per Epoch
                 let mut buttons = Vec::<Button>::new();
 
                 for (epoch_index, epoch) in rnx.epoch_iter().enumerate() {
                     let label = epoch.to_string();
 
-                    let lat = rnx
-                        .ionex_tecu_latlong_ddeg_alt_km_iter()
-                        .filter_map(
-                            |(t, _, lat, _, _)| {
-                                if t == epoch {
-                                    Some(lat)
-                                } else {
-                                    None
-                                }
-                            },
-                        )
-                        .collect::<Vec<_>>();
+                    let (lat, long, tecu) = maps.remove(&epoch).unwrap_or_default();
 
-                    let long = rnx
-                        .ionex_tecu_latlong_ddeg_alt_km_iter()
-                        .filter_map(
-                            |(t, _, long, _, _)| {
-                                if t == epoch {
-                                    Some(long)
-                                } else {
-                                    None
-                                }
-                            },
-                        )
-                        .collect::<Vec<_>>();
-
-                    let tecu = rnx
-                        .ionex_tecu_latlong_ddeg_alt_km_iter()
-                        .filter_map(
-                            |(t, tecu, _, _, _)| {
-                                if t == epoch {
-                                    Some(tecu)
-                                } else {
-                                    None
-                                }
-                            },
-                        )
-                        .collect::<Vec<_>>();
-
-                    let trace = Plot::density_mapbox(
-                        lat.clone(),
-                        long.clone(),
-                        tecu,
-                        &label,
-                        0.6,
-                        3,
-                        epoch_index == 0,
-                    );
+                    let trace =
+                        Plot::density_mapbox(lat, long, tecu, &label, 0.6, 3, epoch_index == 0);
 
                     plot.add_trace(trace);
 
@@ -10,8 +10,8 @@ use doris::DorisReport;
 mod meteo;
 use meteo::MeteoReport;
 
-// mod ionex;
-// use ionex::IonexReport;
+mod ionex;
+use ionex::IonexReport;
 
 mod nav;
 use nav::NavReport;
@@ -28,19 +28,19 @@ pub enum RINEXReport {
     Nav(NavReport),
     Clk(ClkReport),
     Meteo(MeteoReport),
-    // Doris(DorisReport),
-    // Ionex(IonexReport),
+    Doris(DorisReport),
+    Ionex(IonexReport),
 }
 
 impl RINEXReport {
     pub fn new(rnx: &Rinex) -> Result<Self, Error> {
         match rnx.header.rinex_type {
-            // RinexType::DORIS => Ok(Self::Doris(DorisReport::new(rnx))),
+            RinexType::DORIS => Ok(Self::Doris(DorisReport::new(rnx))),
             RinexType::ClockData => Ok(Self::Clk(ClkReport::new(rnx)?)),
             RinexType::MeteoData => Ok(Self::Meteo(MeteoReport::new(rnx)?)),
             RinexType::NavigationData => Ok(Self::Nav(NavReport::new(rnx))),
             RinexType::ObservationData => Ok(Self::Obs(ObsReport::new(rnx))),
-            // RinexType::IonosphereMaps => Ok(Self::Ionex(IonexReport::new(rnx)?)),
+            RinexType::IonosphereMaps => Ok(Self::Ionex(IonexReport::new(rnx)?)),
             _ => Err(Error::NonSupportedRINEX),
         }
     }
@@ -50,8 +50,8 @@ impl RINEXReport {
             Self::Nav(report) => report.html_inline_menu_bar(),
             Self::Clk(report) => report.html_inline_menu_bar(),
             Self::Meteo(report) => report.html_inline_menu_bar(),
-            // Self::Doris(report) => report.html_inline_menu_bar(),
-            // Self::Ionex(report) => report.html_inline_menu_bar(),
+            Self::Doris(report) => report.html_inline_menu_bar(),
+            Self::Ionex(report) => report.html_inline_menu_bar(),
         }
     }
 }
@@ -1,5 +1,6 @@
 use maud::{html, Markup, Render};
 use rinex::prelude::{Duration, Epoch, Rinex};
+use std::collections::BTreeMap;
 
 #[cfg(feature = "sp3")]
 use sp3::SP3;
@@ -53,6 +54,48 @@ impl SamplingReport {
             gaps,
         }
     }
+    /// Builds a [SamplingReport] from these [Epoch]s, in chronological order,
+    /// for data that only cover a subset of the file epochs.
+    /// Returns None when no [Epoch] is provided.
+    pub fn from_epochs(epochs: &[Epoch]) -> Option<Self> {
+        let (first_epoch, last_epoch) = (*epochs.first()?, *epochs.last()?);
+
+        let intervals = epochs
+            .windows(2)
+            .map(|w| (w[0], w[1] - w[0]))
+            .collect::<Vec<_>>();
+
+        // dominant interval: most frequent, shortest on tie
+        let mut histogram = BTreeMap::<Duration, usize>::new();
+        for (_, dt) in intervals.iter() {
+            *histogram.entry(*dt).or_default() += 1;
+        }
+
+        let sampling_interval = histogram
+            .into_iter()
+            .max_by(|(dt_a, n_a), (dt_b, n_b)| n_a.cmp(n_b).then(dt_b.cmp(dt_a)))
+            .map(|(dt, _)| dt);
+
+        let gaps = match sampling_interval {
+            Some(interval) => intervals
+                .into_iter()
+                .filter(|(_, dt)| *dt > interval)
+                .collect::<Vec<_>>(),
+            None => Vec::new(),
+        };
+
+        Some(Self {
+            total: epochs.len(),
+            first_epoch,
+            last_epoch,
+            duration: last_epoch - first_epoch,
+            sampling_interval,
+            dominant_sample_rate: sampling_interval.map(|dt| 1.0 / dt.to_seconds()),
+            shortest_gap: gaps.iter().min_by_key(|(_, dt)| *dt).copied(),
+            longest_gap: gaps.iter().max_by_key(|(_, dt)| *dt).copied(),
+            gaps,
+        })
+    }
     #[cfg(feature = "sp3")]
     pub fn from_sp3(sp3: &SP3) -> Self {
         let t_start = sp3
@@ -184,3 +227,41 @@ impl Render for SamplingReport {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::SamplingReport;
+    use rinex::prelude::{Duration, Epoch};
+    use std::str::FromStr;
+
+    #[test]
+    fn sampling_from_epochs() {
+        assert!(SamplingReport::from_epochs(&[]).is_none());
+
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(10.0);
+
+        // 10s sampling, one 40s gap
+        let epochs = [0, 1, 2, 3, 7, 8, 9]
+            .iter()
+            .map(|n| t0 + *n as f64 * dt)
+            .collect::<Vec<_>>();
+
+        let report = SamplingReport::from_epochs(&epochs).unwrap();
+
+        assert_eq!(report.total, 7);
+        assert_eq!(report.first_epoch, t0);
+        assert_eq!(report.last_epoch, t0 + 9.0 * dt);
+        assert_eq!(report.duration, 9.0 * dt);
+        assert_eq!(report.sampling_interval, Some(dt));
+        assert_eq!(report.dominant_sample_rate, Some(0.1));
+        assert_eq!(report.gaps, vec![(t0 + 3.0 * dt, 4.0 * dt)]);
+        assert_eq!(report.longest_gap, Some((t0 + 3.0 * dt, 4.0 * dt)));
+
+        // single epoch
+        let report = SamplingReport::from_epochs(&epochs[..1]).unwrap();
+        assert_eq!(report.total, 1);
+        assert!(report.sampling_interval.is_none());
+        assert!(report.gaps.is_empty());
+    }
+}
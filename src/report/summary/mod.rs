@@ -1,7 +1,7 @@
 use maud::{html, Markup, Render};
 use rinex::prelude::TimeScale;
 
-use crate::prelude::{QcConfig, QcContext};
+use crate::prelude::{ProductType, QcConfig, QcContext, QcPipeline, QcScope};
 
 mod nav_post;
 use nav_post::QcNavPostSummary;
@@ -9,19 +9,37 @@ use nav_post::QcNavPostSummary;
 mod bias;
 use bias::QcBiasSummary;
 
+mod provenance;
+use provenance::QcProvenanceSummary;
+
+mod reference;
+use reference::QcReferenceSummary;
+
 /// [QcSummary] is the lightest report form,
 /// sort of a report introduction that will always be generated.
 /// It only gives high level and quick description.
 pub struct QcSummary {
     name: String,
+    /// Possible [QcScope] this report is restricted to
+    pub scope: Option<QcScope>,
     /// Configuration used
     cfg: QcConfig,
     /// NAVI summary
     pub navi: QcNavPostSummary,
     /// Main timescale
     timescale: Option<TimeScale>,
+    /// Products expressed in another timescale
+    timescale_conflicts: Vec<(ProductType, TimeScale)>,
+    /// Stations (markers) described by this context
+    stations: Vec<String>,
     /// BIAS summary
     bias_sum: QcBiasSummary,
+    /// Files provenance
+    provenance: QcProvenanceSummary,
+    /// Reference position, and where it comes from
+    reference: Option<QcReferenceSummary>,
+    /// Preprocessing steps that were applied
+    preprocessing: QcPipeline,
 }
 
 impl QcSummary {
@@ -29,9 +47,15 @@ impl QcSummary {
         Self {
             cfg: cfg.clone(),
             name: context.name(),
+            scope: None,
             timescale: context.timescale(),
+            timescale_conflicts: context.timescale_conflicts(),
+            stations: context.stations_iter().map(|s| s.to_string()).collect(),
             bias_sum: QcBiasSummary::new(context),
             navi: QcNavPostSummary::new(context),
+            provenance: QcProvenanceSummary::new(context),
+            reference: QcReferenceSummary::new(context, cfg),
+            preprocessing: context.preprocessing().clone(),
         }
     }
 }
@@ -47,6 +71,18 @@ impl Render for QcSummary {
                                 (self.name.clone())
                             }
                         }
+                        @if let Some(scope) = &self.scope {
+                            tr {
+                                th {
+                                    button aria-label="This report is restricted to a subset of the dataset." data-balloon-pos="right" {
+                                        "Scope"
+                                    }
+                                }
+                                td {
+                                    (scope.to_string())
+                                }
+                            }
+                        }
                         tr {
                             th {
                                 button aria-label="Timescale in which observations are expressed.
@@ -57,6 +93,18 @@ impl Render for QcSummary {
                             @if let Some(timescale) = self.timescale {
                                 td {
                                     (timescale.to_string())
+                                    @if !self.timescale_conflicts.is_empty() {
+                                        br {}
+                                        button aria-label="These products are expressed in another timescale.
+        Use a timescale transposition to obtain a consistent dataset." data-balloon-pos="up" {
+                                            "Mixed: "
+                                            (self.timescale_conflicts
+                                                .iter()
+                                                .map(|(product, ts)| format!("{} ({})", product, ts))
+                                                .collect::<Vec<_>>()
+                                                .join(", "))
+                                        }
+                                    }
                                 }
                             } @else {
                                 td {
@@ -66,6 +114,31 @@ impl Render for QcSummary {
                                 }
                             }
                         }
+                        @if !self.stations.is_empty() {
+                            tr {
+                                th {
+                                    button aria-label="Stations (markers) described by this dataset.
+        The first station loaded is considered Primary." data-balloon-pos="right" {
+                                        "Stations"
+                                    }
+                                }
+                                td {
+                                    (self.stations.join(", "))
+                                }
+                            }
+                        }
+                        @if let Some(reference) = &self.reference {
+                            tr {
+                                th {
+                                    button aria-label="Reference position of the primary station (ECEF)" data-balloon-pos="right" {
+                                        "Reference position"
+                                    }
+                                }
+                                td {
+                                    (reference.render())
+                                }
+                            }
+                        }
                         tr {
                             th class="is-info" {
                                 button aria-label="Context / Dataset compliancy" data-balloon-pos="right" {
@@ -86,6 +159,28 @@ impl Render for QcSummary {
                                 (self.bias_sum.render())
                             }
                         }
+                        @if !self.preprocessing.steps.is_empty() {
+                            tr {
+                                th class="is-info" {
+                                    button aria-label="Preprocessing steps applied to the dataset, in order" data-balloon-pos="right" {
+                                        "Preprocessing"
+                                    }
+                                }
+                                td {
+                                    (self.preprocessing.render())
+                                }
+                            }
+                        }
+                        tr {
+                            th class="is-info" {
+                                button aria-label="Files that produced this report" data-balloon-pos="right" {
+                                    "Provenance"
+                                }
+                            }
+                            td {
+                                (self.provenance.render())
+                            }
+                        }
                     }
                 }
             }
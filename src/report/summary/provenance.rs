@@ -0,0 +1,94 @@
+use crate::prelude::{QcContext, QcFileProvenance};
+use maud::{html, Markup, Render};
+
+use crate::scope::file_name;
+
+/// [QcProvenanceSummary] lists exactly which files produced this report.
+pub struct QcProvenanceSummary {
+    files: Vec<QcFileProvenance>,
+}
+
+impl QcProvenanceSummary {
+    pub fn new(context: &QcContext) -> Self {
+        Self {
+            files: context.provenance(),
+        }
+    }
+}
+
+impl Render for QcProvenanceSummary {
+    fn render(&self) -> Markup {
+        html! {
+            table class="table is-bordered" {
+                thead {
+                    tr {
+                        th class="is-info" {
+                            "File"
+                        }
+                        th class="is-info" {
+                            "Product"
+                        }
+                        th class="is-info" {
+                            button aria-label="Header summary, as detected when loading" data-balloon-pos="up" {
+                                "Header"
+                            }
+                        }
+                        th class="is-info" {
+                            button aria-label="File size, in bytes (as stored, possibly compressed)" data-balloon-pos="up" {
+                                "Size"
+                            }
+                        }
+                        th class="is-info" {
+                            button aria-label="SHA-256 of the file content (as stored, possibly compressed)" data-balloon-pos="up" {
+                                "SHA-256"
+                            }
+                        }
+                        th class="is-info" {
+                            "Loaded"
+                        }
+                    }
+                }
+                tbody {
+                    @for file in self.files.iter() {
+                        tr {
+                            td {
+                                (file_name(&file.path))
+                            }
+                            td {
+                                (file.product.to_string())
+                            }
+                            td {
+                                (file.header)
+                            }
+                            td {
+                                @if let Some(size) = file.size {
+                                    (size.to_string())
+                                } @else {
+                                    "Unknown"
+                                }
+                            }
+                            td {
+                                @if let Some(sha256) = &file.sha256 {
+                                    code {
+                                        (sha256)
+                                    }
+                                } @else {
+                                    button aria-label="File was not readable when loaded" data-balloon-pos="up" {
+                                        "Unknown"
+                                    }
+                                }
+                            }
+                            td {
+                                @if let Some(loaded_at) = file.loaded_at {
+                                    (loaded_at.to_string())
+                                } @else {
+                                    "Unknown"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use maud::{html, Markup, Render};
+
+use crate::prelude::{QcConfig, QcContext};
+
+#[cfg(feature = "navigation")]
+use crate::prelude::ReferenceEcefPosition;
+
+/// Reference position of the primary station, and its source
+pub struct QcReferenceSummary {
+    /// ECEF coordinates [m]
+    ecef_m: (f64, f64, f64),
+    /// ECEF velocity [m/y]
+    velocity_m_yr: Option<(f64, f64, f64)>,
+    /// Source description
+    source: String,
+}
+
+impl QcReferenceSummary {
+    /// User defined position is preferred, then the [QcContext] reference position.
+    pub fn new(context: &QcContext, cfg: &QcConfig) -> Option<Self> {
+        #[cfg(feature = "navigation")]
+        {
+            let position = match cfg.user_rx_ecef {
+                Some(ecef_m) => ReferenceEcefPosition::new(ecef_m),
+                None => context.reference_rx_position()?,
+            };
+
+            return Some(Self {
+                ecef_m: position.ecef_m,
+                velocity_m_yr: position.velocity_m_yr,
+                source: position.source.to_string(),
+            });
+        }
+
+        #[cfg(not(feature = "navigation"))]
+        {
+            let _ = (context, cfg);
+            None
+        }
+    }
+}
+
+impl Render for QcReferenceSummary {
+    fn render(&self) -> Markup {
+        html! {
+            table class="table" {
+                tbody {
+                    tr {
+                        th {
+                            "ECEF [m]"
+                        }
+                        td {
+                            (format!("x={:.3}, y={:.3}, z={:.3}", self.ecef_m.0, self.ecef_m.1, self.ecef_m.2))
+                        }
+                    }
+                    @if let Some((vx, vy, vz)) = self.velocity_m_yr {
+                        tr {
+                            th {
+                                button aria-label="Site velocity, used to propagate the SINEX coordinates to the first observation" data-balloon-pos="up" {
+                                    "Velocity [m/y]"
+                                }
+                            }
+                            td {
+                                (format!("vx={:.4}, vy={:.4}, vz={:.4}", vx, vy, vz))
+                            }
+                        }
+                    }
+                    tr {
+                        th {
+                            "Source"
+                        }
+                        td {
+                            (self.source)
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
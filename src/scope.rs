@@ -1,13 +1,82 @@
-use crate::prelude::ProductType;
+//! Context partitioning
+use std::path::Path;
 
-#[derive(Debug, Clone, PartialEq)]
+use crate::{context::FileMetadata, prelude::ProductType};
+
+/// [QcScope] describes one subset of a [QcContext](crate::prelude::QcContext).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum QcScope {
     /// Scope by [ProductType] (file type)
     ProductType(ProductType),
-    /// Scope by file name
+    /// Scope by file: complete path of the file, so files sharing
+    /// the same name in different directories are kept apart
     FileName(String),
     /// Scope by Agency
     Agency(String),
     /// Scope by Operator / Observer
     Operator(String),
+    /// Scope by Station (marker)
+    Station(String),
+}
+
+/// [QcScopeType] selects how a [QcContext](crate::prelude::QcContext) should be partitioned.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum QcScopeType {
+    /// One scope per [ProductType]
+    ProductType,
+    /// One scope per original file
+    FileName,
+    /// One scope per production Agency
+    Agency,
+    /// One scope per Operator / Observer
+    Operator,
+    /// One scope per Station (marker)
+    Station,
+}
+
+impl std::fmt::Display for QcScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::ProductType(product) => write!(f, "{}", product),
+            Self::FileName(name) => write!(f, "File: {}", name),
+            Self::Agency(agency) => write!(f, "Agency: {}", agency),
+            Self::Operator(operator) => write!(f, "Operator: {}", operator),
+            Self::Station(station) => write!(f, "Station: {}", station),
+        }
+    }
+}
+
+/// Returns file name of this [Path]
+pub(crate) fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string_lossy().to_string())
+}
+
+impl QcScope {
+    /// Returns the [QcScope] of given [QcScopeType] this file belongs to, if any.
+    pub(crate) fn from_file(
+        scope_type: QcScopeType,
+        path: &Path,
+        meta: &FileMetadata,
+    ) -> Option<Self> {
+        match scope_type {
+            QcScopeType::ProductType => Some(Self::ProductType(meta.product)),
+            QcScopeType::FileName => Some(Self::FileName(path.to_string_lossy().to_string())),
+            QcScopeType::Agency => Some(Self::Agency(meta.agency.clone()?)),
+            QcScopeType::Operator => Some(Self::Operator(meta.operator.clone()?)),
+            QcScopeType::Station => Some(Self::Station(meta.station.clone()?)),
+        }
+    }
+
+    /// Returns true if this file is contained in [QcScope].
+    pub(crate) fn contains(&self, path: &Path, meta: &FileMetadata) -> bool {
+        match self {
+            Self::ProductType(product) => meta.product == *product,
+            Self::FileName(name) => path.to_string_lossy() == name.as_str(),
+            Self::Agency(agency) => meta.agency.as_ref() == Some(agency),
+            Self::Operator(operator) => meta.operator.as_ref() == Some(operator),
+            Self::Station(station) => meta.station.as_ref() == Some(station),
+        }
+    }
 }
@@ -0,0 +1,401 @@
+//! Bias-SINEX: Differential (DSB) and Observable Specific (OSB) signal biases
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+    str::FromStr,
+};
+
+#[cfg(feature = "flate2")]
+use flate2::read::GzDecoder;
+
+use itertools::Itertools;
+
+use crate::{
+    prelude::{Epoch, TimeScale, SV},
+    sinex::{column, parse_epoch, SinexError},
+};
+
+use rinex::prelude::Observable;
+
+#[cfg(feature = "snapshot")]
+use serde::{Deserialize, Serialize};
+
+/// [BiasType] as defined in Bias-SINEX
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "snapshot", derive(Serialize, Deserialize))]
+pub enum BiasType {
+    /// Differential Signal Bias (between two observables)
+    DSB,
+    /// Ionosphere-free Signal Bias (between two linear combinations)
+    ISB,
+    /// Observable Specific Signal Bias
+    OSB,
+}
+
+impl FromStr for BiasType {
+    type Err = SinexError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "DSB" => Ok(Self::DSB),
+            "ISB" => Ok(Self::ISB),
+            "OSB" => Ok(Self::OSB),
+            _ => Err(SinexError::InvalidBiasType(s.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for BiasType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::DSB => write!(f, "DSB"),
+            Self::ISB => write!(f, "ISB"),
+            Self::OSB => write!(f, "OSB"),
+        }
+    }
+}
+
+/// [BiasUnit] of each [BiasSolution]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "snapshot", derive(Serialize, Deserialize))]
+pub enum BiasUnit {
+    /// Code biases, in nanoseconds
+    Nanoseconds,
+    /// Phase biases, in cycles
+    Cycles,
+}
+
+impl FromStr for BiasUnit {
+    type Err = SinexError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "ns" => Ok(Self::Nanoseconds),
+            "cyc" => Ok(Self::Cycles),
+            _ => Err(SinexError::InvalidBiasUnit(s.to_string())),
+        }
+    }
+}
+
+/// [BiasSolution] is one estimate of the `+BIAS/SOLUTION` block
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "snapshot", derive(Serialize, Deserialize))]
+pub struct BiasSolution {
+    /// [BiasType]
+    pub bias_type: BiasType,
+    /// Satellite Vehicle Number
+    pub svn: Option<String>,
+    /// Satellite this bias applies to. None for station biases.
+    pub sv: Option<SV>,
+    /// Station this bias applies to. None for satellite biases.
+    pub station: Option<String>,
+    /// First (or only) [Observable]
+    pub obs1: Observable,
+    /// Second [Observable], for differential biases
+    pub obs2: Option<Observable>,
+    /// Start of validity, None when unlimited
+    pub start: Option<Epoch>,
+    /// End of validity, None when unlimited
+    pub end: Option<Epoch>,
+    /// [BiasUnit]
+    pub unit: BiasUnit,
+    /// Estimated value
+    pub value: f64,
+    /// Standard deviation of the estimate
+    pub std_dev: Option<f64>,
+}
+
+impl BiasSolution {
+    /// Returns true if this [BiasSolution] is valid at this [Epoch]
+    pub fn is_valid(&self, t: Epoch) -> bool {
+        let after_start = self.start.map(|start| t >= start).unwrap_or(true);
+        let before_end = self.end.map(|end| t < end).unwrap_or(true);
+        after_start && before_end
+    }
+
+    /// Parses one `+BIAS/SOLUTION` line
+    fn parse(line: &str, timescale: TimeScale) -> Result<Self, SinexError> {
+        let bias_type = BiasType::from_str(column(line, 1, 5).unwrap_or_default())?;
+
+        let sv = match column(line, 11, 14) {
+            Some(prn) => {
+                Some(SV::from_str(prn).map_err(|_| SinexError::InvalidSatellite(prn.to_string()))?)
+            }
+            None => None,
+        };
+
+        let obs1 = column(line, 25, 29).unwrap_or_default();
+        let obs1 = Observable::from_str(obs1)
+            .map_err(|_| SinexError::InvalidObservable(obs1.to_string()))?;
+
+        let obs2 = match column(line, 30, 34) {
+            Some(obs2) => Some(
+                Observable::from_str(obs2)
+                    .map_err(|_| SinexError::InvalidObservable(obs2.to_string()))?,
+            ),
+            None => None,
+        };
+
+        let start = parse_epoch(column(line, 35, 49).unwrap_or_default(), timescale)?;
+        let end = parse_epoch(column(line, 50, 64).unwrap_or_default(), timescale)?;
+
+        let unit = BiasUnit::from_str(column(line, 65, 69).unwrap_or_default())?;
+
+        let value = column(line, 70, 91).unwrap_or_default();
+        let value = value
+            .parse::<f64>()
+            .map_err(|_| SinexError::InvalidValue(value.to_string()))?;
+
+        let std_dev = match column(line, 92, 103) {
+            Some(std_dev) => Some(
+                std_dev
+                    .parse::<f64>()
+                    .map_err(|_| SinexError::InvalidValue(std_dev.to_string()))?,
+            ),
+            None => None,
+        };
+
+        Ok(Self {
+            bias_type,
+            svn: column(line, 6, 10).map(|svn| svn.to_string()),
+            sv,
+            station: column(line, 15, 24).map(|station| station.to_string()),
+            obs1,
+            obs2,
+            start,
+            end,
+            unit,
+            value,
+            std_dev,
+        })
+    }
+}
+
+/// [BiasSolutions] gathers the content of one or several Bias-SINEX files
+/// (DCB and OSB products).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "snapshot", derive(Serialize, Deserialize))]
+pub struct BiasSolutions {
+    /// Production agency
+    pub agency: Option<String>,
+    /// [TimeScale] in which validity periods are expressed
+    pub timescale: TimeScale,
+    /// All [BiasSolution]s
+    pub solutions: Vec<BiasSolution>,
+}
+
+/// Returns the [TimeScale] described by the TIME_SYSTEM descriptor
+fn time_system(content: &str) -> TimeScale {
+    match content.trim() {
+        "E" => TimeScale::GST,
+        "C" => TimeScale::BDT,
+        "R" | "UTC" => TimeScale::UTC,
+        "TAI" => TimeScale::TAI,
+        _ => TimeScale::GPST,
+    }
+}
+
+impl BiasSolutions {
+    /// Parses [BiasSolutions] from this readable Bias-SINEX file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, SinexError> {
+        let fd = File::open(path)?;
+        Self::parse(BufReader::new(fd))
+    }
+
+    /// Parses [BiasSolutions] from this Gzip compressed Bias-SINEX file
+    #[cfg(feature = "flate2")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "flate2")))]
+    pub fn from_gzip_file<P: AsRef<Path>>(path: P) -> Result<Self, SinexError> {
+        let fd = File::open(path)?;
+        Self::parse(BufReader::new(GzDecoder::new(fd)))
+    }
+
+    /// Parses [BiasSolutions] from this readable Bias-SINEX stream
+    pub fn parse<R: BufRead>(reader: R) -> Result<Self, SinexError> {
+        let mut lines = reader.lines();
+
+        let header = lines.next().ok_or(SinexError::MissingHeader)??;
+
+        if !header.starts_with("%=BIA") {
+            return Err(SinexError::MissingHeader);
+        }
+
+        let agency = header
+            .split_ascii_whitespace()
+            .nth(2)
+            .map(|agency| agency.to_string());
+
+        let mut timescale = TimeScale::GPST;
+        let mut in_solution = false;
+        let mut solutions = Vec::new();
+
+        for line in lines {
+            let line = line?;
+
+            if line.starts_with('*') || line.trim().is_empty() {
+                continue; // comments
+            }
+
+            if line.starts_with("+BIAS/SOLUTION") {
+                in_solution = true;
+            } else if line.starts_with("-BIAS/SOLUTION") {
+                in_solution = false;
+            } else if in_solution {
+                solutions.push(BiasSolution::parse(&line, timescale)?);
+            } else if let Some(content) = line.trim_start().strip_prefix("TIME_SYSTEM") {
+                timescale = time_system(content);
+            } else if line.starts_with("%=ENDBIA") {
+                break;
+            }
+        }
+
+        Ok(Self {
+            agency,
+            timescale,
+            solutions,
+        })
+    }
+
+    /// Merges rhs [BiasSolutions] into mutable Self.
+    /// Identical solutions are only kept once.
+    pub fn merge_mut(&mut self, rhs: &Self) {
+        for solution in rhs.solutions.iter() {
+            if !self.solutions.contains(solution) {
+                self.solutions.push(solution.clone());
+            }
+        }
+
+        if self.agency.is_none() {
+            self.agency = rhs.agency.clone();
+        }
+    }
+
+    /// Returns first and last [Epoch] of validity, when defined
+    pub fn time_frame(&self) -> (Option<Epoch>, Option<Epoch>) {
+        (
+            self.solutions.iter().filter_map(|sol| sol.start).min(),
+            self.solutions.iter().filter_map(|sol| sol.end).max(),
+        )
+    }
+
+    /// Returns [SV] iterator, for all satellites described
+    pub fn sv_iter(&self) -> Box<dyn Iterator<Item = SV> + '_> {
+        Box::new(
+            self.solutions
+                .iter()
+                .filter_map(|sol| sol.sv)
+                .unique()
+                .sorted(),
+        )
+    }
+
+    /// Returns iterator over satellites [BiasSolution]s, valid at this [Epoch]
+    fn sv_solutions_iter(&self, sv: SV, t: Epoch) -> impl Iterator<Item = &BiasSolution> + '_ {
+        self.solutions.iter().filter(move |sol| {
+            sol.sv == Some(sv)
+                && sol.station.is_none()
+                && sol.unit == BiasUnit::Nanoseconds
+                && sol.is_valid(t)
+        })
+    }
+
+    /// Returns Observable Specific (code) Bias of this [SV] for this [Observable],
+    /// in nanoseconds, valid at this [Epoch].
+    pub fn observable_specific_bias(
+        &self,
+        sv: SV,
+        observable: &Observable,
+        t: Epoch,
+    ) -> Option<f64> {
+        self.sv_solutions_iter(sv, t)
+            .find(|sol| sol.bias_type == BiasType::OSB && sol.obs1 == *observable)
+            .map(|sol| sol.value)
+    }
+
+    /// Returns Differential (code) Bias of this [SV] between these two [Observable]s,
+    /// in nanoseconds, valid at this [Epoch]: bias(obs1) - bias(obs2).
+    /// When no DSB is published for this pair, it is determined from the OSBs.
+    pub fn differential_code_bias(
+        &self,
+        sv: SV,
+        obs1: &Observable,
+        obs2: &Observable,
+        t: Epoch,
+    ) -> Option<f64> {
+        for sol in self.sv_solutions_iter(sv, t) {
+            if sol.bias_type != BiasType::DSB {
+                continue;
+            }
+            if sol.obs1 == *obs1 && sol.obs2.as_ref() == Some(obs2) {
+                return Some(sol.value);
+            }
+            if sol.obs1 == *obs2 && sol.obs2.as_ref() == Some(obs1) {
+                return Some(-sol.value);
+            }
+        }
+
+        let osb1 = self.observable_specific_bias(sv, obs1, t)?;
+        let osb2 = self.observable_specific_bias(sv, obs2, t)?;
+        Some(osb1 - osb2)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BiasSolutions, BiasType};
+    use crate::prelude::{Epoch, TimeScale, SV};
+    use rinex::prelude::Observable;
+    use std::str::FromStr;
+
+    const CONTENT: &str = "%=BIA 1.00 COD 2016:327:30548 IGS 2016:296:00000 2016:333:00000 R 00000004
++BIAS/DESCRIPTION
+ TIME_SYSTEM                             G
+-BIAS/DESCRIPTION
++BIAS/SOLUTION
+*BIAS SVN_ PRN STATION__ OBS1 OBS2 BIAS_START____ BIAS_END______ UNIT __ESTIMATED_VALUE____ _STD_DEV___
+ DSB  G063 G01           C1C  C1W  2016:296:00000 2016:333:00000 ns                 -0.9800      0.0060
+ DSB  G063 G01           C1W  C2W  2016:296:00000 2016:333:00000 ns                 -4.5150      0.0120
+ OSB  G061 G02           C1W       2016:296:00000 2016:333:00000 ns                  3.1200      0.0100
+ OSB  G061 G02           C2W       2016:296:00000 2016:333:00000 ns                  5.1200      0.0100
+-BIAS/SOLUTION
+%=ENDBIA
+";
+
+    #[test]
+    fn bias_sinex_parsing() {
+        let bias = BiasSolutions::parse(CONTENT.as_bytes()).unwrap();
+
+        assert_eq!(bias.agency.as_deref(), Some("COD"));
+        assert_eq!(bias.timescale, TimeScale::GPST);
+        assert_eq!(bias.solutions.len(), 4);
+
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+
+        assert_eq!(bias.sv_iter().collect::<Vec<_>>(), vec![g01, g02]);
+
+        let first = &bias.solutions[0];
+        assert_eq!(first.bias_type, BiasType::DSB);
+        assert_eq!(first.svn.as_deref(), Some("G063"));
+        assert_eq!(first.std_dev, Some(0.006));
+
+        let c1w = Observable::from_str("C1W").unwrap();
+        let c2w = Observable::from_str("C2W").unwrap();
+
+        let t = Epoch::from_str("2016-11-01T00:00:00 GPST").unwrap();
+
+        assert_eq!(
+            bias.differential_code_bias(g01, &c1w, &c2w, t),
+            Some(-4.515)
+        );
+        assert_eq!(bias.differential_code_bias(g01, &c2w, &c1w, t), Some(4.515));
+
+        // from OSBs
+        assert_eq!(bias.observable_specific_bias(g02, &c1w, t), Some(3.12));
+        let dcb = bias.differential_code_bias(g02, &c1w, &c2w, t).unwrap();
+        assert!((dcb + 2.0).abs() < 1E-9);
+
+        // out of validity period
+        let t = Epoch::from_str("2016-12-01T00:00:00 GPST").unwrap();
+        assert!(bias.differential_code_bias(g01, &c1w, &c2w, t).is_none());
+    }
+}
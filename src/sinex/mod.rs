@@ -0,0 +1,118 @@
+//! SINEX (Solution INdependent EXchange) products:
+//! Bias-SINEX and station coordinates solutions
+use thiserror::Error;
+
+use hifitime::prelude::{Duration, Epoch, TimeScale};
+
+mod bias;
+pub use bias::*;
+
+mod station;
+pub use station::*;
+
+/// SINEX parsing errors
+#[derive(Debug, Error)]
+pub enum SinexError {
+    #[error("I/O error: {0}")]
+    IO(#[from] std::io::Error),
+    #[error("missing SINEX header line")]
+    MissingHeader,
+    #[error("invalid SINEX epoch \"{0}\"")]
+    InvalidEpoch(String),
+    #[error("invalid SINEX value \"{0}\"")]
+    InvalidValue(String),
+    #[error("invalid observable \"{0}\"")]
+    InvalidObservable(String),
+    #[error("invalid satellite \"{0}\"")]
+    InvalidSatellite(String),
+    #[error("invalid bias type \"{0}\"")]
+    InvalidBiasType(String),
+    #[error("invalid bias unit \"{0}\"")]
+    InvalidBiasUnit(String),
+}
+
+/// Returns trimmed content of this (fixed width) column, if not empty
+pub(crate) fn column(line: &str, start: usize, end: usize) -> Option<&str> {
+    let end = end.min(line.len());
+    let content = line.get(start..end)?.trim();
+    if content.is_empty() {
+        None
+    } else {
+        Some(content)
+    }
+}
+
+/// Parses a SINEX epoch `YY:DOY:SECOD` or `YYYY:DOY:SECOD`, expressed in this [TimeScale].
+/// `00:000:00000` is the "undefined" SINEX epoch, which is returned as None.
+pub(crate) fn parse_epoch(
+    content: &str,
+    timescale: TimeScale,
+) -> Result<Option<Epoch>, SinexError> {
+    let invalid = || SinexError::InvalidEpoch(content.to_string());
+
+    let mut items = content.trim().split(':');
+
+    let year = items.next().ok_or_else(invalid)?;
+    let doy = items.next().ok_or_else(invalid)?;
+    let secs = items.next().ok_or_else(invalid)?;
+
+    let (year, doy, secs) = (
+        year.parse::<i32>().map_err(|_| invalid())?,
+        doy.parse::<u32>().map_err(|_| invalid())?,
+        secs.parse::<u32>().map_err(|_| invalid())?,
+    );
+
+    if year == 0 && doy == 0 && secs == 0 {
+        return Ok(None);
+    }
+
+    let year = if year < 100 {
+        // two digit years: 1950-2049
+        if year < 50 {
+            2000 + year
+        } else {
+            1900 + year
+        }
+    } else {
+        year
+    };
+
+    if doy == 0 || doy > 366 || secs > 86_400 {
+        return Err(invalid());
+    }
+
+    let t = Epoch::from_gregorian_at_midnight(year, 1, 1, timescale)
+        + Duration::from_days((doy - 1) as f64)
+        + Duration::from_seconds(secs as f64);
+
+    Ok(Some(t))
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_epoch;
+    use hifitime::prelude::{Epoch, TimeScale};
+    use std::str::FromStr;
+
+    #[test]
+    fn sinex_epoch_parsing() {
+        assert_eq!(
+            parse_epoch("0000:000:00000", TimeScale::GPST).unwrap(),
+            None
+        );
+        assert_eq!(parse_epoch("00:000:00000", TimeScale::GPST).unwrap(), None);
+
+        assert_eq!(
+            parse_epoch("2016:296:00000", TimeScale::GPST).unwrap(),
+            Some(Epoch::from_str("2016-10-22T00:00:00 GPST").unwrap()),
+        );
+
+        assert_eq!(
+            parse_epoch("16:296:43200", TimeScale::GPST).unwrap(),
+            Some(Epoch::from_str("2016-10-22T12:00:00 GPST").unwrap()),
+        );
+
+        assert!(parse_epoch("2016:296", TimeScale::GPST).is_err());
+        assert!(parse_epoch("2016:400:00000", TimeScale::GPST).is_err());
+    }
+}
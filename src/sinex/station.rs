@@ -0,0 +1,331 @@
+//! SINEX station coordinates (and velocities) solutions
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+#[cfg(feature = "flate2")]
+use flate2::read::GzDecoder;
+
+use crate::{
+    prelude::{Epoch, TimeScale},
+    sinex::{column, parse_epoch, SinexError},
+};
+
+#[cfg(feature = "snapshot")]
+use serde::{Deserialize, Serialize};
+
+/// Julian year, in seconds, which is the time unit of SINEX velocities
+const JULIAN_YEAR_S: f64 = 365.25 * 86_400.0;
+
+/// [StationCoordinates] is the position (and possible velocity) estimate
+/// of one station, published in a SINEX solution.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "snapshot", derive(Serialize, Deserialize))]
+pub struct StationCoordinates {
+    /// 4 character site code
+    pub code: String,
+    /// Point code
+    pub point: String,
+    /// DOMES number, when described in the `SITE/ID` block
+    pub domes: Option<String>,
+    /// Reference [Epoch] of the estimate
+    pub epoch: Epoch,
+    /// ECEF coordinates, in meters
+    pub ecef_m: (f64, f64, f64),
+    /// ECEF velocity, in meters per year
+    pub velocity_m_yr: Option<(f64, f64, f64)>,
+    /// Standard deviation of the ECEF coordinates, in meters
+    pub std_dev_m: Option<(f64, f64, f64)>,
+}
+
+impl StationCoordinates {
+    /// Returns ECEF coordinates (in meters) propagated to this [Epoch],
+    /// using the site velocity when it is known.
+    pub fn ecef_m_at(&self, t: Epoch) -> (f64, f64, f64) {
+        match self.velocity_m_yr {
+            Some((vx, vy, vz)) => {
+                let dt_yr = (t - self.epoch).to_seconds() / JULIAN_YEAR_S;
+                (
+                    self.ecef_m.0 + vx * dt_yr,
+                    self.ecef_m.1 + vy * dt_yr,
+                    self.ecef_m.2 + vz * dt_yr,
+                )
+            }
+            None => self.ecef_m,
+        }
+    }
+
+    /// Returns true if this [StationCoordinates] describes this station,
+    /// identified by its marker name and/or DOMES number.
+    /// Marker names are compared on the 4 character site code.
+    pub fn matches(&self, name: Option<&str>, domes: Option<&str>) -> bool {
+        let name_match = name.map(|name| {
+            name.trim()
+                .chars()
+                .take(4)
+                .collect::<String>()
+                .eq_ignore_ascii_case(&self.code)
+        });
+
+        let domes_match = match (domes, &self.domes) {
+            (Some(domes), Some(own)) => Some(domes.trim().eq_ignore_ascii_case(own)),
+            _ => None,
+        };
+
+        match (name_match, domes_match) {
+            // a mismatching DOMES number rules out homonyms
+            (Some(name_match), Some(domes_match)) => name_match && domes_match,
+            (Some(name_match), None) => name_match,
+            (None, Some(domes_match)) => domes_match,
+            (None, None) => false,
+        }
+    }
+}
+
+/// Solution being gathered, per (code, point, solution ID)
+#[derive(Default)]
+struct PendingSolution {
+    epoch: Option<Epoch>,
+    position: [Option<f64>; 3],
+    velocity: [Option<f64>; 3],
+    std_dev: [Option<f64>; 3],
+}
+
+/// [StationSolutions] gathers the station coordinates
+/// of one or several SINEX solution files.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "snapshot", derive(Serialize, Deserialize))]
+pub struct StationSolutions {
+    /// Production agency
+    pub agency: Option<String>,
+    /// All [StationCoordinates]
+    pub stations: Vec<StationCoordinates>,
+}
+
+/// Parses the estimated value or standard deviation of a `SOLUTION/ESTIMATE` line
+fn parse_value(line: &str, start: usize, end: usize) -> Result<Option<f64>, SinexError> {
+    match column(line, start, end) {
+        Some(value) => {
+            Ok(Some(value.parse::<f64>().map_err(|_| {
+                SinexError::InvalidValue(value.to_string())
+            })?))
+        }
+        None => Ok(None),
+    }
+}
+
+impl StationSolutions {
+    /// Parses [StationSolutions] from this readable SINEX file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, SinexError> {
+        let fd = File::open(path)?;
+        Self::parse(BufReader::new(fd))
+    }
+
+    /// Parses [StationSolutions] from this Gzip compressed SINEX file
+    #[cfg(feature = "flate2")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "flate2")))]
+    pub fn from_gzip_file<P: AsRef<Path>>(path: P) -> Result<Self, SinexError> {
+        let fd = File::open(path)?;
+        Self::parse(BufReader::new(GzDecoder::new(fd)))
+    }
+
+    /// Parses [StationSolutions] from this readable SINEX stream.
+    /// Only the `SITE/ID` and the STAX/STAY/STAZ, VELX/VELY/VELZ
+    /// entries of the `SOLUTION/ESTIMATE` block are interpreted.
+    pub fn parse<R: BufRead>(reader: R) -> Result<Self, SinexError> {
+        let mut lines = reader.lines();
+
+        let header = lines.next().ok_or(SinexError::MissingHeader)??;
+
+        if !header.starts_with("%=SNX") {
+            return Err(SinexError::MissingHeader);
+        }
+
+        let agency = header
+            .split_ascii_whitespace()
+            .nth(2)
+            .map(|agency| agency.to_string());
+
+        let mut block = "";
+        let mut domes = BTreeMap::<String, String>::new();
+        let mut pending = BTreeMap::<(String, String, String), PendingSolution>::new();
+
+        for line in lines {
+            let line = line?;
+
+            if line.starts_with('*') || line.trim().is_empty() {
+                continue; // comments
+            }
+
+            if line.starts_with("+SITE/ID") {
+                block = "SITE/ID";
+            } else if line.starts_with("+SOLUTION/ESTIMATE") {
+                block = "SOLUTION/ESTIMATE";
+            } else if line.starts_with('-') {
+                block = "";
+            } else if line.starts_with("%=ENDSNX") {
+                break;
+            } else if block == "SITE/ID" {
+                if let (Some(code), Some(number)) = (column(&line, 1, 5), column(&line, 9, 18)) {
+                    domes.insert(code.to_string(), number.to_string());
+                }
+            } else if block == "SOLUTION/ESTIMATE" {
+                let param = column(&line, 7, 13).unwrap_or_default();
+
+                let (velocity, axis) = match param {
+                    "STAX" => (false, 0),
+                    "STAY" => (false, 1),
+                    "STAZ" => (false, 2),
+                    "VELX" => (true, 0),
+                    "VELY" => (true, 1),
+                    "VELZ" => (true, 2),
+                    _ => continue,
+                };
+
+                let key = (
+                    column(&line, 14, 18).unwrap_or_default().to_string(),
+                    column(&line, 19, 21).unwrap_or_default().to_string(),
+                    column(&line, 22, 26).unwrap_or_default().to_string(),
+                );
+
+                let epoch =
+                    parse_epoch(column(&line, 27, 39).unwrap_or_default(), TimeScale::GPST)?;
+
+                let value = parse_value(&line, 47, 68)?
+                    .ok_or_else(|| SinexError::InvalidValue(line.clone()))?;
+
+                let std_dev = parse_value(&line, 69, 80)?;
+
+                let solution = pending.entry(key).or_default();
+
+                if velocity {
+                    solution.velocity[axis] = Some(value);
+                } else {
+                    solution.position[axis] = Some(value);
+                    solution.std_dev[axis] = std_dev;
+                    if solution.epoch.is_none() {
+                        solution.epoch = epoch;
+                    }
+                }
+            }
+        }
+
+        let stations = pending
+            .into_iter()
+            .filter_map(|((code, point, _), solution)| {
+                let [Some(x), Some(y), Some(z)] = solution.position else {
+                    return None;
+                };
+
+                let velocity_m_yr = match solution.velocity {
+                    [Some(vx), Some(vy), Some(vz)] => Some((vx, vy, vz)),
+                    _ => None,
+                };
+
+                let std_dev_m = match solution.std_dev {
+                    [Some(sx), Some(sy), Some(sz)] => Some((sx, sy, sz)),
+                    _ => None,
+                };
+
+                Some(StationCoordinates {
+                    domes: domes.get(&code).cloned(),
+                    code,
+                    point,
+                    epoch: solution.epoch?,
+                    ecef_m: (x, y, z),
+                    velocity_m_yr,
+                    std_dev_m,
+                })
+            })
+            .collect();
+
+        Ok(Self { agency, stations })
+    }
+
+    /// Merges rhs [StationSolutions] into mutable Self.
+    /// Identical solutions are only kept once.
+    pub fn merge_mut(&mut self, rhs: &Self) {
+        for station in rhs.stations.iter() {
+            if !self.stations.contains(station) {
+                self.stations.push(station.clone());
+            }
+        }
+
+        if self.agency.is_none() {
+            self.agency = rhs.agency.clone();
+        }
+    }
+
+    /// Returns the [StationCoordinates] of this station, identified by its
+    /// marker name and/or DOMES number. When several solutions exist,
+    /// the one whose reference [Epoch] is the closest to t is returned.
+    pub fn station(
+        &self,
+        name: Option<&str>,
+        domes: Option<&str>,
+        t: Epoch,
+    ) -> Option<&StationCoordinates> {
+        self.stations
+            .iter()
+            .filter(|station| station.matches(name, domes))
+            .min_by_key(|station| (t - station.epoch).abs())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::StationSolutions;
+    use crate::prelude::{Duration, Epoch};
+    use std::str::FromStr;
+
+    const CONTENT: &str = "%=SNX 2.02 IGS 21:001:00000 IGS 10:001:00000 10:001:00000 P 00006 2 S
++SITE/ID
+*CODE PT __DOMES__ T _STATION DESCRIPTION__ APPROX_LON_ APPROX_LAT_ _APP_H_
+ ALGO  A 40104M002 P Algonquin Park, Canad 281 55 43.1  45 57 20.9   200.9
+-SITE/ID
++SOLUTION/ESTIMATE
+*INDEX TYPE__ CODE PT SOLN _REF_EPOCH__ UNIT S __ESTIMATED VALUE____ _STD_DEV___
+     1 STAX   ALGO  A    1 10:001:00000 m    2  9.18129018073059e+05 8.58066e-04
+     2 STAY   ALGO  A    1 10:001:00000 m    2 -4.34607096891597e+06 1.35484e-03
+     3 STAZ   ALGO  A    1 10:001:00000 m    2  4.56197771417009e+06 1.41416e-03
+     4 VELX   ALGO  A    1 10:001:00000 m/y  2 -1.62960000000000e-02 2.00000e-05
+     5 VELY   ALGO  A    1 10:001:00000 m/y  2 -3.93000000000000e-03 2.00000e-05
+     6 VELZ   ALGO  A    1 10:001:00000 m/y  2  4.64000000000000e-03 2.00000e-05
+-SOLUTION/ESTIMATE
+%=ENDSNX
+";
+
+    #[test]
+    fn station_sinex_parsing() {
+        let solutions = StationSolutions::parse(CONTENT.as_bytes()).unwrap();
+
+        assert_eq!(solutions.agency.as_deref(), Some("IGS"));
+        assert_eq!(solutions.stations.len(), 1);
+
+        let t0 = Epoch::from_str("2010-01-01T00:00:00 GPST").unwrap();
+
+        let algo = solutions
+            .station(Some("ALGO00CAN"), None, t0)
+            .expect("ALGO should match on its site code");
+
+        assert_eq!(algo.domes.as_deref(), Some("40104M002"));
+        assert_eq!(algo.epoch, t0);
+        assert_eq!(algo.ecef_m.0, 9.18129018073059e+05);
+        assert_eq!(algo.velocity_m_yr, Some((-1.6296e-2, -3.93e-3, 4.64e-3)));
+
+        assert!(solutions.station(None, Some("40104M002"), t0).is_some());
+        assert!(solutions
+            .station(Some("ALGO"), Some("10001M001"), t0)
+            .is_none());
+        assert!(solutions.station(Some("ESBC"), None, t0).is_none());
+
+        // velocity propagation over ten years
+        let t = t0 + Duration::from_days(10.0 * 365.25);
+        let (x, _, z) = algo.ecef_m_at(t);
+        assert!((x - (9.18129018073059e+05 - 0.16296)).abs() < 1E-6);
+        assert!((z - (4.56197771417009e+06 + 0.0464)).abs() < 1E-6);
+    }
+}
@@ -0,0 +1,975 @@
+//! BINEX records decoding: site metadata (0x00), GPS, GLONASS and Galileo
+//! decoded ephemerides (0x01-01, 0x01-02, 0x01-04) and GNSS observations (0x7f-05).
+//!
+//! Only forward readable, big endian records protected by a regular checksum
+//! (sync byte 0xE2) are supported.
+use std::collections::HashMap;
+
+use rinex::navigation::{Ephemeris, NavMessageType, OrbitItem};
+
+use crate::{
+    prelude::{Constellation, Duration, Epoch, TimeScale, SV},
+    stream::{carrier_frequency_hz, BitReader, RinexCollector, StreamError, SPEED_OF_LIGHT_M_S},
+};
+
+/// Forward readable, big endian, regular checksum sync byte
+pub(crate) const SYNC: u8 = 0xE2;
+
+/// Site, monument, marker and setup metadata record
+const RECORD_METADATA: u32 = 0x00;
+
+/// Decoded ephemerides record
+const RECORD_EPHEMERIS: u32 = 0x01;
+
+/// GNSS observations (prototyping) record
+const RECORD_OBSERVATIONS: u32 = 0x7f;
+
+/// CRC-16 (CCITT) generator polynomial
+const CRC16_POLY: u16 = 0x1021;
+
+/// Semi circles to radians
+const SC2RAD: f64 = std::f64::consts::PI;
+
+/// Decodes one ubnxi (1 to 4 bytes unsigned integer).
+/// Returns the value and its size in bytes.
+pub(crate) fn ubnxi(buf: &[u8]) -> Option<(u32, usize)> {
+    let mut value = 0u32;
+
+    // first three bytes: 7 bits and a continuation flag
+    for i in 0..3 {
+        let byte = *buf.get(i)?;
+        value = (value << 7) | (byte & 0x7F) as u32;
+
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+
+    let byte = *buf.get(3)?;
+    Some(((value << 8) | byte as u32, 4))
+}
+
+/// Computes the CRC-16 (CCITT) of this buffer
+pub(crate) fn crc16(buf: &[u8]) -> u16 {
+    let mut crc = 0u16;
+
+    for byte in buf {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ CRC16_POLY
+            } else {
+                crc << 1
+            };
+        }
+    }
+
+    crc
+}
+
+/// Returns the size of the regular checksum protecting this many bytes.
+/// Records above 4096 bytes (CRC-32 and MD5 protected) are not supported.
+fn checksum_size(len: usize) -> Option<usize> {
+    if len < 128 {
+        Some(1)
+    } else if len < 4096 {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+/// True if this regular checksum protects this content
+fn valid_checksum(content: &[u8], checksum: &[u8]) -> bool {
+    match checksum.len() {
+        1 => content.iter().fold(0, |acc, byte| acc ^ byte) == checksum[0],
+        _ => crc16(content).to_be_bytes() == checksum,
+    }
+}
+
+/// One BINEX record
+pub(crate) struct Record<'a> {
+    /// Record ID
+    pub id: u32,
+    /// Record message
+    pub message: &'a [u8],
+}
+
+/// Iterates over each valid BINEX record of this buffer.
+/// Bytes that do not belong to a valid record (partial or corrupt records,
+/// records of other flavors) are skipped.
+pub(crate) fn records(buf: &[u8]) -> impl Iterator<Item = Record<'_>> + '_ {
+    let mut pos = 0;
+
+    std::iter::from_fn(move || {
+        while pos + 4 <= buf.len() {
+            if buf[pos] != SYNC {
+                pos += 1;
+                continue;
+            }
+
+            let Some((id, id_size)) = ubnxi(&buf[pos + 1..]) else {
+                pos += 1;
+                continue;
+            };
+
+            let Some((len, len_size)) = ubnxi(&buf[pos + 1 + id_size..]) else {
+                pos += 1;
+                continue;
+            };
+
+            let start = pos + 1 + id_size + len_size;
+            let end = start + len as usize;
+
+            let Some(checksum_size) = checksum_size(end - pos - 1) else {
+                pos += 1;
+                continue;
+            };
+
+            if end + checksum_size > buf.len() {
+                pos += 1;
+                continue;
+            }
+
+            if !valid_checksum(&buf[pos + 1..end], &buf[end..end + checksum_size]) {
+                pos += 1;
+                continue;
+            }
+
+            let record = Record {
+                id,
+                message: &buf[start..end],
+            };
+
+            pos = end + checksum_size;
+            return Some(record);
+        }
+
+        None
+    })
+}
+
+/// True if this buffer contains at least one valid BINEX record
+pub(crate) fn is_binex(buf: &[u8]) -> bool {
+    records(buf).next().is_some()
+}
+
+/// Reads one IEEE-754 single precision float
+fn r4(r: &mut BitReader) -> Result<f64, StreamError> {
+    Ok(f32::from_bits(r.u(32)? as u32) as f64)
+}
+
+/// Reads one IEEE-754 double precision float
+fn r8(r: &mut BitReader) -> Result<f64, StreamError> {
+    Ok(f64::from_bits(r.u(64)?))
+}
+
+/// Returns this time of ephemeris (seconds of week) in nanoseconds,
+/// rejecting ephemeris records with an invalid time of week.
+fn time_of_ephemeris_nanos(toe: i64) -> Result<u64, StreamError> {
+    if !(0..604_800).contains(&toe) {
+        return Err(StreamError::InvalidMessage(RECORD_EPHEMERIS as u16));
+    }
+
+    Ok(toe as u64 * 1_000_000_000)
+}
+
+/// Returns the [Constellation] of this 0x7f-05 system identifier
+fn binex_constellation(sys: u8) -> Option<Constellation> {
+    match sys {
+        0 => Some(Constellation::GPS),
+        1 => Some(Constellation::Glonass),
+        2 => Some(Constellation::SBAS),
+        3 => Some(Constellation::Galileo),
+        4 => Some(Constellation::BeiDou),
+        5 => Some(Constellation::QZSS),
+        _ => None,
+    }
+}
+
+/// Returns the RINEX code (without physics) of this 0x7f-05 signal identifier
+fn binex_signal_code(constellation: Constellation, code: u8) -> Option<&'static str> {
+    let code = match (constellation, code) {
+        (Constellation::GPS, 0 | 1) => "1C",
+        (Constellation::GPS, 2) => "1P",
+        (Constellation::GPS, 3) => "1W",
+        (Constellation::GPS, 4) => "1Y",
+        (Constellation::GPS, 5) => "1M",
+        (Constellation::GPS, 6) => "1X",
+        (Constellation::GPS, 7) => "1N",
+        (Constellation::GPS, 10 | 17) => "2W",
+        (Constellation::GPS, 11) => "2C",
+        (Constellation::GPS, 12) => "2D",
+        (Constellation::GPS, 13) => "2S",
+        (Constellation::GPS, 14) => "2L",
+        (Constellation::GPS, 15) => "2X",
+        (Constellation::GPS, 16) => "2P",
+        (Constellation::GPS, 18) => "2Y",
+        (Constellation::GPS, 19) => "2M",
+        (Constellation::GPS, 20) => "2N",
+        (Constellation::GPS, 23 | 26) => "5X",
+        (Constellation::GPS, 24) => "5I",
+        (Constellation::GPS, 25) => "5Q",
+        (Constellation::Glonass, 0 | 1) => "1C",
+        (Constellation::Glonass, 2) => "1P",
+        (Constellation::Glonass, 10 | 11) => "2C",
+        (Constellation::Glonass, 12) => "2P",
+        (Constellation::Glonass, 13 | 16) => "3X",
+        (Constellation::Glonass, 14) => "3I",
+        (Constellation::Glonass, 15) => "3Q",
+        (Constellation::SBAS, 0 | 1) => "1C",
+        (Constellation::Galileo, 0 | 3) => "1C",
+        (Constellation::Galileo, 1) => "1A",
+        (Constellation::Galileo, 2) => "1B",
+        (Constellation::Galileo, 4) => "1X",
+        (Constellation::Galileo, 5) => "1Z",
+        (Constellation::Galileo, 6 | 9) => "5X",
+        (Constellation::Galileo, 7) => "5I",
+        (Constellation::Galileo, 8) => "5Q",
+        (Constellation::Galileo, 10 | 13) => "7X",
+        (Constellation::Galileo, 11) => "7I",
+        (Constellation::Galileo, 12) => "7Q",
+        (Constellation::Galileo, 14 | 17) => "8X",
+        (Constellation::Galileo, 15) => "8I",
+        (Constellation::Galileo, 16) => "8Q",
+        (Constellation::Galileo, 18 | 22) => "6X",
+        (Constellation::Galileo, 19) => "6A",
+        (Constellation::Galileo, 20) => "6B",
+        (Constellation::Galileo, 21) => "6C",
+        (Constellation::Galileo, 23) => "6Z",
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Builds the [Ephemeris] orbit fields, using the RINEX field names
+fn orbits(fields: &[(&str, f64)]) -> HashMap<String, OrbitItem> {
+    fields
+        .iter()
+        .map(|(key, value)| (key.to_string(), OrbitItem::F64(*value)))
+        .collect()
+}
+
+/// Keplerian parameters shared by the GPS (0x01-01) and Galileo (0x01-04) ephemerides
+struct Keplerian {
+    delta_n: f64,
+    m0: f64,
+    e: f64,
+    sqrt_a: f64,
+    cic: f64,
+    crc: f64,
+    cis: f64,
+    crs: f64,
+    cuc: f64,
+    cus: f64,
+    omega0: f64,
+    omega: f64,
+    i0: f64,
+    omega_dot: f64,
+    idot: f64,
+}
+
+impl Keplerian {
+    /// Decodes the Keplerian parameters, from deltaN to IDOT
+    fn decode(r: &mut BitReader) -> Result<Self, StreamError> {
+        let delta_n = r4(r)? * SC2RAD;
+        let m0 = r8(r)?;
+        let e = r8(r)?;
+        let sqrt_a = r8(r)?;
+        let cic = r4(r)?;
+        let crc = r4(r)?;
+        let cis = r4(r)?;
+        let crs = r4(r)?;
+        let cuc = r4(r)?;
+        let cus = r4(r)?;
+        let omega0 = r8(r)?;
+        let omega = r8(r)?;
+        let i0 = r8(r)?;
+        let omega_dot = r4(r)? * SC2RAD;
+        let idot = r4(r)? * SC2RAD;
+
+        Ok(Self {
+            delta_n,
+            m0,
+            e,
+            sqrt_a,
+            cic,
+            crc,
+            cis,
+            crs,
+            cuc,
+            cus,
+            omega0,
+            omega,
+            i0,
+            omega_dot,
+            idot,
+        })
+    }
+
+    /// Returns the RINEX orbit fields
+    fn fields(&self, toe: f64) -> Vec<(&'static str, f64)> {
+        vec![
+            ("crs", self.crs),
+            ("deltaN", self.delta_n),
+            ("m0", self.m0),
+            ("cuc", self.cuc),
+            ("e", self.e),
+            ("cus", self.cus),
+            ("sqrta", self.sqrt_a),
+            ("toe", toe),
+            ("cic", self.cic),
+            ("omega0", self.omega0),
+            ("cis", self.cis),
+            ("i0", self.i0),
+            ("crc", self.crc),
+            ("omega", self.omega),
+            ("omegaDot", self.omega_dot),
+            ("idot", self.idot),
+        ]
+    }
+}
+
+/// BINEX [Decoder]
+pub(crate) struct Decoder {
+    /// Decoded content
+    collector: RinexCollector,
+}
+
+impl Decoder {
+    /// Decodes all supported records of this BINEX file.
+    ///
+    /// BINEX records are completely time tagged: unlike RTCM3 or UBX,
+    /// no reference [Epoch] is required.
+    pub fn decode(buf: &[u8]) -> Result<RinexCollector, StreamError> {
+        let mut decoder = Self {
+            collector: Default::default(),
+        };
+
+        let mut nrecords = 0;
+
+        // metadata and ephemerides first: GLONASS frequency channels are
+        // required to express the carrier phase in cycles.
+        for record in records(buf) {
+            nrecords += 1;
+
+            // corrupt or non supported records are simply skipped
+            let _ = match record.id {
+                RECORD_METADATA => decoder.decode_metadata(record.message),
+                RECORD_EPHEMERIS => decoder.decode_ephemeris(record.message),
+                _ => Ok(()),
+            };
+        }
+
+        if nrecords == 0 {
+            return Err(StreamError::NoFrames);
+        }
+
+        for record in records(buf) {
+            if record.id == RECORD_OBSERVATIONS {
+                let _ = decoder.decode_observations(record.message);
+            }
+        }
+
+        if !decoder.collector.has_observations() && !decoder.collector.has_ephemerides() {
+            return Err(StreamError::NoData);
+        }
+
+        Ok(decoder.collector)
+    }
+
+    /// Decodes one site metadata record (0x00).
+    /// Fields we cannot describe in a RINEX header are dropped.
+    fn decode_metadata(&mut self, message: &[u8]) -> Result<(), StreamError> {
+        // creation time (minutes and quarter seconds) and data source
+        let mut pos = 4 + 1 + 1;
+
+        if message.len() < pos {
+            return Err(StreamError::Truncated);
+        }
+
+        let metadata = &mut self.collector.metadata;
+
+        while pos < message.len() {
+            let (field, size) = ubnxi(&message[pos..]).ok_or(StreamError::Truncated)?;
+            pos += size;
+
+            // all fields we support are readable strings
+            let is_string = field <= 0x0c
+                || (0x0f..=0x1c).contains(&field)
+                || (0x20..=0x22).contains(&field)
+                || field == 0x7f;
+
+            if !is_string {
+                // we cannot determine the size of this field: drop the remaining fields
+                return Ok(());
+            }
+
+            let (len, size) = ubnxi(&message[pos..]).ok_or(StreamError::Truncated)?;
+            pos += size;
+
+            let end = pos + len as usize;
+
+            if end > message.len() {
+                return Err(StreamError::Truncated);
+            }
+
+            let value = String::from_utf8_lossy(&message[pos..end])
+                .trim()
+                .to_string();
+            pos = end;
+
+            match field {
+                0x00 => metadata.comments.push(value),
+                0x01 => metadata.program = Some(value),
+                0x02 => metadata.run_by = Some(value),
+                0x08 => metadata.agency = Some(value),
+                0x09 => metadata.observer = Some(value),
+                0x0b => {
+                    let marker = metadata.marker.take().unwrap_or_default();
+                    metadata.marker = Some(marker.with_name(&value));
+                }
+                0x0c => {
+                    let marker = metadata.marker.take().unwrap_or_default();
+                    metadata.marker = Some(marker.with_number(&value));
+                }
+                0x17 => {
+                    let antenna = metadata.antenna.take().unwrap_or_default();
+                    metadata.antenna = Some(antenna.with_model(&value));
+                }
+                0x18 => {
+                    let antenna = metadata.antenna.take().unwrap_or_default();
+                    metadata.antenna = Some(antenna.with_serial_number(&value));
+                }
+                0x19 => {
+                    let receiver = metadata.receiver.take().unwrap_or_default();
+                    metadata.receiver = Some(receiver.with_model(&value));
+                }
+                0x1a => {
+                    let receiver = metadata.receiver.take().unwrap_or_default();
+                    metadata.receiver = Some(receiver.with_serial_number(&value));
+                }
+                0x1b => {
+                    let receiver = metadata.receiver.take().unwrap_or_default();
+                    metadata.receiver = Some(receiver.with_firmware(&value));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Decodes one decoded ephemeris record (0x01)
+    fn decode_ephemeris(&mut self, message: &[u8]) -> Result<(), StreamError> {
+        let mut r = BitReader::new(message);
+
+        match r.u(8)? {
+            0x01 => self.decode_gps_ephemeris(&mut r),
+            0x02 => self.decode_glo_ephemeris(&mut r),
+            0x04 => self.decode_gal_ephemeris(&mut r),
+            _ => Ok(()),
+        }
+    }
+
+    /// Decodes one GPS ephemeris (0x01-01)
+    fn decode_gps_ephemeris(&mut self, r: &mut BitReader) -> Result<(), StreamError> {
+        let prn = (r.u(8)? + 1) as u8;
+        let week = r.u(16)? as u32;
+        // time of transmission
+        r.skip(32)?;
+        let toe = r.i(32)?;
+        let tgd = r4(r)?;
+        let iodc = r.i(32)? as f64;
+        let af2 = r4(r)?;
+        let af1 = r4(r)?;
+        let af0 = r4(r)?;
+        let iode = r.i(32)? as f64;
+        let keplerian = Keplerian::decode(r)?;
+        let accuracy = r4(r)? * 0.1;
+        let health = r.u(16)? as f64;
+        let flags = r.u(16)?;
+
+        let fit = (flags & 0xFF) as f64;
+        let l2p_flag = ((flags >> 8) & 0x01) as f64;
+        let l2_codes = ((flags >> 9) & 0x03) as f64;
+
+        // the time of clock is the time of ephemeris
+        let toc = Epoch::from_time_of_week(week, time_of_ephemeris_nanos(toe)?, TimeScale::GPST);
+
+        let mut fields = vec![("iode", iode)];
+        fields.extend(keplerian.fields(toe as f64));
+        fields.extend([
+            ("l2Codes", l2_codes),
+            ("week", week as f64),
+            ("l2pDataFlag", l2p_flag),
+            ("svAccuracy", accuracy),
+            ("health", health),
+            ("tgd", tgd),
+            ("iodc", iodc),
+            ("fitInt", fit),
+        ]);
+
+        let ephemeris = Ephemeris {
+            clock_bias: af0,
+            clock_drift: af1,
+            clock_drift_rate: af2,
+            orbits: orbits(&fields),
+        };
+
+        let sv = SV::new(Constellation::GPS, prn);
+
+        self.collector
+            .add_ephemeris(toc, sv, NavMessageType::LNAV, ephemeris);
+
+        Ok(())
+    }
+
+    /// Decodes one Galileo ephemeris (0x01-04)
+    fn decode_gal_ephemeris(&mut self, r: &mut BitReader) -> Result<(), StreamError> {
+        let prn = (r.u(8)? + 1) as u8;
+        // continuous (GPS aligned) week number
+        let week = r.u(16)? as u32;
+        // time of transmission
+        r.skip(32)?;
+        let toe = r.i(32)?;
+        let bgd_e5a_e1 = r4(r)?;
+        let bgd_e5b_e1 = r4(r)?;
+        let iodnav = r.i(32)? as f64;
+        let af2 = r4(r)?;
+        let af1 = r4(r)?;
+        let af0 = r4(r)?;
+        let keplerian = Keplerian::decode(r)?;
+        let sisa = r4(r)?;
+        let health = r.u(16)? as f64;
+        let source = r.u(16)?;
+
+        // I/NAV, unless only F/NAV (E5a) data was used
+        let msgtype = if source & 0x02 != 0 && source & 0x05 == 0 {
+            NavMessageType::FNAV
+        } else {
+            NavMessageType::INAV
+        };
+
+        let toc = Epoch::from_time_of_week(week, time_of_ephemeris_nanos(toe)?, TimeScale::GPST)
+            .to_time_scale(TimeScale::GST);
+
+        let mut fields = vec![("iodnav", iodnav)];
+        fields.extend(keplerian.fields(toe as f64));
+        fields.extend([
+            ("dataSrc", source as f64),
+            ("week", week as f64),
+            ("sisa", sisa),
+            ("health", health),
+            ("bgdE5aE1", bgd_e5a_e1),
+            ("bgdE5bE1", bgd_e5b_e1),
+        ]);
+
+        let ephemeris = Ephemeris {
+            clock_bias: af0,
+            clock_drift: af1,
+            clock_drift_rate: af2,
+            orbits: orbits(&fields),
+        };
+
+        let sv = SV::new(Constellation::Galileo, prn);
+
+        self.collector.add_ephemeris(toc, sv, msgtype, ephemeris);
+
+        Ok(())
+    }
+
+    /// Decodes one GLONASS ephemeris (0x01-02)
+    fn decode_glo_ephemeris(&mut self, r: &mut BitReader) -> Result<(), StreamError> {
+        let prn = (r.u(8)? + 1) as u8;
+        // days since the GPST origin, UTC time of day
+        let day = r.u(16)?;
+        let tod = r.u(32)?;
+        // -TauN
+        let clock_bias = r8(r)?;
+        let gamma_n = r8(r)?;
+        // message frame time, UTC time of day
+        let tk = r.u(32)? as f64;
+
+        let (mut pos_km, mut vel_km_s, mut accel_km_s2) =
+            ([0.0_f64; 3], [0.0_f64; 3], [0.0_f64; 3]);
+
+        for ((pos, vel), accel) in pos_km
+            .iter_mut()
+            .zip(vel_km_s.iter_mut())
+            .zip(accel_km_s2.iter_mut())
+        {
+            *pos = r8(r)?;
+            *vel = r8(r)?;
+            *accel = r8(r)?;
+        }
+
+        let health = (r.u(8)? & 0x01) as f64;
+        let channel = r.i(8)? as i8;
+        let age = r.u(8)? as f64;
+
+        let toc = Epoch::from_gregorian_utc_at_midnight(1980, 1, 6)
+            + Duration::from_days(day as f64)
+            + Duration::from_seconds(tod as f64);
+
+        let ephemeris = Ephemeris {
+            clock_bias,
+            clock_drift: gamma_n,
+            clock_drift_rate: tk,
+            orbits: orbits(&[
+                ("satPosX", pos_km[0]),
+                ("velX", vel_km_s[0]),
+                ("accelX", accel_km_s2[0]),
+                ("health", health),
+                ("satPosY", pos_km[1]),
+                ("velY", vel_km_s[1]),
+                ("accelY", accel_km_s2[1]),
+                ("channel", channel as f64),
+                ("satPosZ", pos_km[2]),
+                ("velZ", vel_km_s[2]),
+                ("accelZ", accel_km_s2[2]),
+                ("ageOp", age),
+            ]),
+        };
+
+        let sv = SV::new(Constellation::Glonass, prn);
+
+        self.collector.set_glonass_channel(sv, channel);
+        self.collector
+            .add_ephemeris(toc, sv, NavMessageType::FDMA, ephemeris);
+
+        Ok(())
+    }
+
+    /// Decodes one GNSS observations record (0x7f).
+    /// Only the 0x7f-05 (Trimble NetR8 and later) sub record is supported.
+    fn decode_observations(&mut self, message: &[u8]) -> Result<(), StreamError> {
+        let mut r = BitReader::new(message);
+
+        let subrecord = r.u(8)?;
+        let minutes = r.u(32)?;
+        let ms = r.u(16)?;
+
+        if subrecord != 0x05 {
+            return Ok(());
+        }
+
+        // time elapsed since the GPST origin (1980-01-06)
+        let t = Epoch::from_gpst_seconds(minutes as f64 * 60.0 + ms as f64 * 1.0E-3);
+
+        let has_clock = r.bit()?;
+        let has_time_offsets = r.bit()?;
+        let nsat = r.u(6)? + 1;
+
+        if has_clock {
+            // clock reset counter and receiver clock offset
+            r.skip(24)?;
+        }
+
+        if has_time_offsets {
+            let nsys = r.u(4)?;
+            r.skip(4 + 32 * nsys as usize)?;
+        }
+
+        for _ in 0..nsat {
+            let prn = r.u(8)? as u8;
+            r.skip(1)?;
+            let nobs = r.u(3)?;
+            let sys = r.u(4)? as u8;
+
+            let constellation = binex_constellation(sys);
+
+            let sv = constellation.map(|constellation| match constellation {
+                // SBAS may be identified by their PRN (120..158)
+                Constellation::SBAS if prn >= 100 => SV::new(constellation, prn - 100),
+                _ => SV::new(constellation, prn),
+            });
+
+            // the first signal is the reference of the following ones
+            let mut reference_m = 0.0;
+
+            for i in 0..nobs {
+                let mut extended = r.bit()?;
+                // cycle slip indicators
+                r.skip(2)?;
+                let code = r.u(5)? as u8;
+
+                let mut flags = [0u8; 4];
+
+                for _ in 0..4 {
+                    if !extended {
+                        break;
+                    }
+                    let flag = r.u(8)? as u8;
+                    flags[(flag & 0x03) as usize] = flag & 0x7F;
+                    extended = flag & 0x80 != 0;
+                }
+
+                // phase resolution
+                let resolution_m = if flags[0] & 0x20 != 0 {
+                    0.0001
+                } else {
+                    0.00002
+                };
+
+                let mut cn0_dbhz = r.u(8)? as f64 * 0.4;
+
+                let pseudo_range_m = if i == 0 {
+                    cn0_dbhz += r.i(2)? as f64 * 0.1;
+                    reference_m = r.u(32)? as f64 * 0.064 + r.u(6)? as f64 * 0.001;
+                    reference_m
+                } else if flags[0] & 0x40 != 0 {
+                    cn0_dbhz += r.i(2)? as f64 * 0.1;
+                    r.skip(2)?;
+                    reference_m + r.i(20)? as f64 * 0.001
+                } else {
+                    reference_m + r.i(16)? as f64 * 0.001
+                };
+
+                let phase_m = if flags[0] & 0x40 != 0 {
+                    pseudo_range_m + r.i(24)? as f64 * resolution_m
+                } else {
+                    cn0_dbhz += r.i(2)? as f64 * 0.1;
+                    pseudo_range_m + r.i(22)? as f64 * resolution_m
+                };
+
+                let doppler_hz = if flags[0] & 0x04 != 0 {
+                    Some(r.i(24)? as f64 / 256.0)
+                } else {
+                    None
+                };
+
+                if flags[0] & 0x08 != 0 {
+                    // cycle slip counter
+                    let bits = if flags[0] & 0x10 != 0 { 16 } else { 8 };
+                    r.skip(bits)?;
+                }
+
+                let (Some(sv), Some(constellation)) = (sv, constellation) else {
+                    continue;
+                };
+
+                if constellation == Constellation::Glonass && flags[2] != 0 {
+                    let channel = ((flags[2] << 2) as i8) >> 4;
+                    self.collector.set_glonass_channel(sv, channel);
+                }
+
+                let Some(code) = binex_signal_code(constellation, code) else {
+                    continue;
+                };
+
+                let wavelength_m = code
+                    .chars()
+                    .next()
+                    .and_then(|band| {
+                        carrier_frequency_hz(
+                            constellation,
+                            band,
+                            self.collector.glonass_channel(sv),
+                        )
+                    })
+                    .map(|hz| SPEED_OF_LIGHT_M_S / hz);
+
+                self.collector.add_signal(
+                    t,
+                    sv,
+                    code,
+                    Some(pseudo_range_m),
+                    wavelength_m.map(|lambda| phase_m / lambda),
+                    doppler_hz,
+                    Some(cn0_dbhz),
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{crc16, is_binex, records, ubnxi, Decoder};
+    use crate::{
+        prelude::{Constellation, Epoch, ProductType, SV},
+        stream::StreamError,
+        tests::toolkit::binex::{gps_ephemeris, metadata, observations, record, BinexSignal},
+    };
+    use std::str::FromStr;
+
+    #[test]
+    fn binex_framing() {
+        assert_eq!(ubnxi(&[0x05]), Some((0x05, 1)));
+        assert_eq!(ubnxi(&[0x81, 0x00]), Some((0x80, 2)));
+        assert_eq!(ubnxi(&[0xFF, 0xFF, 0xFF, 0xFF]), Some((0x1FFFFFFF, 4)));
+        assert_eq!(ubnxi(&[0x81]), None);
+
+        assert_eq!(crc16(b"123456789"), 0x31C3);
+
+        let short = record(0x7f, &[0x00; 10]);
+        let long = record(0x7f, &[0x01; 300]);
+        assert_eq!(short.len(), 1 + 1 + 1 + 10 + 1);
+        assert_eq!(long.len(), 1 + 1 + 2 + 300 + 2);
+
+        // garbage and corrupt records are skipped
+        let mut buf = vec![0x00, 0xE2, 0x42];
+        buf.extend_from_slice(&short);
+        let mut corrupt = long.clone();
+        corrupt[10] ^= 0x01;
+        buf.extend_from_slice(&corrupt);
+        buf.extend_from_slice(&long);
+
+        assert!(is_binex(&buf));
+        assert_eq!(
+            records(&buf).map(|r| r.message.len()).collect::<Vec<_>>(),
+            vec![10, 300]
+        );
+        assert!(!is_binex(&[0xE2, 0x00, 0x01, 0x02, 0x04]));
+    }
+
+    #[test]
+    fn binex_observations() {
+        let t = Epoch::from_str("2024-01-09T12:00:00 GPST").unwrap();
+
+        let mut buf = metadata(&[
+            (0x01, "teqc"),
+            (0x0b, "MOJN"),
+            (0x19, "TRIMBLE NETR9"),
+            (0x1b, "5.45"),
+        ]);
+
+        buf.extend(observations(
+            t,
+            &[
+                (
+                    0,
+                    5,
+                    vec![
+                        BinexSignal {
+                            code: 0,
+                            pseudo_range_m: 21_000_000.125,
+                            phase_m: 21_000_000.25,
+                            doppler_hz: Some(-1250.5),
+                            cn0_dbhz: 44.0,
+                        },
+                        BinexSignal {
+                            code: 11,
+                            pseudo_range_m: 21_000_002.5,
+                            phase_m: 21_000_002.75,
+                            doppler_hz: None,
+                            cn0_dbhz: 40.0,
+                        },
+                    ],
+                ),
+                // unknown system
+                (
+                    9,
+                    1,
+                    vec![BinexSignal {
+                        code: 0,
+                        pseudo_range_m: 20_000_000.0,
+                        phase_m: 20_000_000.0,
+                        doppler_hz: None,
+                        cn0_dbhz: 30.0,
+                    }],
+                ),
+            ],
+        ));
+
+        let collector = Decoder::decode(&buf).unwrap();
+        assert!(collector.navigation().is_none());
+
+        let rinex = collector.observation().unwrap();
+
+        assert_eq!(
+            ProductType::from(rinex.header.rinex_type),
+            ProductType::Observation
+        );
+
+        assert_eq!(rinex.first_epoch(), Some(t));
+
+        let marker = rinex.header.geodetic_marker.as_ref().unwrap();
+        assert_eq!(marker.name, "MOJN");
+
+        let receiver = rinex.header.rcvr.as_ref().unwrap();
+        assert_eq!(receiver.model, "TRIMBLE NETR9");
+        assert_eq!(receiver.firmware, "5.45");
+
+        assert_eq!(rinex.header.program, Some("teqc".to_string()));
+
+        let g05 = SV::new(Constellation::GPS, 5);
+        let lambda = 299_792_458.0 / 1575.42E6;
+        let lambda_l2 = 299_792_458.0 / 1227.60E6;
+
+        let mut nobs = 0;
+
+        for (_, signal) in rinex.signal_observations_iter() {
+            assert_eq!(signal.sv, g05);
+            match signal.observable.to_string().as_str() {
+                "C1C" => assert!((signal.value - 21_000_000.125).abs() < 1.0E-3),
+                "L1C" => assert!((signal.value - 21_000_000.25 / lambda).abs() < 1.0E-3),
+                "D1C" => assert_eq!(signal.value, -1250.5),
+                "S1C" => assert!((signal.value - 44.0).abs() < 0.1),
+                "C2C" => assert!((signal.value - 21_000_002.5).abs() < 1.0E-3),
+                "L2C" => assert!((signal.value - 21_000_002.75 / lambda_l2).abs() < 1.0E-3),
+                "S2C" => assert!((signal.value - 40.0).abs() < 0.1),
+                observable => panic!("unexpected observable {}", observable),
+            }
+            nobs += 1;
+        }
+
+        assert_eq!(nobs, 7);
+    }
+
+    #[test]
+    fn binex_gps_ephemeris() {
+        let buf = gps_ephemeris(12, 2296, 7200);
+
+        let collector = Decoder::decode(&buf).unwrap();
+        assert!(collector.has_ephemerides());
+
+        let rinex = collector.navigation().unwrap();
+        assert_eq!(
+            ProductType::from(rinex.header.rinex_type),
+            ProductType::BroadcastNavigation
+        );
+
+        let (key, ephemeris) = rinex.nav_ephemeris_frames_iter().next().unwrap();
+
+        assert_eq!(key.sv, SV::new(Constellation::GPS, 12));
+        assert_eq!(
+            key.epoch,
+            Epoch::from_str("2024-01-07T02:00:00 GPST").unwrap()
+        );
+        assert_eq!(ephemeris.clock_bias, 2.0_f64.powi(-20));
+        assert_eq!(ephemeris.get_orbit_f64("sqrta"), Some(5153.0));
+        assert_eq!(ephemeris.get_orbit_f64("e"), Some(0.015625));
+        assert_eq!(ephemeris.get_orbit_f64("iode"), Some(42.0));
+        assert_eq!(ephemeris.get_orbit_f64("week"), Some(2296.0));
+        assert_eq!(ephemeris.get_orbit_f64("svAccuracy"), Some(2.0));
+        assert_eq!(
+            ephemeris.get_orbit_f64("i0"),
+            Some(std::f64::consts::PI / 4.0)
+        );
+    }
+
+    #[test]
+    fn binex_invalid_time_of_ephemeris() {
+        // 604800 s, and -1 s once encoded as a signed 32 bit integer
+        for toe_s in [604_800, u32::MAX] {
+            let buf = gps_ephemeris(12, 2296, toe_s);
+
+            let record = records(&buf).next().unwrap();
+
+            let mut decoder = Decoder {
+                collector: Default::default(),
+            };
+
+            assert!(matches!(
+                decoder.decode_ephemeris(record.message),
+                Err(StreamError::InvalidMessage(0x01))
+            ));
+
+            assert!(matches!(Decoder::decode(&buf), Err(StreamError::NoData)));
+        }
+    }
+}
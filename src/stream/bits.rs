@@ -0,0 +1,99 @@
+//! Big endian bit reader, as used by RTCM3 messages
+use crate::stream::StreamError;
+
+/// [BitReader] reads unaligned, MSB first, bit fields.
+pub(crate) struct BitReader<'a> {
+    buf: &'a [u8],
+    /// Position, in bits
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    /// Number of bits that remain
+    pub fn remaining(&self) -> usize {
+        (self.buf.len() * 8).saturating_sub(self.pos)
+    }
+
+    /// Skips this number of bits
+    pub fn skip(&mut self, bits: usize) -> Result<(), StreamError> {
+        if bits > self.remaining() {
+            return Err(StreamError::Truncated);
+        }
+        self.pos += bits;
+        Ok(())
+    }
+
+    /// Reads an unsigned field, up to 64 bits
+    pub fn u(&mut self, bits: usize) -> Result<u64, StreamError> {
+        debug_assert!(bits <= 64);
+
+        if bits > self.remaining() {
+            return Err(StreamError::Truncated);
+        }
+
+        let mut value = 0u64;
+
+        for _ in 0..bits {
+            let byte = self.buf[self.pos / 8];
+            let bit = (byte >> (7 - (self.pos % 8))) & 0x01;
+            value = (value << 1) | bit as u64;
+            self.pos += 1;
+        }
+
+        Ok(value)
+    }
+
+    /// Reads a two's complement signed field, up to 64 bits
+    pub fn i(&mut self, bits: usize) -> Result<i64, StreamError> {
+        let value = self.u(bits)?;
+
+        if bits < 64 && value & (1 << (bits - 1)) != 0 {
+            Ok((value as i64) - (1i64 << bits))
+        } else {
+            Ok(value as i64)
+        }
+    }
+
+    /// Reads a sign-magnitude field (MSB is the sign), up to 64 bits
+    #[cfg_attr(not(feature = "rtcm"), allow(dead_code))]
+    pub fn sm(&mut self, bits: usize) -> Result<i64, StreamError> {
+        let sign = self.u(1)?;
+        let magnitude = self.u(bits - 1)? as i64;
+
+        if sign == 1 {
+            Ok(-magnitude)
+        } else {
+            Ok(magnitude)
+        }
+    }
+
+    /// Reads a single bit flag
+    #[cfg_attr(not(any(feature = "rtcm", feature = "binex")), allow(dead_code))]
+    pub fn bit(&mut self) -> Result<bool, StreamError> {
+        Ok(self.u(1)? == 1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::BitReader;
+
+    #[test]
+    fn bit_reader() {
+        let buf = [0b1011_0011, 0b1111_0000, 0x80, 0x01];
+        let mut reader = BitReader::new(&buf);
+
+        assert_eq!(reader.u(4).unwrap(), 0b1011);
+        assert_eq!(reader.i(4).unwrap(), 3);
+        assert_eq!(reader.i(4).unwrap(), -1);
+        assert_eq!(reader.sm(5).unwrap(), 1);
+        assert_eq!(reader.remaining(), 15);
+        assert_eq!(reader.sm(8).unwrap(), 0);
+        assert_eq!(reader.u(7).unwrap(), 1);
+        assert!(reader.u(1).is_err());
+    }
+}
@@ -0,0 +1,305 @@
+//! Decoded stream content, collected into RINEX products
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+};
+
+use rinex::{
+    hardware::{Antenna, Receiver},
+    marker::GeodeticMarker,
+    navigation::{Ephemeris, NavFrame, NavFrameType, NavKey, NavMessageType},
+    observation::{EpochFlag, ObsKey, Observations, SignalObservation},
+    prelude::{Constellation, Epoch, Header, Observable, Rinex, TimeScale, SV},
+    record::Record,
+};
+
+/// Speed of light [m/s]
+#[cfg(any(feature = "rtcm", feature = "binex"))]
+pub(crate) const SPEED_OF_LIGHT_M_S: f64 = 299_792_458.0;
+
+/// Returns the carrier frequency (in Hz) of this RINEX band (frequency digit),
+/// for this [Constellation]. GLONASS FDMA frequencies require the frequency channel.
+#[cfg(any(feature = "rtcm", feature = "binex"))]
+pub(crate) fn carrier_frequency_hz(
+    constellation: Constellation,
+    band: char,
+    glonass_channel: Option<i8>,
+) -> Option<f64> {
+    let mhz = match (constellation, band) {
+        (Constellation::Glonass, '1') => 1602.0 + glonass_channel? as f64 * 0.5625,
+        (Constellation::Glonass, '2') => 1246.0 + glonass_channel? as f64 * 0.4375,
+        (Constellation::BeiDou, '2') => 1561.098,
+        (Constellation::BeiDou, '6') => 1268.52,
+        (Constellation::BeiDou, '7') => 1207.14,
+        (Constellation::Galileo, '6') | (Constellation::QZSS, '6') => 1278.75,
+        (Constellation::Galileo, '7') => 1207.14,
+        (Constellation::Galileo, '8') => 1191.795,
+        (_, '1') => 1575.42,
+        (_, '2') => 1227.60,
+        (_, '5') => 1176.45,
+        _ => return None,
+    };
+    Some(mhz * 1.0E6)
+}
+
+/// Station and production metadata, when streamed
+#[derive(Default, Clone)]
+pub(crate) struct StreamMetadata {
+    /// Geodetic marker
+    pub marker: Option<GeodeticMarker>,
+    /// Receiver model, serial number and firmware
+    pub receiver: Option<Receiver>,
+    /// Antenna model and serial number
+    pub antenna: Option<Antenna>,
+    /// Observer name
+    pub observer: Option<String>,
+    /// Agency
+    pub agency: Option<String>,
+    /// Program that produced the stream
+    pub program: Option<String>,
+    /// Operator of that program
+    pub run_by: Option<String>,
+    /// Comments
+    pub comments: Vec<String>,
+}
+
+/// [RinexCollector] gathers the observations and ephemerides
+/// decoded from a binary stream, to form Observation and Navigation [Rinex].
+#[derive(Default)]
+pub(crate) struct RinexCollector {
+    /// Receiver (or reference station) ECEF position [m], when streamed.
+    pub rx_position: Option<(f64, f64, f64)>,
+    /// Station and production metadata, when streamed
+    pub metadata: StreamMetadata,
+    /// GLONASS frequency channels, as streamed
+    glonass_channels: HashMap<SV, i8>,
+    /// Observations, per [Epoch] (expressed in GPST)
+    observations: BTreeMap<Epoch, Vec<SignalObservation>>,
+    /// Ephemeris frames
+    ephemerides: BTreeMap<NavKey, NavFrame>,
+}
+
+impl RinexCollector {
+    /// Declares the GLONASS frequency channel of this [SV]
+    pub fn set_glonass_channel(&mut self, sv: SV, channel: i8) {
+        self.glonass_channels.insert(sv, channel);
+    }
+
+    /// Returns the GLONASS frequency channel of this [SV], when known
+    #[cfg(any(feature = "rtcm", feature = "binex"))]
+    pub fn glonass_channel(&self, sv: SV) -> Option<i8> {
+        self.glonass_channels.get(&sv).copied()
+    }
+
+    /// Adds one observation. `code` is the RINEX observable code without its
+    /// physics (for example "1C"), and t is converted to GPST.
+    pub fn add_observation(&mut self, t: Epoch, sv: SV, physics: char, code: &str, value: f64) {
+        let observable = match Observable::from_str(&format!("{}{}", physics, code)) {
+            Ok(observable) => observable,
+            Err(_) => return,
+        };
+
+        let signal = SignalObservation {
+            sv,
+            observable,
+            value,
+            lli: None,
+            snr: None,
+        };
+
+        let t = t.to_time_scale(TimeScale::GPST);
+
+        if let Some(signals) = self.observations.get_mut(&t) {
+            if !signals
+                .iter()
+                .any(|s| s.sv == signal.sv && s.observable == signal.observable)
+            {
+                signals.push(signal);
+            }
+        } else {
+            self.observations.insert(t, vec![signal]);
+        }
+    }
+
+    /// Adds all observations of one signal: pseudo range [m], carrier phase [cycles],
+    /// doppler [Hz] and C/N0 [dB.Hz], when they are valid.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_signal(
+        &mut self,
+        t: Epoch,
+        sv: SV,
+        code: &str,
+        pseudo_range_m: Option<f64>,
+        phase_cycles: Option<f64>,
+        doppler_hz: Option<f64>,
+        cn0_dbhz: Option<f64>,
+    ) {
+        for (physics, value) in [
+            ('C', pseudo_range_m),
+            ('L', phase_cycles),
+            ('D', doppler_hz),
+            ('S', cn0_dbhz),
+        ] {
+            if let Some(value) = value {
+                self.add_observation(t, sv, physics, code, value);
+            }
+        }
+    }
+
+    /// Adds one [Ephemeris], published for this [SV] at this time of clock.
+    pub fn add_ephemeris(
+        &mut self,
+        toc: Epoch,
+        sv: SV,
+        msgtype: NavMessageType,
+        ephemeris: Ephemeris,
+    ) {
+        let key = NavKey {
+            epoch: toc,
+            sv,
+            msgtype,
+            frmtype: NavFrameType::Ephemeris,
+        };
+
+        self.ephemerides.insert(key, NavFrame::EPH(ephemeris));
+    }
+
+    /// True if observations were collected
+    pub fn has_observations(&self) -> bool {
+        !self.observations.is_empty()
+    }
+
+    /// True if ephemerides were collected
+    pub fn has_ephemerides(&self) -> bool {
+        !self.ephemerides.is_empty()
+    }
+
+    /// Returns the [Constellation] described by this set of [SV]s
+    fn constellation<I: Iterator<Item = SV>>(satellites: I) -> Option<Constellation> {
+        let mut constellations = satellites.map(|sv| sv.constellation).collect::<Vec<_>>();
+        constellations.sort();
+        constellations.dedup();
+
+        match constellations.len() {
+            0 => None,
+            1 => Some(constellations[0]),
+            _ => Some(Constellation::Mixed),
+        }
+    }
+
+    /// Describes the production of this stream in this [Header]
+    fn apply_production_metadata(&self, header: &mut Header) {
+        if self.metadata.agency.is_some() {
+            header.agency = self.metadata.agency.clone();
+        }
+        if self.metadata.program.is_some() {
+            header.program = self.metadata.program.clone();
+        }
+        if self.metadata.run_by.is_some() {
+            header.run_by = self.metadata.run_by.clone();
+        }
+        header
+            .comments
+            .extend(self.metadata.comments.iter().cloned());
+    }
+
+    /// Forms the Observation [Rinex], if observations were collected.
+    pub fn observation(&self) -> Option<Rinex> {
+        if self.observations.is_empty() {
+            return None;
+        }
+
+        let mut header = Header::basic_obs();
+
+        header.constellation = Self::constellation(
+            self.observations
+                .values()
+                .flat_map(|signals| signals.iter().map(|s| s.sv)),
+        );
+
+        header.rx_position = self.rx_position;
+        header.glo_channels = self.glonass_channels.clone();
+        header.geodetic_marker = self.metadata.marker.clone();
+        header.rcvr = self.metadata.receiver.clone();
+        header.rcvr_antenna = self.metadata.antenna.clone();
+        header.observer = self.metadata.observer.clone();
+        self.apply_production_metadata(&mut header);
+
+        if let Some(obs) = &mut header.obs {
+            for signal in self.observations.values().flatten() {
+                let codes = obs
+                    .codes
+                    .entry(signal.sv.constellation)
+                    .or_insert_with(Vec::new);
+
+                if !codes.contains(&signal.observable) {
+                    codes.push(signal.observable.clone());
+                }
+            }
+
+            for codes in obs.codes.values_mut() {
+                codes.sort();
+            }
+        }
+
+        let record = self
+            .observations
+            .iter()
+            .map(|(t, signals)| {
+                let key = ObsKey {
+                    epoch: *t,
+                    flag: EpochFlag::Ok,
+                };
+
+                let observations = Observations {
+                    clock: None,
+                    signals: signals.clone(),
+                };
+
+                (key, observations)
+            })
+            .collect();
+
+        Some(Rinex::new(header, Record::ObsRecord(record)))
+    }
+
+    /// Forms the Navigation [Rinex], if ephemerides were collected.
+    pub fn navigation(&self) -> Option<Rinex> {
+        if self.ephemerides.is_empty() {
+            return None;
+        }
+
+        let mut header = Header::basic_nav();
+        header.constellation = Self::constellation(self.ephemerides.keys().map(|k| k.sv));
+        self.apply_production_metadata(&mut header);
+
+        Some(Rinex::new(
+            header,
+            Record::NavRecord(self.ephemerides.clone()),
+        ))
+    }
+}
+
+#[cfg(all(test, feature = "rtcm"))]
+mod test {
+    use super::carrier_frequency_hz;
+    use crate::prelude::Constellation;
+
+    #[test]
+    fn carrier_frequencies() {
+        for (constellation, band, channel, expected_mhz) in [
+            (Constellation::GPS, '1', None, 1575.42),
+            (Constellation::GPS, '5', None, 1176.45),
+            (Constellation::Galileo, '7', None, 1207.14),
+            (Constellation::BeiDou, '2', None, 1561.098),
+            (Constellation::Glonass, '1', Some(-7), 1598.0625),
+            (Constellation::Glonass, '2', Some(6), 1248.625),
+        ] {
+            let hz = carrier_frequency_hz(constellation, band, channel).unwrap();
+            assert!((hz - expected_mhz * 1.0E6).abs() < 1.0);
+        }
+
+        assert!(carrier_frequency_hz(Constellation::Glonass, '1', None).is_none());
+        assert!(carrier_frequency_hz(Constellation::GPS, '9', None).is_none());
+    }
+}
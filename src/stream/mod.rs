@@ -0,0 +1,97 @@
+//! Binary GNSS streams decoding.
+//!
+//! Recorded binary streams are decoded into the same Observation and
+//! Broadcast Navigation [Rinex](crate::prelude::Rinex) products as readable files.
+use thiserror::Error;
+
+use crate::prelude::{Duration, Epoch, TimeScale};
+
+mod bits;
+pub(crate) use bits::BitReader;
+
+mod collector;
+pub(crate) use collector::*;
+
+#[cfg(feature = "rtcm")]
+pub(crate) mod rtcm;
+
+#[cfg(feature = "ubx")]
+pub(crate) mod ubx;
+
+#[cfg(feature = "binex")]
+pub(crate) mod binex;
+
+/// Binary stream decoding errors
+#[derive(Debug, Error)]
+pub enum StreamError {
+    #[error("truncated message")]
+    Truncated,
+    #[error("invalid message {0}")]
+    InvalidMessage(u16),
+    #[error("no valid frame found")]
+    NoFrames,
+    #[error("no observations nor ephemerides decoded")]
+    NoData,
+    #[error("time of week may not be resolved: no reference epoch")]
+    NoReferenceEpoch,
+}
+
+/// Resolves a week number, that is only streamed modulo `modulo`,
+/// to the complete week number closest to `reference_week`.
+pub(crate) fn resolve_week(week: u32, modulo: u32, reference_week: u32) -> u32 {
+    let (week, modulo, reference_week) = (week as i64, modulo as i64, reference_week as i64);
+    let cycles = (reference_week - week + modulo / 2).div_euclid(modulo);
+    (week + cycles * modulo).max(0) as u32
+}
+
+/// Resolves a time of week (in nanoseconds) expressed in this [TimeScale],
+/// to the [Epoch] closest to `reference`.
+pub(crate) fn resolve_time_of_week(
+    tow_nanos: u64,
+    timescale: TimeScale,
+    reference: Epoch,
+) -> Epoch {
+    let (week, _) = reference.to_time_scale(timescale).to_time_of_week();
+    let t = Epoch::from_time_of_week(week, tow_nanos, timescale);
+    closest(t, reference, Duration::from_days(7.0))
+}
+
+/// Shifts `t` by whole `period`s, to the [Epoch] closest to `reference`
+pub(crate) fn closest(t: Epoch, reference: Epoch, period: Duration) -> Epoch {
+    let half = period * 0.5;
+
+    if t - reference > half {
+        t - period
+    } else if reference - t > half {
+        t + period
+    } else {
+        t
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{resolve_time_of_week, resolve_week};
+    use crate::prelude::{Epoch, TimeScale};
+    use std::str::FromStr;
+
+    #[test]
+    fn week_resolution() {
+        assert_eq!(resolve_week(300, 1024, 2348), 2348);
+        assert_eq!(resolve_week(1023, 1024, 2048), 2047);
+        assert_eq!(resolve_week(1, 1024, 2047), 2049);
+        assert_eq!(resolve_week(1300, 4096, 1300), 1300);
+    }
+
+    #[test]
+    fn time_of_week_resolution() {
+        let reference = Epoch::from_str("2024-01-07T00:10:00 GPST").unwrap();
+
+        // saturday of the previous week
+        let t = resolve_time_of_week(6 * 86400 * 1_000_000_000, TimeScale::GPST, reference);
+        assert_eq!(t, Epoch::from_str("2024-01-06T00:00:00 GPST").unwrap());
+
+        let t = resolve_time_of_week(300 * 1_000_000_000, TimeScale::GPST, reference);
+        assert_eq!(t, Epoch::from_str("2024-01-07T00:05:00 GPST").unwrap());
+    }
+}
@@ -0,0 +1,969 @@
+//! RTCM3 messages decoding: MSM4/5/7 observations,
+//! GPS (1019), GLONASS (1020), BeiDou (1042) and Galileo (1045/1046) ephemerides
+//! and station coordinates (1005/1006).
+use std::collections::HashMap;
+
+use rinex::navigation::{Ephemeris, NavMessageType, OrbitItem};
+
+use crate::{
+    prelude::{Constellation, Duration, Epoch, TimeScale, SV},
+    stream::{
+        carrier_frequency_hz, closest, resolve_time_of_week, resolve_week, BitReader,
+        RinexCollector, StreamError, SPEED_OF_LIGHT_M_S,
+    },
+};
+
+/// RTCM3 frame preamble
+pub(crate) const PREAMBLE: u8 = 0xD3;
+
+/// CRC-24Q generator polynomial
+const CRC24Q_POLY: u32 = 0x1864CFB;
+
+/// Semi circles to radians
+const SC2RAD: f64 = std::f64::consts::PI;
+
+/// Returns 2^exp
+fn p2(exp: i32) -> f64 {
+    2.0_f64.powi(exp)
+}
+
+/// Computes the CRC-24Q of this buffer
+pub(crate) fn crc24q(buf: &[u8]) -> u32 {
+    let mut crc = 0u32;
+
+    for byte in buf {
+        crc ^= (*byte as u32) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x1000000 != 0 {
+                crc ^= CRC24Q_POLY;
+            }
+        }
+    }
+
+    crc & 0xFFFFFF
+}
+
+/// Iterates over the payload of each valid RTCM3 frame of this buffer.
+/// Bytes that do not belong to a valid frame (partial or corrupt frames,
+/// other protocols) are skipped.
+pub(crate) fn frames(buf: &[u8]) -> impl Iterator<Item = &[u8]> + '_ {
+    let mut pos = 0;
+
+    std::iter::from_fn(move || {
+        while pos + 6 <= buf.len() {
+            // preamble and 6 reserved bits
+            if buf[pos] != PREAMBLE || buf[pos + 1] & 0xFC != 0 {
+                pos += 1;
+                continue;
+            }
+
+            let len = (((buf[pos + 1] & 0x03) as usize) << 8) | buf[pos + 2] as usize;
+            let end = pos + 3 + len + 3;
+
+            if end > buf.len() {
+                pos += 1;
+                continue;
+            }
+
+            let crc =
+                ((buf[end - 3] as u32) << 16) | ((buf[end - 2] as u32) << 8) | buf[end - 1] as u32;
+
+            if crc24q(&buf[pos..end - 3]) != crc {
+                pos += 1;
+                continue;
+            }
+
+            let payload = &buf[pos + 3..end - 3];
+            pos = end;
+            return Some(payload);
+        }
+
+        None
+    })
+}
+
+/// True if this buffer contains at least one valid RTCM3 frame
+pub(crate) fn is_rtcm3(buf: &[u8]) -> bool {
+    frames(buf).next().is_some()
+}
+
+/// Returns the RINEX code (without physics) of this MSM signal ID
+fn msm_signal_code(constellation: Constellation, id: u8) -> Option<&'static str> {
+    let code = match constellation {
+        Constellation::GPS => match id {
+            2 => "1C",
+            3 => "1P",
+            4 => "1W",
+            8 => "2C",
+            9 => "2P",
+            10 => "2W",
+            15 => "2S",
+            16 => "2L",
+            17 => "2X",
+            22 => "5I",
+            23 => "5Q",
+            24 => "5X",
+            30 => "1S",
+            31 => "1L",
+            32 => "1X",
+            _ => return None,
+        },
+        Constellation::Glonass => match id {
+            2 => "1C",
+            3 => "1P",
+            8 => "2C",
+            9 => "2P",
+            _ => return None,
+        },
+        Constellation::Galileo => match id {
+            2 => "1C",
+            3 => "1A",
+            4 => "1B",
+            5 => "1X",
+            6 => "1Z",
+            8 => "6C",
+            9 => "6A",
+            10 => "6B",
+            11 => "6X",
+            12 => "6Z",
+            14 => "7I",
+            15 => "7Q",
+            16 => "7X",
+            18 => "8I",
+            19 => "8Q",
+            20 => "8X",
+            22 => "5I",
+            23 => "5Q",
+            24 => "5X",
+            _ => return None,
+        },
+        Constellation::BeiDou => match id {
+            2 => "2I",
+            3 => "2Q",
+            4 => "2X",
+            8 => "6I",
+            9 => "6Q",
+            10 => "6X",
+            14 => "7I",
+            15 => "7Q",
+            16 => "7X",
+            22 => "5D",
+            23 => "5P",
+            24 => "5X",
+            _ => return None,
+        },
+        Constellation::QZSS => match id {
+            2 => "1C",
+            9 => "6S",
+            10 => "6L",
+            11 => "6X",
+            15 => "2S",
+            16 => "2L",
+            17 => "2X",
+            22 => "5I",
+            23 => "5Q",
+            24 => "5X",
+            30 => "1S",
+            31 => "1L",
+            32 => "1X",
+            _ => return None,
+        },
+        Constellation::SBAS => match id {
+            2 => "1C",
+            22 => "5I",
+            23 => "5Q",
+            24 => "5X",
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Builds the [Ephemeris] orbit fields, using the RINEX field names
+fn orbits(fields: &[(&str, f64)]) -> HashMap<String, OrbitItem> {
+    fields
+        .iter()
+        .map(|(key, value)| (key.to_string(), OrbitItem::F64(*value)))
+        .collect()
+}
+
+/// RTCM3 [Decoder]
+pub(crate) struct Decoder {
+    /// Reference [Epoch], used to resolve the streamed time of week
+    /// and truncated week numbers.
+    reference: Epoch,
+    /// Decoded content
+    collector: RinexCollector,
+}
+
+impl Decoder {
+    /// Decodes all supported messages of this recorded RTCM3 stream.
+    ///
+    /// Observations only stream their time of week (or time of day for GLONASS),
+    /// which we resolve against the first GPS, Galileo or BeiDou ephemeris,
+    /// or against this `reference` when the stream does not contain any.
+    /// Truncated (GPS) week numbers are resolved against this `reference`, or against
+    /// the first Galileo or BeiDou ephemeris when it is not known.
+    /// Fails with [StreamError::NoReferenceEpoch] when none of them is available.
+    pub fn decode(buf: &[u8], reference: Option<Epoch>) -> Result<RinexCollector, StreamError> {
+        if frames(buf).next().is_none() {
+            return Err(StreamError::NoFrames);
+        }
+
+        let reference = reference
+            .or_else(|| Self::complete_week_reference(buf))
+            .ok_or(StreamError::NoReferenceEpoch)?;
+
+        let mut decoder = Self {
+            reference,
+            collector: Default::default(),
+        };
+
+        // ephemerides first: they stream the week number, observations do not
+        let mut week_reference = Option::<Epoch>::None;
+
+        for payload in frames(buf) {
+            // corrupt or non supported messages are simply skipped
+            if let Ok(Some(toc)) = decoder.decode_ephemeris(payload) {
+                if week_reference.is_none() {
+                    week_reference = Some(toc);
+                }
+            }
+        }
+
+        if let Some(week_reference) = week_reference {
+            decoder.reference = week_reference;
+        }
+
+        for payload in frames(buf) {
+            let _ = decoder.decode_message(payload);
+        }
+
+        if !decoder.collector.has_observations() && !decoder.collector.has_ephemerides() {
+            return Err(StreamError::NoData);
+        }
+
+        Ok(decoder.collector)
+    }
+
+    /// Returns the time of clock of the first Galileo or BeiDou ephemeris of this stream.
+    /// Their week numbers cover 4096 and 8192 weeks: unlike GPS (1024 weeks),
+    /// they are resolved without any reference.
+    fn complete_week_reference(buf: &[u8]) -> Option<Epoch> {
+        let mut decoder = Self {
+            // middle of the Galileo week range
+            reference: Epoch::from_time_of_week(2047, 0, TimeScale::GST),
+            collector: Default::default(),
+        };
+
+        frames(buf).find_map(|payload| {
+            let mut r = BitReader::new(payload);
+            match r.u(12).ok()? as u16 {
+                1042 => decoder.decode_bds_ephemeris(&mut r).ok(),
+                msg @ (1045 | 1046) => decoder.decode_gal_ephemeris(msg, &mut r).ok(),
+                _ => None,
+            }
+        })
+    }
+
+    /// Decodes GPS, Galileo and BeiDou ephemeris messages, and GLONASS frequency channels.
+    /// Returns the time of clock of the decoded ephemeris.
+    fn decode_ephemeris(&mut self, payload: &[u8]) -> Result<Option<Epoch>, StreamError> {
+        let mut r = BitReader::new(payload);
+        let msg = r.u(12)? as u16;
+
+        match msg {
+            1019 => self.decode_gps_ephemeris(&mut r).map(Some),
+            1042 => self.decode_bds_ephemeris(&mut r).map(Some),
+            1045 | 1046 => self.decode_gal_ephemeris(msg, &mut r).map(Some),
+            1020 => {
+                // GLONASS frequency channels are required by all observations
+                let sv = SV::new(Constellation::Glonass, r.u(6)? as u8);
+                let channel = r.u(5)? as i8 - 7;
+                self.collector.set_glonass_channel(sv, channel);
+                Ok(None)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Decodes all other supported messages
+    fn decode_message(&mut self, payload: &[u8]) -> Result<(), StreamError> {
+        let mut r = BitReader::new(payload);
+        let msg = r.u(12)? as u16;
+
+        match msg {
+            1005 | 1006 => self.decode_station(&mut r),
+            1020 => self.decode_glo_ephemeris(&mut r),
+            1071..=1127 if matches!(msg % 10, 4 | 5 | 7) => self.decode_msm(msg, &mut r),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the week number of the reference [Epoch], in this [TimeScale]
+    fn reference_week(&self, timescale: TimeScale) -> u32 {
+        self.reference.to_time_scale(timescale).to_time_of_week().0
+    }
+
+    /// Resolves this GLONASS time of day (in Moscow time),
+    /// to the UTC [Epoch] closest to the reference.
+    fn glonass_epoch(&self, tod_ms: u64) -> Epoch {
+        let moscow = self.reference.to_time_scale(TimeScale::UTC) + Duration::from_hours(3.0);
+        let (y, m, d, _, _, _, _) = moscow.to_gregorian_utc();
+
+        let t = Epoch::from_gregorian_utc_at_midnight(y, m, d)
+            + Duration::from_milliseconds(tod_ms as f64)
+            - Duration::from_hours(3.0);
+
+        closest(t, self.reference, Duration::from_days(1.0))
+    }
+
+    /// Decodes the station ECEF coordinates (1005 / 1006)
+    fn decode_station(&mut self, r: &mut BitReader) -> Result<(), StreamError> {
+        // station ID, ITRF year, GPS, GLONASS, Galileo and reference station indicators
+        r.skip(12 + 6 + 4)?;
+        let x = r.i(38)? as f64 * 1.0E-4;
+        // single receiver oscillator, reserved
+        r.skip(2)?;
+        let y = r.i(38)? as f64 * 1.0E-4;
+        // quarter cycle indicator
+        r.skip(2)?;
+        let z = r.i(38)? as f64 * 1.0E-4;
+
+        self.collector.rx_position = Some((x, y, z));
+        Ok(())
+    }
+
+    /// Decodes one Multiple Signal Message (MSM4, MSM5 or MSM7)
+    fn decode_msm(&mut self, msg: u16, r: &mut BitReader) -> Result<(), StreamError> {
+        let constellation = match msg / 10 {
+            107 => Constellation::GPS,
+            108 => Constellation::Glonass,
+            109 => Constellation::Galileo,
+            110 => Constellation::SBAS,
+            111 => Constellation::QZSS,
+            112 => Constellation::BeiDou,
+            _ => return Ok(()),
+        };
+
+        let msm = msg % 10;
+
+        // station ID
+        r.skip(12)?;
+
+        let t = match constellation {
+            Constellation::Glonass => {
+                // day of week, time of day [ms]
+                r.skip(3)?;
+                self.glonass_epoch(r.u(27)?)
+            }
+            Constellation::Galileo => {
+                resolve_time_of_week(r.u(30)? * 1_000_000, TimeScale::GST, self.reference)
+            }
+            Constellation::BeiDou => {
+                resolve_time_of_week(r.u(30)? * 1_000_000, TimeScale::BDT, self.reference)
+            }
+            _ => resolve_time_of_week(r.u(30)? * 1_000_000, TimeScale::GPST, self.reference),
+        };
+
+        // multiple message, IODS, reserved, clock steering, external clock,
+        // smoothing indicator and interval
+        r.skip(1 + 3 + 7 + 2 + 2 + 1 + 3)?;
+
+        let sat_mask = r.u(64)?;
+        let sig_mask = r.u(32)?;
+
+        let satellites = (0..64u8)
+            .filter(|i| sat_mask & (1 << (63 - i)) != 0)
+            .map(|i| i + 1)
+            .collect::<Vec<_>>();
+
+        let signals = (0..32u8)
+            .filter(|i| sig_mask & (1 << (31 - i)) != 0)
+            .map(|i| i + 1)
+            .collect::<Vec<_>>();
+
+        let (nsat, nsig) = (satellites.len(), signals.len());
+
+        if nsat * nsig > 64 {
+            return Err(StreamError::InvalidMessage(msg));
+        }
+
+        let mut cells = Vec::with_capacity(nsat * nsig);
+        for _ in 0..nsat * nsig {
+            cells.push(r.bit()?);
+        }
+
+        let ncell = cells.iter().filter(|cell| **cell).count();
+
+        // satellite data
+        let mut rough_ms = Vec::with_capacity(nsat);
+        for _ in 0..nsat {
+            let int_ms = r.u(8)?;
+            rough_ms.push(if int_ms == 255 {
+                None
+            } else {
+                Some(int_ms as f64)
+            });
+        }
+
+        let mut extended = vec![None; nsat];
+        if msm != 4 {
+            for info in extended.iter_mut() {
+                *info = Some(r.u(4)?);
+            }
+        }
+
+        for rough in rough_ms.iter_mut() {
+            let mod_ms = r.u(10)? as f64 * p2(-10);
+            *rough = rough.map(|int_ms| int_ms + mod_ms);
+        }
+
+        let mut rough_rate = vec![None; nsat];
+        if msm != 4 {
+            for rate in rough_rate.iter_mut() {
+                let value = r.i(14)?;
+                *rate = if value == -8192 {
+                    None
+                } else {
+                    Some(value as f64)
+                };
+            }
+        }
+
+        // signal data
+        let (pr_bits, pr_scale, ph_bits, ph_scale, lock_bits, cnr_bits, cnr_scale) = if msm == 7 {
+            (20, p2(-29), 24, p2(-31), 10, 10, p2(-4))
+        } else {
+            (15, p2(-24), 22, p2(-29), 4, 6, 1.0)
+        };
+
+        let mut fine_pr = Vec::with_capacity(ncell);
+        for _ in 0..ncell {
+            let value = r.i(pr_bits)?;
+            fine_pr.push(if value == -(1 << (pr_bits - 1)) {
+                None
+            } else {
+                Some(value as f64 * pr_scale)
+            });
+        }
+
+        let mut fine_ph = Vec::with_capacity(ncell);
+        for _ in 0..ncell {
+            let value = r.i(ph_bits)?;
+            fine_ph.push(if value == -(1 << (ph_bits - 1)) {
+                None
+            } else {
+                Some(value as f64 * ph_scale)
+            });
+        }
+
+        // lock time indicators, half cycle ambiguities
+        r.skip(ncell * (lock_bits + 1))?;
+
+        let mut cnr = Vec::with_capacity(ncell);
+        for _ in 0..ncell {
+            let value = r.u(cnr_bits)?;
+            cnr.push(if value == 0 {
+                None
+            } else {
+                Some(value as f64 * cnr_scale)
+            });
+        }
+
+        let mut fine_rate = vec![None; ncell];
+        if msm != 4 {
+            for rate in fine_rate.iter_mut() {
+                let value = r.i(15)?;
+                *rate = if value == -16384 {
+                    None
+                } else {
+                    Some(value as f64 * 1.0E-4)
+                };
+            }
+        }
+
+        let ms_to_m = SPEED_OF_LIGHT_M_S * 1.0E-3;
+        let mut cell = 0;
+
+        for (i, sat) in satellites.iter().enumerate() {
+            let sv = match constellation {
+                // SBAS PRN 120 is satellite ID 1
+                Constellation::SBAS => SV::new(constellation, sat + 19),
+                _ => SV::new(constellation, *sat),
+            };
+
+            if constellation == Constellation::Glonass {
+                // extended satellite info is the frequency channel + 7
+                if let Some(info) = extended[i].filter(|info| *info <= 13) {
+                    self.collector.set_glonass_channel(sv, info as i8 - 7);
+                }
+            }
+
+            for (j, sig) in signals.iter().enumerate() {
+                if !cells[i * nsig + j] {
+                    continue;
+                }
+
+                let k = cell;
+                cell += 1;
+
+                let Some(code) = msm_signal_code(constellation, *sig) else {
+                    continue;
+                };
+
+                let wavelength_m = code
+                    .chars()
+                    .next()
+                    .and_then(|band| {
+                        carrier_frequency_hz(
+                            constellation,
+                            band,
+                            self.collector.glonass_channel(sv),
+                        )
+                    })
+                    .map(|hz| SPEED_OF_LIGHT_M_S / hz);
+
+                let pseudo_range_m = rough_ms[i]
+                    .zip(fine_pr[k])
+                    .map(|(rough, fine)| (rough + fine) * ms_to_m);
+
+                let phase_cycles = rough_ms[i]
+                    .zip(fine_ph[k])
+                    .zip(wavelength_m)
+                    .map(|((rough, fine), lambda)| (rough + fine) * ms_to_m / lambda);
+
+                let doppler_hz = rough_rate[i]
+                    .zip(fine_rate[k])
+                    .zip(wavelength_m)
+                    .map(|((rough, fine), lambda)| -(rough + fine) / lambda);
+
+                self.collector.add_signal(
+                    t,
+                    sv,
+                    code,
+                    pseudo_range_m,
+                    phase_cycles,
+                    doppler_hz,
+                    cnr[k],
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Decodes one GPS ephemeris (1019)
+    fn decode_gps_ephemeris(&mut self, r: &mut BitReader) -> Result<Epoch, StreamError> {
+        let prn = r.u(6)? as u8;
+        let week = resolve_week(r.u(10)? as u32, 1024, self.reference_week(TimeScale::GPST));
+        let accuracy = r.u(4)? as f64;
+        let l2_codes = r.u(2)? as f64;
+        let idot = r.i(14)? as f64 * p2(-43) * SC2RAD;
+        let iode = r.u(8)? as f64;
+        let toc = r.u(16)? as f64 * 16.0;
+        let af2 = r.i(8)? as f64 * p2(-55);
+        let af1 = r.i(16)? as f64 * p2(-43);
+        let af0 = r.i(22)? as f64 * p2(-31);
+        let iodc = r.u(10)? as f64;
+        let crs = r.i(16)? as f64 * p2(-5);
+        let delta_n = r.i(16)? as f64 * p2(-43) * SC2RAD;
+        let m0 = r.i(32)? as f64 * p2(-31) * SC2RAD;
+        let cuc = r.i(16)? as f64 * p2(-29);
+        let e = r.u(32)? as f64 * p2(-33);
+        let cus = r.i(16)? as f64 * p2(-29);
+        let sqrt_a = r.u(32)? as f64 * p2(-19);
+        let toe = r.u(16)? as f64 * 16.0;
+        let cic = r.i(16)? as f64 * p2(-29);
+        let omega0 = r.i(32)? as f64 * p2(-31) * SC2RAD;
+        let cis = r.i(16)? as f64 * p2(-29);
+        let i0 = r.i(32)? as f64 * p2(-31) * SC2RAD;
+        let crc = r.i(16)? as f64 * p2(-5);
+        let omega = r.i(32)? as f64 * p2(-31) * SC2RAD;
+        let omega_dot = r.i(24)? as f64 * p2(-43) * SC2RAD;
+        let tgd = r.i(8)? as f64 * p2(-31);
+        let health = r.u(6)? as f64;
+        let l2p_flag = r.u(1)? as f64;
+        let fit = r.u(1)? as f64;
+
+        let toc = Epoch::from_time_of_week(week, (toc * 1.0E9) as u64, TimeScale::GPST);
+
+        let ephemeris = Ephemeris {
+            clock_bias: af0,
+            clock_drift: af1,
+            clock_drift_rate: af2,
+            orbits: orbits(&[
+                ("iode", iode),
+                ("crs", crs),
+                ("deltaN", delta_n),
+                ("m0", m0),
+                ("cuc", cuc),
+                ("e", e),
+                ("cus", cus),
+                ("sqrta", sqrt_a),
+                ("toe", toe),
+                ("cic", cic),
+                ("omega0", omega0),
+                ("cis", cis),
+                ("i0", i0),
+                ("crc", crc),
+                ("omega", omega),
+                ("omegaDot", omega_dot),
+                ("idot", idot),
+                ("l2Codes", l2_codes),
+                ("week", week as f64),
+                ("l2pDataFlag", l2p_flag),
+                ("svAccuracy", accuracy),
+                ("health", health),
+                ("tgd", tgd),
+                ("iodc", iodc),
+                ("fitInt", fit),
+            ]),
+        };
+
+        let sv = SV::new(Constellation::GPS, prn);
+
+        self.collector
+            .add_ephemeris(toc, sv, NavMessageType::LNAV, ephemeris);
+
+        Ok(toc)
+    }
+
+    /// Decodes one Galileo F/NAV (1045) or I/NAV (1046) ephemeris
+    fn decode_gal_ephemeris(&mut self, msg: u16, r: &mut BitReader) -> Result<Epoch, StreamError> {
+        let prn = r.u(6)? as u8;
+        let week = resolve_week(r.u(12)? as u32, 4096, self.reference_week(TimeScale::GST));
+        let iodnav = r.u(10)? as f64;
+        let sisa = r.u(8)? as f64;
+        let idot = r.i(14)? as f64 * p2(-43) * SC2RAD;
+        let toc = r.u(14)? as f64 * 60.0;
+        let af2 = r.i(6)? as f64 * p2(-59);
+        let af1 = r.i(21)? as f64 * p2(-46);
+        let af0 = r.i(31)? as f64 * p2(-34);
+        let crs = r.i(16)? as f64 * p2(-5);
+        let delta_n = r.i(16)? as f64 * p2(-43) * SC2RAD;
+        let m0 = r.i(32)? as f64 * p2(-31) * SC2RAD;
+        let cuc = r.i(16)? as f64 * p2(-29);
+        let e = r.u(32)? as f64 * p2(-33);
+        let cus = r.i(16)? as f64 * p2(-29);
+        let sqrt_a = r.u(32)? as f64 * p2(-19);
+        let toe = r.u(14)? as f64 * 60.0;
+        let cic = r.i(16)? as f64 * p2(-29);
+        let omega0 = r.i(32)? as f64 * p2(-31) * SC2RAD;
+        let cis = r.i(16)? as f64 * p2(-29);
+        let i0 = r.i(32)? as f64 * p2(-31) * SC2RAD;
+        let crc = r.i(16)? as f64 * p2(-5);
+        let omega = r.i(32)? as f64 * p2(-31) * SC2RAD;
+        let omega_dot = r.i(24)? as f64 * p2(-43) * SC2RAD;
+        let bgd_e5a_e1 = r.i(10)? as f64 * p2(-32);
+
+        let (msgtype, bgd_e5b_e1, health) = if msg == 1045 {
+            // E5a signal health and data validity
+            let health = (r.u(2)? << 4) | (r.u(1)? << 3);
+            (NavMessageType::FNAV, 0.0, health as f64)
+        } else {
+            let bgd_e5b_e1 = r.i(10)? as f64 * p2(-32);
+            // E5b and E1B signal health and data validity
+            let e5b_hs = r.u(2)?;
+            let e5b_dvs = r.u(1)?;
+            let e1b_hs = r.u(2)?;
+            let e1b_dvs = r.u(1)?;
+            let health = e1b_dvs | (e1b_hs << 1) | (e5b_dvs << 6) | (e5b_hs << 7);
+            (NavMessageType::INAV, bgd_e5b_e1, health as f64)
+        };
+
+        let toc = Epoch::from_time_of_week(week, (toc * 1.0E9) as u64, TimeScale::GST);
+
+        let ephemeris = Ephemeris {
+            clock_bias: af0,
+            clock_drift: af1,
+            clock_drift_rate: af2,
+            orbits: orbits(&[
+                ("iodnav", iodnav),
+                ("crs", crs),
+                ("deltaN", delta_n),
+                ("m0", m0),
+                ("cuc", cuc),
+                ("e", e),
+                ("cus", cus),
+                ("sqrta", sqrt_a),
+                ("toe", toe),
+                ("cic", cic),
+                ("omega0", omega0),
+                ("cis", cis),
+                ("i0", i0),
+                ("crc", crc),
+                ("omega", omega),
+                ("omegaDot", omega_dot),
+                ("idot", idot),
+                // RINEX uses continuous (GPS aligned) week numbers
+                ("week", (week + 1024) as f64),
+                ("sisa", sisa),
+                ("health", health),
+                ("bgdE5aE1", bgd_e5a_e1),
+                ("bgdE5bE1", bgd_e5b_e1),
+            ]),
+        };
+
+        let sv = SV::new(Constellation::Galileo, prn);
+        self.collector.add_ephemeris(toc, sv, msgtype, ephemeris);
+
+        Ok(toc)
+    }
+
+    /// Decodes one BeiDou ephemeris (1042)
+    fn decode_bds_ephemeris(&mut self, r: &mut BitReader) -> Result<Epoch, StreamError> {
+        let prn = r.u(6)? as u8;
+        let week = resolve_week(r.u(13)? as u32, 8192, self.reference_week(TimeScale::BDT));
+        let accuracy = r.u(4)? as f64;
+        let idot = r.i(14)? as f64 * p2(-43) * SC2RAD;
+        let aode = r.u(5)? as f64;
+        let toc = r.u(17)? as f64 * 8.0;
+        let a2 = r.i(11)? as f64 * p2(-66);
+        let a1 = r.i(22)? as f64 * p2(-50);
+        let a0 = r.i(24)? as f64 * p2(-33);
+        let aodc = r.u(5)? as f64;
+        let crs = r.i(18)? as f64 * p2(-6);
+        let delta_n = r.i(16)? as f64 * p2(-43) * SC2RAD;
+        let m0 = r.i(32)? as f64 * p2(-31) * SC2RAD;
+        let cuc = r.i(18)? as f64 * p2(-31);
+        let e = r.u(32)? as f64 * p2(-33);
+        let cus = r.i(18)? as f64 * p2(-31);
+        let sqrt_a = r.u(32)? as f64 * p2(-19);
+        let toe = r.u(17)? as f64 * 8.0;
+        let cic = r.i(18)? as f64 * p2(-31);
+        let omega0 = r.i(32)? as f64 * p2(-31) * SC2RAD;
+        let cis = r.i(18)? as f64 * p2(-31);
+        let i0 = r.i(32)? as f64 * p2(-31) * SC2RAD;
+        let crc = r.i(18)? as f64 * p2(-6);
+        let omega = r.i(32)? as f64 * p2(-31) * SC2RAD;
+        let omega_dot = r.i(24)? as f64 * p2(-43) * SC2RAD;
+        let tgd1 = r.i(10)? as f64 * 1.0E-10;
+        let tgd2 = r.i(10)? as f64 * 1.0E-10;
+        let health = r.u(1)? as f64;
+
+        let toc = Epoch::from_time_of_week(week, (toc * 1.0E9) as u64, TimeScale::BDT);
+
+        let ephemeris = Ephemeris {
+            clock_bias: a0,
+            clock_drift: a1,
+            clock_drift_rate: a2,
+            orbits: orbits(&[
+                ("aode", aode),
+                ("crs", crs),
+                ("deltaN", delta_n),
+                ("m0", m0),
+                ("cuc", cuc),
+                ("e", e),
+                ("cus", cus),
+                ("sqrta", sqrt_a),
+                ("toe", toe),
+                ("cic", cic),
+                ("omega0", omega0),
+                ("cis", cis),
+                ("i0", i0),
+                ("crc", crc),
+                ("omega", omega),
+                ("omegaDot", omega_dot),
+                ("idot", idot),
+                ("week", week as f64),
+                ("svAccuracy", accuracy),
+                ("satH1", health),
+                ("tgd1b1b3", tgd1),
+                ("tgd2b2b3", tgd2),
+                ("aodc", aodc),
+            ]),
+        };
+
+        // GEO satellites stream the D2 message
+        let msgtype = if prn <= 5 || prn >= 59 {
+            NavMessageType::D2
+        } else {
+            NavMessageType::D1
+        };
+
+        let sv = SV::new(Constellation::BeiDou, prn);
+        self.collector.add_ephemeris(toc, sv, msgtype, ephemeris);
+
+        Ok(toc)
+    }
+
+    /// Decodes one GLONASS ephemeris (1020)
+    fn decode_glo_ephemeris(&mut self, r: &mut BitReader) -> Result<(), StreamError> {
+        let prn = r.u(6)? as u8;
+        let channel = r.u(5)? as i8 - 7;
+        // almanac health, health availability, P1, tk
+        r.skip(1 + 1 + 2 + 12)?;
+        let health = r.u(1)? as f64;
+        // P2
+        r.skip(1)?;
+        let tb = r.u(7)?;
+
+        let (mut pos_km, mut vel_km_s, mut accel_km_s2) =
+            ([0.0_f64; 3], [0.0_f64; 3], [0.0_f64; 3]);
+
+        for ((pos, vel), accel) in pos_km
+            .iter_mut()
+            .zip(vel_km_s.iter_mut())
+            .zip(accel_km_s2.iter_mut())
+        {
+            *vel = r.sm(24)? as f64 * p2(-20);
+            *pos = r.sm(27)? as f64 * p2(-11);
+            *accel = r.sm(5)? as f64 * p2(-30);
+        }
+
+        // P3
+        r.skip(1)?;
+        let gamma_n = r.sm(11)? as f64 * p2(-40);
+        // P, ln
+        r.skip(2 + 1)?;
+        let tau_n = r.sm(22)? as f64 * p2(-30);
+        // delta tau
+        r.skip(5)?;
+        let age = r.u(5)? as f64;
+
+        // tb is a 15' interval of the day, in Moscow time
+        let toc = self.glonass_epoch(tb * 15 * 60 * 1000);
+
+        let ephemeris = Ephemeris {
+            clock_bias: -tau_n,
+            clock_drift: gamma_n,
+            // the message frame time is not retained
+            clock_drift_rate: 0.0,
+            orbits: orbits(&[
+                ("satPosX", pos_km[0]),
+                ("velX", vel_km_s[0]),
+                ("accelX", accel_km_s2[0]),
+                ("health", health),
+                ("satPosY", pos_km[1]),
+                ("velY", vel_km_s[1]),
+                ("accelY", accel_km_s2[1]),
+                ("channel", channel as f64),
+                ("satPosZ", pos_km[2]),
+                ("velZ", vel_km_s[2]),
+                ("accelZ", accel_km_s2[2]),
+                ("ageOp", age),
+            ]),
+        };
+
+        let sv = SV::new(Constellation::Glonass, prn);
+
+        self.collector.set_glonass_channel(sv, channel);
+        self.collector
+            .add_ephemeris(toc, sv, NavMessageType::FDMA, ephemeris);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{crc24q, frames, is_rtcm3, Decoder};
+    use crate::{
+        prelude::{Constellation, Epoch, ProductType, SV},
+        tests::toolkit::rtcm::{gps_ephemeris, gps_msm4, MSG_1005},
+    };
+    use std::str::FromStr;
+
+    #[test]
+    fn rtcm3_framing() {
+        assert_eq!(crc24q(&[]), 0);
+        assert_eq!(crc24q(&MSG_1005[..22]), 0x360B98);
+
+        // garbage and corrupt frames are skipped
+        let mut buf = vec![0x00, 0xD3, 0x42];
+        buf.extend_from_slice(&MSG_1005);
+        let mut corrupt = MSG_1005;
+        corrupt[10] ^= 0x01;
+        buf.extend_from_slice(&corrupt);
+        buf.extend_from_slice(&MSG_1005);
+
+        assert!(is_rtcm3(&buf));
+        assert_eq!(frames(&buf).count(), 2);
+        assert!(!is_rtcm3(&[0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06]));
+    }
+
+    #[test]
+    fn rtcm3_station_msm4() {
+        let reference = Epoch::from_str("2024-01-10T00:00:00 GPST").unwrap();
+        let expected = Epoch::from_str("2024-01-09T12:00:00 GPST").unwrap();
+
+        // tuesday 12:00:00
+        let tow_ms = (2 * 86400 + 12 * 3600) * 1000;
+
+        let mut buf = MSG_1005.to_vec();
+        buf.extend(gps_msm4(5, tow_ms, 72.5, 72.4999, 45));
+
+        let collector = Decoder::decode(&buf, Some(reference)).unwrap();
+
+        assert!(collector.has_observations());
+        assert!(!collector.has_ephemerides());
+        assert!(collector.navigation().is_none());
+
+        let (x, y, z) = collector.rx_position.unwrap();
+        assert!((x - 1114104.5999).abs() < 1.0E-3);
+        assert!((y + 4850729.7108).abs() < 1.0E-3);
+        assert!((z - 3975521.4643).abs() < 1.0E-3);
+
+        let rinex = collector.observation().unwrap();
+        assert_eq!(
+            ProductType::from(rinex.header.rinex_type),
+            ProductType::Observation
+        );
+        assert_eq!(rinex.first_epoch(), Some(expected));
+
+        let g05 = SV::new(Constellation::GPS, 5);
+        let lambda = 299_792_458.0 / 1575.42E6;
+
+        let mut nobs = 0;
+
+        for (_, signal) in rinex.signal_observations_iter() {
+            assert_eq!(signal.sv, g05);
+            match signal.observable.to_string().as_str() {
+                "C1C" => assert!((signal.value - 72.5 * 299_792.458).abs() < 0.01),
+                "L1C" => assert!((signal.value - 72.4999 * 299_792.458 / lambda).abs() < 0.01),
+                "S1C" => assert_eq!(signal.value, 45.0),
+                observable => panic!("unexpected observable {}", observable),
+            }
+            nobs += 1;
+        }
+
+        assert_eq!(nobs, 3);
+    }
+
+    #[test]
+    fn rtcm3_gps_ephemeris() {
+        let buf = gps_ephemeris(12, 2296, 7200);
+
+        // the truncated week number is resolved against the reference
+        let reference = Epoch::from_str("2024-01-10T00:00:00 GPST").unwrap();
+        let collector = Decoder::decode(&buf, Some(reference)).unwrap();
+
+        assert!(collector.has_ephemerides());
+
+        let rinex = collector.navigation().unwrap();
+        assert_eq!(
+            ProductType::from(rinex.header.rinex_type),
+            ProductType::BroadcastNavigation
+        );
+
+        let (key, ephemeris) = rinex.nav_ephemeris_frames_iter().next().unwrap();
+
+        assert_eq!(key.sv, SV::new(Constellation::GPS, 12));
+        assert_eq!(
+            key.epoch,
+            Epoch::from_str("2024-01-07T02:00:00 GPST").unwrap()
+        );
+        assert_eq!(ephemeris.clock_bias, 1000.0 * 2.0_f64.powi(-31));
+        assert_eq!(ephemeris.clock_drift, -10.0 * 2.0_f64.powi(-43));
+        assert_eq!(ephemeris.get_orbit_f64("sqrta"), Some(5153.0));
+        assert_eq!(ephemeris.get_orbit_f64("e"), Some(2.0_f64.powi(-6)));
+        assert_eq!(ephemeris.get_orbit_f64("week"), Some(2296.0));
+        assert_eq!(
+            ephemeris.get_orbit_f64("i0"),
+            Some(std::f64::consts::PI / 4.0)
+        );
+    }
+}
@@ -0,0 +1,778 @@
+//! u-blox UBX messages decoding: RXM-RAWX observations, and RXM-SFRBX
+//! GPS / QZSS LNAV subframes and Galileo I/NAV words, decoded into ephemerides.
+use std::collections::HashMap;
+
+use rinex::navigation::{Ephemeris, NavMessageType, OrbitItem};
+
+use crate::{
+    prelude::{Constellation, Epoch, TimeScale, SV},
+    stream::{resolve_time_of_week, resolve_week, BitReader, RinexCollector, StreamError},
+};
+
+/// UBX frame sync characters
+pub(crate) const SYNC: [u8; 2] = [0xB5, 0x62];
+
+/// RXM class
+const CLASS_RXM: u8 = 0x02;
+
+/// RXM-RAWX: multi GNSS raw measurements
+const ID_RAWX: u8 = 0x15;
+
+/// RXM-SFRBX: broadcast navigation data subframes
+const ID_SFRBX: u8 = 0x13;
+
+/// Semi circles to radians
+const SC2RAD: f64 = std::f64::consts::PI;
+
+/// Returns 2^exp
+fn p2(exp: i32) -> f64 {
+    2.0_f64.powi(exp)
+}
+
+/// Computes the 8 bit Fletcher checksum of this buffer
+pub(crate) fn checksum(buf: &[u8]) -> (u8, u8) {
+    let (mut ck_a, mut ck_b) = (0u8, 0u8);
+
+    for byte in buf {
+        ck_a = ck_a.wrapping_add(*byte);
+        ck_b = ck_b.wrapping_add(ck_a);
+    }
+
+    (ck_a, ck_b)
+}
+
+/// One UBX message
+pub(crate) struct Message<'a> {
+    /// Message class
+    pub class: u8,
+    /// Message ID
+    pub id: u8,
+    /// Payload
+    pub payload: &'a [u8],
+}
+
+/// Iterates over each valid UBX message of this buffer.
+/// Bytes that do not belong to a valid frame (partial or corrupt frames,
+/// NMEA sentences, other protocols) are skipped.
+pub(crate) fn messages(buf: &[u8]) -> impl Iterator<Item = Message<'_>> + '_ {
+    let mut pos = 0;
+
+    std::iter::from_fn(move || {
+        while pos + 8 <= buf.len() {
+            if buf[pos..pos + 2] != SYNC {
+                pos += 1;
+                continue;
+            }
+
+            let len = u16::from_le_bytes([buf[pos + 4], buf[pos + 5]]) as usize;
+            let end = pos + 6 + len + 2;
+
+            if end > buf.len() {
+                pos += 1;
+                continue;
+            }
+
+            if checksum(&buf[pos + 2..end - 2]) != (buf[end - 2], buf[end - 1]) {
+                pos += 1;
+                continue;
+            }
+
+            let message = Message {
+                class: buf[pos + 2],
+                id: buf[pos + 3],
+                payload: &buf[pos + 6..end - 2],
+            };
+
+            pos = end;
+            return Some(message);
+        }
+
+        None
+    })
+}
+
+/// True if this buffer contains at least one valid UBX message
+pub(crate) fn is_ubx(buf: &[u8]) -> bool {
+    messages(buf).next().is_some()
+}
+
+/// Returns the [Constellation] of this UBX GNSS identifier
+fn ubx_constellation(gnss_id: u8) -> Option<Constellation> {
+    match gnss_id {
+        0 => Some(Constellation::GPS),
+        1 => Some(Constellation::SBAS),
+        2 => Some(Constellation::Galileo),
+        3 => Some(Constellation::BeiDou),
+        5 => Some(Constellation::QZSS),
+        6 => Some(Constellation::Glonass),
+        _ => None,
+    }
+}
+
+/// Returns the RINEX code (without physics) of this UBX signal identifier
+fn ubx_signal_code(constellation: Constellation, sig_id: u8) -> Option<&'static str> {
+    let code = match (constellation, sig_id) {
+        (Constellation::GPS, 0) => "1C",
+        (Constellation::GPS, 3) => "2L",
+        (Constellation::GPS, 4) => "2S",
+        (Constellation::GPS, 6) => "5I",
+        (Constellation::GPS, 7) => "5Q",
+        (Constellation::SBAS, 0) => "1C",
+        (Constellation::Galileo, 0) => "1C",
+        (Constellation::Galileo, 1) => "1B",
+        (Constellation::Galileo, 3) => "5I",
+        (Constellation::Galileo, 4) => "5Q",
+        (Constellation::Galileo, 5) => "7I",
+        (Constellation::Galileo, 6) => "7Q",
+        (Constellation::Galileo, 8) => "6B",
+        (Constellation::Galileo, 9) => "6C",
+        (Constellation::Galileo, 10) => "6A",
+        (Constellation::BeiDou, 0 | 1) => "2I",
+        (Constellation::BeiDou, 2 | 3) => "7I",
+        (Constellation::BeiDou, 4 | 10) => "6I",
+        (Constellation::BeiDou, 5) => "1P",
+        (Constellation::BeiDou, 6) => "1D",
+        (Constellation::BeiDou, 7) => "5P",
+        (Constellation::BeiDou, 8) => "5D",
+        (Constellation::QZSS, 0) => "1C",
+        (Constellation::QZSS, 1) => "1Z",
+        (Constellation::QZSS, 4) => "2S",
+        (Constellation::QZSS, 5) => "2L",
+        (Constellation::QZSS, 8) => "5I",
+        (Constellation::QZSS, 9) => "5Q",
+        (Constellation::Glonass, 0) => "1C",
+        (Constellation::Glonass, 2) => "2C",
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Returns the [SV] of this UBX satellite identifier
+fn ubx_satellite(constellation: Constellation, sv_id: u8) -> Option<SV> {
+    match constellation {
+        // SBAS are identified by their PRN (120..158)
+        Constellation::SBAS => Some(SV::new(constellation, sv_id.checked_sub(100)?)),
+        _ => Some(SV::new(constellation, sv_id)),
+    }
+}
+
+/// Concatenates these bit ranges (start, length) of this buffer, MSB first
+fn extract_bits(buf: &[u8], ranges: &[(usize, usize)]) -> Vec<u8> {
+    let total = ranges.iter().map(|(_, len)| len).sum::<usize>();
+    let mut bytes = vec![0u8; total.div_ceil(8)];
+    let mut pos = 0;
+
+    for (start, len) in ranges.iter() {
+        for bit in *start..start + len {
+            if buf[bit / 8] & (0x80 >> (bit % 8)) != 0 {
+                bytes[pos / 8] |= 0x80 >> (pos % 8);
+            }
+            pos += 1;
+        }
+    }
+
+    bytes
+}
+
+/// Returns the measurement [Epoch] of this RXM-RAWX payload (complete GPS week number)
+fn rawx_epoch(payload: &[u8]) -> Result<Epoch, StreamError> {
+    if payload.len() < 16 {
+        return Err(StreamError::Truncated);
+    }
+
+    let tow_s = f64::from_le_bytes(payload[0..8].try_into().unwrap());
+    let week = u16::from_le_bytes([payload[8], payload[9]]);
+
+    Ok(Epoch::from_time_of_week(
+        week as u32,
+        (tow_s * 1.0E9).round() as u64,
+        TimeScale::GPST,
+    ))
+}
+
+/// Builds the [Ephemeris] orbit fields, using the RINEX field names
+fn orbits(fields: &[(&str, f64)]) -> HashMap<String, OrbitItem> {
+    fields
+        .iter()
+        .map(|(key, value)| (key.to_string(), OrbitItem::F64(*value)))
+        .collect()
+}
+
+/// UBX [Decoder]
+pub(crate) struct Decoder {
+    /// Reference [Epoch], used to resolve truncated week numbers
+    reference: Option<Epoch>,
+    /// Decoded content
+    collector: RinexCollector,
+    /// Latest LNAV subframes 1, 2 and 3 (24 bit data words), per GPS / QZSS [SV]
+    lnav: HashMap<SV, [Option<Vec<u8>>; 3]>,
+    /// Latest I/NAV words 1 to 5, per Galileo [SV]
+    inav: HashMap<SV, [Option<Vec<u8>>; 5]>,
+}
+
+impl Decoder {
+    /// Decodes all supported messages of this UBX log.
+    ///
+    /// Subframes only stream truncated week numbers, which we resolve against the
+    /// first RXM-RAWX epoch, or against this `reference` when the log does not contain any.
+    /// Subframes that may not be resolved are skipped: fails with
+    /// [StreamError::NoReferenceEpoch] when nothing else was decoded.
+    pub fn decode(buf: &[u8], reference: Option<Epoch>) -> Result<RinexCollector, StreamError> {
+        // RXM-RAWX first: it streams the week number, subframes may precede it
+        let reference = messages(buf)
+            .filter(|message| (message.class, message.id) == (CLASS_RXM, ID_RAWX))
+            .find_map(|message| rawx_epoch(message.payload).ok())
+            .or(reference);
+
+        let mut decoder = Self {
+            reference,
+            collector: Default::default(),
+            lnav: Default::default(),
+            inav: Default::default(),
+        };
+
+        let mut nmessages = 0;
+        let mut unresolved = false;
+
+        for message in messages(buf) {
+            nmessages += 1;
+
+            // corrupt or non supported messages are simply skipped
+            if let Err(StreamError::NoReferenceEpoch) = decoder.decode_message(&message) {
+                unresolved = true;
+            }
+        }
+
+        if nmessages == 0 {
+            return Err(StreamError::NoFrames);
+        }
+
+        if !decoder.collector.has_observations() && !decoder.collector.has_ephemerides() {
+            if unresolved {
+                return Err(StreamError::NoReferenceEpoch);
+            }
+            return Err(StreamError::NoData);
+        }
+
+        Ok(decoder.collector)
+    }
+
+    /// Decodes one supported message
+    fn decode_message(&mut self, message: &Message) -> Result<(), StreamError> {
+        match (message.class, message.id) {
+            (CLASS_RXM, ID_RAWX) => self.decode_rawx(message.payload),
+            (CLASS_RXM, ID_SFRBX) => self.decode_sfrbx(message.payload),
+            _ => Ok(()),
+        }
+    }
+
+    /// Decodes one RXM-RAWX message
+    fn decode_rawx(&mut self, payload: &[u8]) -> Result<(), StreamError> {
+        let t = rawx_epoch(payload)?;
+        let nmeas = payload[11] as usize;
+
+        if payload.len() < 16 + 32 * nmeas {
+            return Err(StreamError::Truncated);
+        }
+
+        for meas in payload[16..16 + 32 * nmeas].chunks_exact(32) {
+            let pseudo_range_m = f64::from_le_bytes(meas[0..8].try_into().unwrap());
+            let phase_cycles = f64::from_le_bytes(meas[8..16].try_into().unwrap());
+            let doppler_hz = f32::from_le_bytes(meas[16..20].try_into().unwrap()) as f64;
+            let (gnss_id, sv_id, sig_id, freq_id) = (meas[20], meas[21], meas[22], meas[23]);
+            let cno = meas[26];
+            let trk_stat = meas[30];
+
+            let Some(constellation) = ubx_constellation(gnss_id) else {
+                continue;
+            };
+
+            let Some(sv) = ubx_satellite(constellation, sv_id) else {
+                continue;
+            };
+
+            let Some(code) = ubx_signal_code(constellation, sig_id) else {
+                continue;
+            };
+
+            // frequency channel + 7
+            if constellation == Constellation::Glonass && freq_id <= 13 {
+                self.collector.set_glonass_channel(sv, freq_id as i8 - 7);
+            }
+
+            self.collector.add_signal(
+                t,
+                sv,
+                code,
+                // pseudo range and carrier phase validity
+                if trk_stat & 0x01 != 0 {
+                    Some(pseudo_range_m)
+                } else {
+                    None
+                },
+                if trk_stat & 0x02 != 0 {
+                    Some(phase_cycles)
+                } else {
+                    None
+                },
+                Some(doppler_hz),
+                if cno > 0 { Some(cno as f64) } else { None },
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Decodes one RXM-SFRBX message
+    fn decode_sfrbx(&mut self, payload: &[u8]) -> Result<(), StreamError> {
+        if payload.len() < 8 {
+            return Err(StreamError::Truncated);
+        }
+
+        let (gnss_id, sv_id, num_words) = (payload[0], payload[1], payload[4] as usize);
+
+        if payload.len() < 8 + 4 * num_words {
+            return Err(StreamError::Truncated);
+        }
+
+        // words are streamed little endian: we store them MSB first
+        let words = payload[8..8 + 4 * num_words]
+            .chunks_exact(4)
+            .flat_map(|word| {
+                u32::from_le_bytes(word.try_into().unwrap())
+                    .to_be_bytes()
+                    .into_iter()
+            })
+            .collect::<Vec<_>>();
+
+        match ubx_constellation(gnss_id) {
+            Some(Constellation::GPS) if num_words >= 10 => {
+                self.decode_lnav(SV::new(Constellation::GPS, sv_id), &words)
+            }
+            Some(Constellation::QZSS) if num_words >= 10 => {
+                self.decode_lnav(SV::new(Constellation::QZSS, sv_id), &words)
+            }
+            Some(Constellation::Galileo) if num_words >= 8 => {
+                self.decode_inav(SV::new(Constellation::Galileo, sv_id), &words)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Stores one LNAV subframe, and decodes the ephemeris once complete.
+    fn decode_lnav(&mut self, sv: SV, words: &[u8]) -> Result<(), StreamError> {
+        // 30 bit words: 24 data bits followed by 6 parity bits
+        let ranges = (0..10).map(|i| (32 * i + 2, 24)).collect::<Vec<_>>();
+        let subframe = extract_bits(words, &ranges);
+
+        // TLM preamble
+        if subframe[0] != 0x8B {
+            return Err(StreamError::InvalidMessage(ID_SFRBX as u16));
+        }
+
+        let mut r = BitReader::new(&subframe);
+        r.skip(24 + 19)?;
+        let id = r.u(3)? as usize;
+
+        // almanac pages (4 and 5) are not used
+        if !(1..=3).contains(&id) {
+            return Ok(());
+        }
+
+        let subframes = self.lnav.entry(sv).or_default();
+        subframes[id - 1] = Some(subframe);
+
+        let [Some(sf1), Some(sf2), Some(sf3)] = subframes.clone() else {
+            return Ok(());
+        };
+
+        let Some((toc, ephemeris)) = self.lnav_ephemeris(&sf1, &sf2, &sf3)? else {
+            // issues of data do not match (yet)
+            return Ok(());
+        };
+
+        self.lnav.remove(&sv);
+        self.collector
+            .add_ephemeris(toc, sv, NavMessageType::LNAV, ephemeris);
+
+        Ok(())
+    }
+
+    /// Decodes the LNAV [Ephemeris] from these subframes,
+    /// if they share the same issue of data.
+    fn lnav_ephemeris(
+        &self,
+        sf1: &[u8],
+        sf2: &[u8],
+        sf3: &[u8],
+    ) -> Result<Option<(Epoch, Ephemeris)>, StreamError> {
+        // TLM and HOW words
+        let mut r1 = BitReader::new(sf1);
+        r1.skip(48)?;
+        let week = r1.u(10)? as u32;
+        let l2_codes = r1.u(2)? as f64;
+        let accuracy = r1.u(4)? as f64;
+        let health = r1.u(6)? as f64;
+        let iodc_msb = r1.u(2)?;
+        let l2p_flag = r1.u(1)? as f64;
+        // reserved
+        r1.skip(23 + 24 + 24 + 16)?;
+        let tgd = r1.i(8)? as f64 * p2(-31);
+        let iodc = (iodc_msb << 8) | r1.u(8)?;
+        let toc = r1.u(16)? * 16;
+        let af2 = r1.i(8)? as f64 * p2(-55);
+        let af1 = r1.i(16)? as f64 * p2(-43);
+        let af0 = r1.i(22)? as f64 * p2(-31);
+
+        let mut r2 = BitReader::new(sf2);
+        r2.skip(48)?;
+        let iode = r2.u(8)?;
+        let crs = r2.i(16)? as f64 * p2(-5);
+        let delta_n = r2.i(16)? as f64 * p2(-43) * SC2RAD;
+        let m0 = r2.i(32)? as f64 * p2(-31) * SC2RAD;
+        let cuc = r2.i(16)? as f64 * p2(-29);
+        let e = r2.u(32)? as f64 * p2(-33);
+        let cus = r2.i(16)? as f64 * p2(-29);
+        let sqrt_a = r2.u(32)? as f64 * p2(-19);
+        let toe = r2.u(16)? as f64 * 16.0;
+        let fit = r2.u(1)? as f64;
+
+        let mut r3 = BitReader::new(sf3);
+        r3.skip(48)?;
+        let cic = r3.i(16)? as f64 * p2(-29);
+        let omega0 = r3.i(32)? as f64 * p2(-31) * SC2RAD;
+        let cis = r3.i(16)? as f64 * p2(-29);
+        let i0 = r3.i(32)? as f64 * p2(-31) * SC2RAD;
+        let crc = r3.i(16)? as f64 * p2(-5);
+        let omega = r3.i(32)? as f64 * p2(-31) * SC2RAD;
+        let omega_dot = r3.i(24)? as f64 * p2(-43) * SC2RAD;
+        let iode3 = r3.u(8)?;
+        let idot = r3.i(14)? as f64 * p2(-43) * SC2RAD;
+
+        if iode != iode3 || iode != iodc & 0xFF {
+            return Ok(None);
+        }
+
+        let reference_week = self
+            .reference
+            .ok_or(StreamError::NoReferenceEpoch)?
+            .to_time_scale(TimeScale::GPST)
+            .to_time_of_week()
+            .0;
+
+        let week = resolve_week(week, 1024, reference_week);
+        let toc = Epoch::from_time_of_week(week, toc * 1_000_000_000, TimeScale::GPST);
+
+        let ephemeris = Ephemeris {
+            clock_bias: af0,
+            clock_drift: af1,
+            clock_drift_rate: af2,
+            orbits: orbits(&[
+                ("iode", iode as f64),
+                ("crs", crs),
+                ("deltaN", delta_n),
+                ("m0", m0),
+                ("cuc", cuc),
+                ("e", e),
+                ("cus", cus),
+                ("sqrta", sqrt_a),
+                ("toe", toe),
+                ("cic", cic),
+                ("omega0", omega0),
+                ("cis", cis),
+                ("i0", i0),
+                ("crc", crc),
+                ("omega", omega),
+                ("omegaDot", omega_dot),
+                ("idot", idot),
+                ("l2Codes", l2_codes),
+                ("week", week as f64),
+                ("l2pDataFlag", l2p_flag),
+                ("svAccuracy", accuracy),
+                ("health", health),
+                ("tgd", tgd),
+                ("iodc", iodc as f64),
+                ("fitInt", fit),
+            ]),
+        };
+
+        Ok(Some((toc, ephemeris)))
+    }
+
+    /// Stores one I/NAV word, and decodes the ephemeris once complete.
+    fn decode_inav(&mut self, sv: SV, pages: &[u8]) -> Result<(), StreamError> {
+        let mut r = BitReader::new(pages);
+
+        // even page first, then odd page. Alert pages are not used.
+        let (even, even_alert) = (r.u(1)?, r.u(1)?);
+        r.skip(126)?;
+        let (odd, odd_alert) = (r.u(1)?, r.u(1)?);
+
+        if even != 0 || odd != 1 || even_alert != 0 || odd_alert != 0 {
+            return Ok(());
+        }
+
+        // 112 data bits of the even page, 16 data bits of the odd page
+        let word = extract_bits(pages, &[(2, 112), (130, 16)]);
+        let word_type = (word[0] >> 2) as usize;
+
+        if !(1..=5).contains(&word_type) {
+            return Ok(());
+        }
+
+        let words = self.inav.entry(sv).or_default();
+        words[word_type - 1] = Some(word);
+
+        let [Some(w1), Some(w2), Some(w3), Some(w4), w5] = words.clone() else {
+            return Ok(());
+        };
+
+        let Some((toc, ephemeris)) = self.inav_ephemeris(&w1, &w2, &w3, &w4, w5.as_deref())? else {
+            // issues of data do not match (yet)
+            return Ok(());
+        };
+
+        self.collector
+            .add_ephemeris(toc, sv, NavMessageType::INAV, ephemeris);
+
+        // word 5 (health and group delays) is kept for following issues of data
+        if let Some(words) = self.inav.get_mut(&sv) {
+            for word in words.iter_mut().take(4) {
+                *word = None;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Decodes the I/NAV [Ephemeris] from these words,
+    /// if they share the same issue of data.
+    fn inav_ephemeris(
+        &self,
+        w1: &[u8],
+        w2: &[u8],
+        w3: &[u8],
+        w4: &[u8],
+        w5: Option<&[u8]>,
+    ) -> Result<Option<(Epoch, Ephemeris)>, StreamError> {
+        let mut r1 = BitReader::new(w1);
+        r1.skip(6)?;
+        let iodnav = r1.u(10)?;
+        let toe = r1.u(14)? as f64 * 60.0;
+        let m0 = r1.i(32)? as f64 * p2(-31) * SC2RAD;
+        let e = r1.u(32)? as f64 * p2(-33);
+        let sqrt_a = r1.u(32)? as f64 * p2(-19);
+
+        let mut r2 = BitReader::new(w2);
+        r2.skip(6)?;
+        let iodnav2 = r2.u(10)?;
+        let omega0 = r2.i(32)? as f64 * p2(-31) * SC2RAD;
+        let i0 = r2.i(32)? as f64 * p2(-31) * SC2RAD;
+        let omega = r2.i(32)? as f64 * p2(-31) * SC2RAD;
+        let idot = r2.i(14)? as f64 * p2(-43) * SC2RAD;
+
+        let mut r3 = BitReader::new(w3);
+        r3.skip(6)?;
+        let iodnav3 = r3.u(10)?;
+        let omega_dot = r3.i(24)? as f64 * p2(-43) * SC2RAD;
+        let delta_n = r3.i(16)? as f64 * p2(-43) * SC2RAD;
+        let cuc = r3.i(16)? as f64 * p2(-29);
+        let cus = r3.i(16)? as f64 * p2(-29);
+        let crc = r3.i(16)? as f64 * p2(-5);
+        let crs = r3.i(16)? as f64 * p2(-5);
+        let sisa = r3.u(8)? as f64;
+
+        let mut r4 = BitReader::new(w4);
+        r4.skip(6)?;
+        let iodnav4 = r4.u(10)?;
+        // SVID
+        r4.skip(6)?;
+        let cic = r4.i(16)? as f64 * p2(-29);
+        let cis = r4.i(16)? as f64 * p2(-29);
+        let toc = r4.u(14)? * 60;
+        let af0 = r4.i(31)? as f64 * p2(-34);
+        let af1 = r4.i(21)? as f64 * p2(-46);
+        let af2 = r4.i(6)? as f64 * p2(-59);
+
+        if iodnav != iodnav2 || iodnav != iodnav3 || iodnav != iodnav4 {
+            return Ok(None);
+        }
+
+        let (bgd_e5a_e1, bgd_e5b_e1, health) = match w5 {
+            Some(w5) => {
+                let mut r5 = BitReader::new(w5);
+                // word type, ionospheric model and disturbance flags
+                r5.skip(6 + 11 + 11 + 14 + 5)?;
+                let bgd_e5a_e1 = r5.i(10)? as f64 * p2(-32);
+                let bgd_e5b_e1 = r5.i(10)? as f64 * p2(-32);
+                let e5b_hs = r5.u(2)?;
+                let e1b_hs = r5.u(2)?;
+                let e5b_dvs = r5.u(1)?;
+                let e1b_dvs = r5.u(1)?;
+                let health = e1b_dvs | (e1b_hs << 1) | (e5b_dvs << 6) | (e5b_hs << 7);
+                (bgd_e5a_e1, bgd_e5b_e1, health as f64)
+            }
+            None => (0.0, 0.0, 0.0),
+        };
+
+        let reference = self.reference.ok_or(StreamError::NoReferenceEpoch)?;
+        let toc = resolve_time_of_week(toc * 1_000_000_000, TimeScale::GST, reference);
+        let (week, _) = toc.to_time_of_week();
+
+        let ephemeris = Ephemeris {
+            clock_bias: af0,
+            clock_drift: af1,
+            clock_drift_rate: af2,
+            orbits: orbits(&[
+                ("iodnav", iodnav as f64),
+                ("crs", crs),
+                ("deltaN", delta_n),
+                ("m0", m0),
+                ("cuc", cuc),
+                ("e", e),
+                ("cus", cus),
+                ("sqrta", sqrt_a),
+                ("toe", toe),
+                ("cic", cic),
+                ("omega0", omega0),
+                ("cis", cis),
+                ("i0", i0),
+                ("crc", crc),
+                ("omega", omega),
+                ("omegaDot", omega_dot),
+                ("idot", idot),
+                // RINEX uses continuous (GPS aligned) week numbers
+                ("week", (week + 1024) as f64),
+                ("sisa", sisa),
+                ("health", health),
+                ("bgdE5aE1", bgd_e5a_e1),
+                ("bgdE5bE1", bgd_e5b_e1),
+            ]),
+        };
+
+        Ok(Some((toc, ephemeris)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{checksum, is_ubx, messages, Decoder};
+    use crate::{
+        prelude::{Constellation, Epoch, ProductType, SV},
+        tests::toolkit::ubx::{frame, gps_lnav_subframes, rawx, RawxMeasurement},
+    };
+    use std::str::FromStr;
+
+    #[test]
+    fn ubx_framing() {
+        assert_eq!(
+            checksum(&[0x06, 0x01, 0x02, 0x00, 0xF0, 0x05]),
+            (0xFE, 0x16)
+        );
+
+        let message = frame(0x06, 0x01, &[0xF0, 0x05]);
+        assert_eq!(message[message.len() - 2..], [0xFE, 0x16]);
+
+        // garbage, NMEA and corrupt frames are skipped
+        let mut buf = b"$GPGGA,,,,*00\r\n".to_vec();
+        buf.extend_from_slice(&message);
+        let mut corrupt = message.clone();
+        corrupt[6] ^= 0x01;
+        buf.extend_from_slice(&corrupt);
+        buf.extend_from_slice(&message);
+
+        assert!(is_ubx(&buf));
+        assert_eq!(messages(&buf).count(), 2);
+        assert!(!is_ubx(b"$GPGGA,,,,*00\r\n"));
+    }
+
+    #[test]
+    fn ubx_rawx() {
+        // 2024-01-09T12:00:00 GPST
+        let buf = rawx(
+            2296,
+            2.0 * 86400.0 + 12.0 * 3600.0,
+            &[
+                RawxMeasurement {
+                    gnss_id: 0,
+                    sv_id: 5,
+                    sig_id: 0,
+                    freq_id: 0,
+                    pseudo_range_m: 21_000_000.5,
+                    phase_cycles: 110_357_000.25,
+                    doppler_hz: -1250.5,
+                    cno: 44,
+                    trk_stat: 0x03,
+                },
+                RawxMeasurement {
+                    gnss_id: 6,
+                    sv_id: 3,
+                    sig_id: 0,
+                    freq_id: 12,
+                    pseudo_range_m: 20_000_000.0,
+                    phase_cycles: 0.0,
+                    doppler_hz: 500.0,
+                    cno: 30,
+                    // carrier phase is not valid
+                    trk_stat: 0x01,
+                },
+            ],
+        );
+
+        let reference = Epoch::from_str("2020-01-01T00:00:00 GPST").unwrap();
+        let collector = Decoder::decode(&buf, Some(reference)).unwrap();
+
+        let rinex = collector.observation().unwrap();
+
+        let r03 = SV::new(Constellation::Glonass, 3);
+        assert_eq!(rinex.header.glo_channels.get(&r03), Some(&5));
+
+        assert_eq!(
+            ProductType::from(rinex.header.rinex_type),
+            ProductType::Observation
+        );
+
+        assert_eq!(
+            rinex.first_epoch(),
+            Some(Epoch::from_str("2024-01-09T12:00:00 GPST").unwrap())
+        );
+
+        let mut observables = rinex
+            .signal_observations_iter()
+            .map(|(_, signal)| format!("{}:{}", signal.sv, signal.observable))
+            .collect::<Vec<_>>();
+
+        observables.sort();
+
+        assert_eq!(
+            observables,
+            vec!["G05:C1C", "G05:D1C", "G05:L1C", "G05:S1C", "R03:C1C", "R03:D1C", "R03:S1C"]
+        );
+    }
+
+    #[test]
+    fn ubx_lnav_ephemeris() {
+        let mut buf = Vec::new();
+
+        for subframe in gps_lnav_subframes(12, 2296, 7200) {
+            buf.extend(subframe);
+        }
+
+        let reference = Epoch::from_str("2024-01-10T00:00:00 GPST").unwrap();
+        let collector = Decoder::decode(&buf, Some(reference)).unwrap();
+
+        let rinex = collector.navigation().unwrap();
+        let (key, ephemeris) = rinex.nav_ephemeris_frames_iter().next().unwrap();
+
+        assert_eq!(key.sv, SV::new(Constellation::GPS, 12));
+        assert_eq!(
+            key.epoch,
+            Epoch::from_str("2024-01-07T02:00:00 GPST").unwrap()
+        );
+        assert_eq!(ephemeris.clock_bias, 1000.0 * 2.0_f64.powi(-31));
+        assert_eq!(ephemeris.get_orbit_f64("sqrta"), Some(5153.0));
+        assert_eq!(ephemeris.get_orbit_f64("e"), Some(2.0_f64.powi(-6)));
+        assert_eq!(ephemeris.get_orbit_f64("iode"), Some(42.0));
+        assert_eq!(ephemeris.get_orbit_f64("week"), Some(2296.0));
+    }
+}
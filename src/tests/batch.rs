@@ -0,0 +1,113 @@
+use crate::prelude::{Duration, ProductType, QcBatchOptions, QcContext};
+
+#[test]
+fn test_observation_time_batches() {
+    let mut context = QcContext::new();
+
+    context
+        .load_gzip_rinex_file("data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+        .unwrap();
+
+    context
+        .load_gzip_rinex_file("data/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+        .unwrap();
+
+    let total = context.observation().unwrap().epoch_iter().count();
+
+    // single day
+    let batches = context.time_batches(&QcBatchOptions::daily());
+    assert_eq!(batches.len(), 1);
+
+    let (start, batch) = &batches[0];
+    assert_eq!(batch.observation().unwrap().first_epoch(), Some(*start));
+    assert_eq!(batch.observation().unwrap().epoch_iter().count(), total);
+
+    // custom length
+    let opts = QcBatchOptions::default().with_period(Duration::from_hours(6.0));
+    let batches = context.time_batches(&opts);
+    assert_eq!(batches.len(), 4);
+
+    let mut count = 0;
+
+    for (i, (start, batch)) in batches.iter().enumerate() {
+        if i > 0 {
+            assert_eq!(*start - batches[i - 1].0, Duration::from_hours(6.0));
+        }
+
+        let obs = batch.observation().unwrap();
+
+        for epoch in obs.epoch_iter() {
+            assert!(epoch >= *start);
+            assert!(epoch < *start + Duration::from_hours(6.0));
+        }
+
+        count += obs.epoch_iter().count();
+
+        // ephemerides are preserved
+        assert!(batch.has_brdc_navigation());
+
+        // so is the provenance
+        assert!(batch.files(ProductType::Observation).is_some());
+        assert!(batch.files(ProductType::BroadcastNavigation).is_some());
+    }
+
+    assert_eq!(count, total);
+}
+
+#[test]
+fn test_time_batches_provenance() {
+    let mut context = QcContext::new();
+
+    // 2021-12-21
+    context.load_rinex_file("data/OBS/V2/AJAC3550.21O").unwrap();
+
+    // 2020-06-25
+    context
+        .load_gzip_rinex_file("data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+        .unwrap();
+
+    let batches = context.time_batches(&QcBatchOptions::daily());
+    assert_eq!(batches.len(), 2);
+
+    // each batch only describes the files within its window
+    for (name, (_, batch)) in ["ESBC00DNK", "AJAC3550"].iter().zip(batches.iter()) {
+        let files = batch.files(ProductType::Observation).unwrap();
+        assert_eq!(files.len(), 1);
+        assert!(files[0].to_string_lossy().contains(name));
+        assert_eq!(batch.provenance().len(), 1);
+    }
+}
+
+#[test]
+#[cfg(feature = "sp3")]
+fn test_sp3_time_batches() {
+    let mut context = QcContext::new();
+
+    context
+        .load_gzip_sp3_file("data/SP3/D/COD0MGXFIN_20230500000_01D_05M_ORB.SP3.gz")
+        .unwrap();
+
+    let opts = QcBatchOptions::hourly().with_interpolation_order(9);
+    let batches = context.time_batches(&opts);
+    assert_eq!(batches.len(), 24);
+
+    // 5 samples (25') interpolation margin
+    let margin = Duration::from_seconds(5.0 * 300.0);
+
+    for (i, (start, batch)) in batches.iter().enumerate() {
+        let sp3 = batch.sp3().unwrap();
+
+        if i > 0 {
+            assert_eq!(sp3.first_epoch(), Some(*start - margin));
+        } else {
+            assert_eq!(sp3.first_epoch(), Some(*start));
+        }
+
+        if i < 23 {
+            assert_eq!(
+                sp3.last_epoch(),
+                Some(*start + Duration::from_hours(1.0) + margin - Duration::from_seconds(300.0))
+            );
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use std::{
+    fs::{remove_dir_all, write},
+    str::FromStr,
+};
+
+use crate::{
+    prelude::{BiasType, Constellation, Epoch, ProductType, QcContext, SV},
+    report::bias::{BiasReport, BROADCAST_GROUP_DELAYS},
+    tests::toolkit::test_directory,
+};
+
+use rinex::prelude::Observable;
+
+const NAV: &str = "data/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz";
+
+/// Bias-SINEX header, up to the solution block
+const HEADER: &str = "%=BIA 1.00 COD 2020:178:00000 IGS 2020:177:00000 2020:178:00000 R 00000000
++BIAS/DESCRIPTION
+ TIME_SYSTEM                             G
+-BIAS/DESCRIPTION
++BIAS/SOLUTION
+*BIAS SVN_ PRN STATION__ OBS1 OBS2 BIAS_START____ BIAS_END______ UNIT __ESTIMATED_VALUE____ _STD_DEV___
+";
+
+/// Synthesizes a Bias-SINEX file: one DSB per satellite and broadcast group delay,
+/// valid from start to end, of value offset + PRN / 10 [ns].
+fn bias_sinex(start: &str, end: &str, offset: f64) -> String {
+    let mut content = HEADER.to_string();
+
+    for group_delay in BROADCAST_GROUP_DELAYS.iter() {
+        for prn in 1..=40 {
+            let sv = SV::new(group_delay.constellation, prn);
+            content.push_str(&format!(
+                " DSB  {:<4} {:<3} {:<9} {:<4} {:<4} {} {} {:<5}{:>21.4} {:>11.4}\n",
+                "",
+                sv.to_string(),
+                "",
+                group_delay.obs1,
+                group_delay.obs2,
+                start,
+                end,
+                "ns",
+                offset + prn as f64 / 10.0,
+                0.01,
+            ));
+        }
+    }
+
+    content.push_str("-BIAS/SOLUTION\n%=ENDBIA\n");
+    content
+}
+
+#[test]
+fn test_bias_loading() {
+    let directory = test_directory("bias-loading");
+    let path = directory.join("COD0MGXFIN_20201770000_01D_01D_DSB.BIA");
+    write(&path, bias_sinex("2020:177:00000", "2020:178:00000", 0.0)).unwrap();
+
+    let mut context = QcContext::new();
+
+    let product = context.load_file(&path).unwrap();
+    assert_eq!(product, ProductType::CodeBias);
+
+    assert!(context.has_bias());
+    assert_eq!(context.files(ProductType::CodeBias).unwrap().len(), 1);
+
+    let bias = context.bias().unwrap();
+    assert_eq!(bias.agency.as_deref(), Some("COD"));
+    assert_eq!(bias.solutions.len(), 3 * 40);
+    assert!(bias
+        .solutions
+        .iter()
+        .all(|sol| sol.bias_type == BiasType::DSB));
+
+    let g05 = SV::new(Constellation::GPS, 5);
+    let (c1w, c2w) = (
+        Observable::from_str("C1W").unwrap(),
+        Observable::from_str("C2W").unwrap(),
+    );
+
+    let t = Epoch::from_str("2020-06-25T12:00:00 GPST").unwrap();
+    let dcb = bias.differential_code_bias(g05, &c1w, &c2w, t).unwrap();
+    assert!((dcb - 0.5).abs() < 1.0E-9);
+
+    let _ = remove_dir_all(&directory);
+}
+
+#[test]
+fn test_bias_merge() {
+    let directory = test_directory("bias-merge");
+    let (morning, afternoon) = (
+        directory.join("COD0MGXFIN_20201770000_12H_12H_DSB.BIA"),
+        directory.join("COD0MGXFIN_20201771200_12H_12H_DSB.BIA"),
+    );
+
+    write(
+        &morning,
+        bias_sinex("2020:177:00000", "2020:177:43200", 0.0),
+    )
+    .unwrap();
+
+    write(
+        &afternoon,
+        bias_sinex("2020:177:43200", "2020:178:00000", 1.0),
+    )
+    .unwrap();
+
+    let mut context = QcContext::new();
+    context.load_file(&morning).unwrap();
+    context.load_file(&afternoon).unwrap();
+
+    // one product, described by both files
+    assert_eq!(context.files(ProductType::CodeBias).unwrap().len(), 2);
+
+    let bias = context.bias().unwrap();
+    assert_eq!(bias.solutions.len(), 2 * 3 * 40);
+
+    let (start, end) = bias.time_frame();
+    assert_eq!(
+        start,
+        Some(Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap())
+    );
+    assert_eq!(
+        end,
+        Some(Epoch::from_str("2020-06-26T00:00:00 GPST").unwrap())
+    );
+
+    // each DCB is resolved from the file valid at that epoch
+    let g05 = SV::new(Constellation::GPS, 5);
+    let (c1w, c2w) = (
+        Observable::from_str("C1W").unwrap(),
+        Observable::from_str("C2W").unwrap(),
+    );
+
+    for (t, expected) in [
+        ("2020-06-25T06:00:00 GPST", 0.5),
+        ("2020-06-25T18:00:00 GPST", 1.5),
+    ] {
+        let t = Epoch::from_str(t).unwrap();
+        let dcb = bias.differential_code_bias(g05, &c1w, &c2w, t).unwrap();
+        assert!((dcb - expected).abs() < 1.0E-9, "{}: {}", t, dcb);
+    }
+
+    let _ = remove_dir_all(&directory);
+}
+
+#[test]
+#[cfg(feature = "flate2")]
+fn test_bias_report_group_delays() {
+    let directory = test_directory("bias-report");
+    let path = directory.join("COD0MGXFIN_20201770000_12H_12H_DSB.BIA");
+
+    // valid in the morning only
+    let (start, end) = (
+        Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap(),
+        Epoch::from_str("2020-06-25T12:00:00 GPST").unwrap(),
+    );
+
+    write(&path, bias_sinex("2020:177:00000", "2020:177:43200", 0.0)).unwrap();
+
+    let mut context = QcContext::new();
+    context.load_file(&path).unwrap();
+    context.load_gzip_rinex_file(NAV).unwrap();
+
+    let brdc = context.brdc_navigation().unwrap();
+    let record = brdc.record.as_nav().unwrap();
+
+    let report = BiasReport::new(context.bias().unwrap(), Some(brdc));
+
+    let mut compared = 0;
+
+    for comparison in report.comparisons.iter() {
+        let group_delay = BROADCAST_GROUP_DELAYS
+            .iter()
+            .find(|group_delay| group_delay.constellation == comparison.sv.constellation)
+            .unwrap();
+
+        assert_eq!(comparison.label, group_delay.label);
+
+        // synthesized DCB
+        let dcb_ns = comparison.sv.prn as f64 / 10.0;
+        assert!((comparison.dcb_ns - dcb_ns).abs() < 1.0E-9);
+
+        // BeiDou TGD1 is the DCB itself, others are scaled
+        let derived_ns = match comparison.sv.constellation {
+            Constellation::BeiDou => dcb_ns,
+            Constellation::GPS => dcb_ns / (1.0 - (1575.42_f64 / 1227.60).powi(2)),
+            _ => dcb_ns / (1.0 - (1575.42_f64 / 1176.45).powi(2)),
+        };
+
+        assert!(
+            (comparison.derived_ns - derived_ns).abs() < 1.0E-9,
+            "{}: {} {}",
+            comparison.sv,
+            comparison.derived_ns,
+            derived_ns
+        );
+
+        // only the ephemerides published within the DCB validity period
+        let tgds = record
+            .iter()
+            .filter(|(k, _)| k.sv == comparison.sv && k.epoch >= start && k.epoch < end)
+            .filter_map(|(_, frame)| Some(frame.as_ephemeris()?.tgd()?.to_seconds() * 1.0E9))
+            .collect::<Vec<_>>();
+
+        assert_eq!(comparison.ephemerides, tgds.len(), "{}", comparison.sv);
+
+        match comparison.broadcast_ns {
+            Some(broadcast_ns) => {
+                let expected = tgds.iter().sum::<f64>() / tgds.len() as f64;
+                assert!(
+                    (broadcast_ns - expected).abs() < 1.0E-9,
+                    "{}: {} {}",
+                    comparison.sv,
+                    broadcast_ns,
+                    expected
+                );
+                compared += 1;
+            }
+            None => assert!(tgds.is_empty(), "{}", comparison.sv),
+        }
+    }
+
+    assert!(
+        report
+            .comparisons
+            .iter()
+            .any(
+                |comparison| comparison.sv.constellation == Constellation::GPS
+                    && comparison.broadcast_ns.is_some()
+            ),
+        "no GPS TGD compared"
+    );
+
+    assert!(compared > 0);
+
+    let _ = remove_dir_all(&directory);
+}
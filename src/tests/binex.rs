@@ -0,0 +1,97 @@
+use std::{io::Write, str::FromStr};
+
+use crate::{
+    prelude::{Duration, Epoch, ProductType, QcContext},
+    tests::toolkit::{
+        binex::{gps_ephemeris, metadata, observations, BinexSignal},
+        test_directory,
+    },
+};
+
+#[test]
+fn test_binex_file_loading() {
+    let directory = test_directory("binex-loading");
+    let path = directory.join("binex_log.bnx");
+
+    let mut fd = std::fs::File::create(&path).unwrap();
+
+    fd.write_all(&metadata(&[
+        (0x0b, "MOJN00DNK"),
+        (0x09, "Observer"),
+        (0x19, "SEPT POLARX5"),
+        (0x17, "LEIAR25.R4"),
+    ]))
+    .unwrap();
+
+    let t0 = Epoch::from_str("2024-01-07T02:00:00 GPST").unwrap();
+
+    for i in 0..2 {
+        let satellites = [12, 25]
+            .into_iter()
+            .map(|prn| {
+                (
+                    0,
+                    prn,
+                    vec![BinexSignal {
+                        code: 0,
+                        pseudo_range_m: 21_000_000.0 + prn as f64,
+                        phase_m: 21_000_000.5 + prn as f64,
+                        doppler_hz: Some(100.0),
+                        cn0_dbhz: 40.0,
+                    }],
+                )
+            })
+            .collect::<Vec<_>>();
+
+        fd.write_all(&observations(
+            t0 + Duration::from_seconds(30.0 * i as f64),
+            &satellites,
+        ))
+        .unwrap();
+    }
+
+    fd.write_all(&gps_ephemeris(12, 2296, 7200)).unwrap();
+
+    drop(fd);
+
+    let mut context = QcContext::new();
+
+    // the observations prevail
+    let product = context.load_file(&path).unwrap();
+    assert_eq!(product, ProductType::Observation);
+
+    assert!(context.is_navigation_compatible());
+
+    // named after the marker
+    assert_eq!(context.stations_iter().collect::<Vec<_>>(), vec!["MOJN"]);
+
+    let obs = context.observation().unwrap();
+    assert_eq!(obs.epoch_iter().count(), 2);
+    assert_eq!(obs.header.observer, Some("Observer".to_string()));
+    assert_eq!(obs.header.rcvr.as_ref().unwrap().model, "SEPT POLARX5");
+    assert_eq!(
+        obs.header.rcvr_antenna.as_ref().unwrap().model,
+        "LEIAR25.R4"
+    );
+
+    let nav = context.brdc_navigation().unwrap();
+    assert_eq!(nav.nav_ephemeris_frames_iter().count(), 1);
+
+    for entry in context.provenance() {
+        assert!(entry.header.starts_with("BINEX stream"));
+    }
+
+    context.unload_file(&path).unwrap();
+    assert!(!context.has_observation());
+    assert!(!context.has_brdc_navigation());
+
+    // explicit loading
+    let products = context.load_binex_file(&path).unwrap();
+
+    assert_eq!(
+        products,
+        vec![ProductType::BroadcastNavigation, ProductType::Observation]
+    );
+
+    let _ = std::fs::remove_dir_all(&directory);
+}
@@ -0,0 +1,135 @@
+use std::fs::{copy, create_dir_all, remove_dir_all, write};
+
+use crate::{
+    prelude::{Error, ProductType, QcContext, QcDirectoryOptions, QcLoadingOutcome},
+    tests::toolkit::test_directory,
+};
+
+#[test]
+fn test_directory_outcomes() {
+    let directory = test_directory("directory-outcomes");
+
+    let nested = directory.join("nested");
+    create_dir_all(&nested).unwrap();
+
+    // supported
+    copy("data/OBS/V2/AJAC3550.21O", nested.join("AJAC3550.21O")).unwrap();
+
+    // readable, but not supported
+    write(directory.join("notes.txt"), "some notes\n").unwrap();
+
+    // gzip magic bytes, but corrupt compressed stream
+    write(
+        directory.join("corrupt.rnx.gz"),
+        [
+            0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, // header
+            0xff, 0xff, 0xff, 0xff, // invalid deflate block type
+        ],
+    )
+    .unwrap();
+
+    // binary content, that is not a known stream
+    write(
+        directory.join("binary.bin"),
+        [0xff, 0xfe, 0x00, 0x80, 0xc3, 0x28],
+    )
+    .unwrap();
+
+    // symbolic link forming a directory loop
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(&directory, nested.join("loop")).unwrap();
+
+    let mut context = QcContext::new();
+
+    let outcomes = context
+        .load_directory(&directory, &QcDirectoryOptions::default().with_recursion())
+        .unwrap();
+
+    // each file is reported once, loop is not followed twice
+    assert_eq!(outcomes.len(), 4);
+
+    for (path, outcome) in outcomes.iter() {
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+
+        match name.as_str() {
+            "AJAC3550.21O" => {
+                assert!(matches!(
+                    outcome,
+                    QcLoadingOutcome::Loaded(ProductType::Observation)
+                ));
+            }
+            "notes.txt" => {
+                assert!(matches!(outcome, QcLoadingOutcome::Skipped));
+            }
+            "corrupt.rnx.gz" => {
+                assert!(
+                    matches!(outcome, QcLoadingOutcome::Failed(Error::CorruptGzip)),
+                    "{:?}",
+                    outcome
+                );
+            }
+            "binary.bin" => {
+                assert!(
+                    matches!(outcome, QcLoadingOutcome::Failed(_)),
+                    "{:?}",
+                    outcome
+                );
+            }
+            _ => panic!("unexpected file {}", path.display()),
+        }
+    }
+
+    assert!(context.has_observation());
+
+    let _ = remove_dir_all(&directory);
+}
+
+#[test]
+#[cfg(unix)]
+fn test_directory_unreadable() {
+    use std::{fs::set_permissions, os::unix::fs::PermissionsExt};
+
+    let directory = test_directory("directory-unreadable");
+
+    let (readable, locked) = (directory.join("a"), directory.join("b"));
+
+    for nested in [&readable, &locked] {
+        create_dir_all(nested).unwrap();
+        copy("data/OBS/V2/AJAC3550.21O", nested.join("AJAC3550.21O")).unwrap();
+    }
+
+    set_permissions(&locked, PermissionsExt::from_mode(0o000)).unwrap();
+
+    // privileged users may still read it
+    let is_locked = std::fs::read_dir(&locked).is_err();
+
+    let mut context = QcContext::new();
+
+    // one unreadable sub-directory does not abort the loading
+    let outcomes = context
+        .load_directory(&directory, &QcDirectoryOptions::default().with_recursion())
+        .unwrap();
+
+    set_permissions(&locked, PermissionsExt::from_mode(0o755)).unwrap();
+
+    assert!(context.has_observation());
+
+    assert!(outcomes.iter().any(|(path, outcome)| {
+        path == &readable.join("AJAC3550.21O")
+            && matches!(outcome, QcLoadingOutcome::Loaded(ProductType::Observation))
+    }));
+
+    if is_locked {
+        assert_eq!(outcomes.len(), 2);
+
+        let (path, outcome) = &outcomes[1];
+        assert_eq!(path, &locked);
+        assert!(
+            matches!(outcome, QcLoadingOutcome::Failed(_)),
+            "{:?}",
+            outcome
+        );
+    }
+
+    let _ = remove_dir_all(&directory);
+}
@@ -0,0 +1,46 @@
+use maud::Render;
+
+use crate::prelude::{ProductType, QcConfig, QcContext, QcReport};
+
+const DORIS: &str = "data/DOR/V3/cs2rx18164.gz";
+
+#[test]
+fn test_doris_loading() {
+    let mut context = QcContext::new();
+
+    let product = context.load_file(DORIS).unwrap();
+    assert_eq!(product, ProductType::DORIS);
+
+    assert!(context.has_doris());
+    assert_eq!(context.files(ProductType::DORIS).unwrap().len(), 1);
+
+    let doris = context.doris().unwrap();
+    assert!(doris.record.as_doris().is_some());
+    assert!(doris.epoch_iter().count() > 0);
+
+    let provenance = context.provenance();
+    assert_eq!(provenance.len(), 1);
+    assert_eq!(provenance[0].product, ProductType::DORIS);
+}
+
+#[test]
+fn test_doris_report() {
+    let mut context = QcContext::new();
+    context.load_file(DORIS).unwrap();
+
+    let html = QcReport::new(&context, QcConfig::default())
+        .render()
+        .into_string();
+
+    assert!(html.contains("menu:doris"));
+    assert!(html.contains("DORIS Observations"));
+
+    // one page per carrier, one plot per carrier dependent observable
+    for html_id in ["doris:S1:L1", "doris:S1:C1", "doris:U2:L2", "doris:U2:C2"] {
+        assert!(html.contains(html_id), "{} not reported", html_id);
+    }
+
+    // meteo observables are not carrier dependent
+    assert!(!html.contains("doris:S1:P"));
+    assert!(!html.contains("doris:U2:T"));
+}
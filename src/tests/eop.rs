@@ -0,0 +1,106 @@
+use crate::prelude::{EarthOrientation, EopFormat, EopSample, Epoch, Orbit, QcContext};
+
+/// Receiver at the North pole (ITRF), in km
+const POLE_KM: (f64, f64, f64) = (0.0, 0.0, 6356.752);
+
+/// Azimuth and elevation (degrees) of this Earth fixed position,
+/// seen from a receiver at the North pole (longitude 0):
+/// north is -x, east is +y
+fn pole_azimuth_elevation(sv: &Orbit) -> (f64, f64) {
+    let (dx, dy, dz) = (
+        sv.radius_km.x - POLE_KM.0,
+        sv.radius_km.y - POLE_KM.1,
+        sv.radius_km.z - POLE_KM.2,
+    );
+
+    let (north, east, up) = (-dx, dy, dz);
+
+    let azimuth = east.atan2(north).to_degrees().rem_euclid(360.0);
+    let elevation = up.atan2((north.powi(2) + east.powi(2)).sqrt()).to_degrees();
+
+    (azimuth, elevation)
+}
+
+/// Builds a [QcContext] with constant Earth orientation parameters around this [Epoch]
+fn eop_context(t: Epoch, x_arcsec: f64, y_arcsec: f64, ut1_utc_s: f64) -> QcContext {
+    let mut context = QcContext::new();
+
+    let samples = [-1.0, 1.0]
+        .iter()
+        .map(|offset_days| EopSample {
+            epoch: Epoch::from_mjd_utc(t.to_mjd_utc_days().floor() + offset_days),
+            x_arcsec,
+            y_arcsec,
+            ut1_utc_s,
+            lod_s: None,
+            predicted: false,
+        })
+        .collect();
+
+    context
+        .load_eop(
+            "eop",
+            EarthOrientation {
+                format: EopFormat::C04,
+                samples,
+            },
+        )
+        .unwrap();
+
+    context
+}
+
+#[test]
+fn test_eop_azimuth_elevation() {
+    let t = Epoch::from_mjd_utc(58849.25);
+
+    let nominal = QcContext::new();
+    let frame = nominal.earth_cef;
+
+    // satellite states propagated in the inertial frame
+    let overhead = Orbit::new(0.0, 0.0, 26560.0, 0.0, 0.0, 0.0, t, frame);
+    let slanted = Orbit::new(15000.0, 5000.0, 20000.0, 0.0, 0.0, 0.0, t, frame);
+
+    // UT1-UTC: rotation about the pole, azimuth shifts by the Earth rotation angle difference
+    let ut1_utc_s = -0.1772547;
+    let context = eop_context(t, 0.0, 0.0, ut1_utc_s);
+
+    let (az_nominal, el_nominal) = pole_azimuth_elevation(&nominal.inertial_to_itrf_orbit(slanted));
+    let (az, el) = pole_azimuth_elevation(&context.inertial_to_itrf_orbit(slanted));
+
+    let expected = (ut1_utc_s * 360.985_612_288_808 / 86_400.0).abs();
+    assert!(expected > 5.0E-4);
+
+    let delta = (az - az_nominal + 180.0).rem_euclid(360.0) - 180.0;
+
+    assert!(
+        (delta.abs() - expected).abs() < 1.0E-9,
+        "{} {}",
+        delta,
+        expected
+    );
+    assert!(delta < 0.0, "UT1 < UTC: the Earth lags behind");
+    assert!((el - el_nominal).abs() < 1.0E-9);
+
+    // polar motion: the pole is tilted, the satellite is no longer overhead
+    let (x_arcsec, y_arcsec) = (0.076577, 0.282336);
+    let context = eop_context(t, x_arcsec, y_arcsec, 0.0);
+
+    let (_, el_nominal) = pole_azimuth_elevation(&nominal.inertial_to_itrf_orbit(overhead));
+    let (_, el) = pole_azimuth_elevation(&context.inertial_to_itrf_orbit(overhead));
+
+    assert!((el_nominal - 90.0).abs() < 1.0E-9);
+
+    let tilt_rad = ((x_arcsec.powi(2) + y_arcsec.powi(2)).sqrt() / 3600.0).to_radians();
+    let zenith = (tilt_rad * 26560.0 / (26560.0 - POLE_KM.2))
+        .atan()
+        .to_degrees();
+
+    assert!((90.0 - el - zenith).abs() < 1.0E-9, "{} {}", el, zenith);
+
+    // round trip
+    let itrf = context.inertial_to_itrf_orbit(slanted);
+    let inertial = context.itrf_to_inertial_orbit(itrf);
+
+    assert!((inertial.radius_km - slanted.radius_km).norm() < 1.0E-6);
+}
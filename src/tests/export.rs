@@ -0,0 +1,93 @@
+use crate::{
+    error::Error,
+    prelude::{Constellation, Filter, FilterItem, MaskOperand, QcContext, QcExportOptions, Rinex},
+    tests::toolkit::test_directory,
+};
+
+#[test]
+fn test_context_export() {
+    let mut context = QcContext::new();
+
+    context
+        .load_gzip_rinex_file("data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+        .unwrap();
+
+    context
+        .load_gzip_rinex_file("data/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+        .unwrap();
+
+    // GPS only
+    context.filter_mut(&Filter::mask(
+        MaskOperand::Equals,
+        FilterItem::ConstellationItem(vec![Constellation::GPS]),
+    ));
+
+    let directory = test_directory("export");
+
+    let opts = QcExportOptions::default().with_crinex().with_gzip();
+    let files = context.export(&directory, &opts).unwrap();
+
+    let names = files
+        .iter()
+        .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(names.len(), 2);
+    assert_eq!(names[0], "ESBC00DNK_R_20201770000_01D_30S_GO.crx.gz");
+    assert!(names[1].starts_with("BRDC00WRD_R_2020"));
+    assert!(names[1].ends_with("_GN.rnx.gz"));
+
+    // exported files are readable and describe the new content
+    let mut exported = QcContext::new();
+
+    for file in files.iter() {
+        exported.load_gzip_rinex_file(file).unwrap();
+    }
+
+    let original = context.observation().unwrap();
+    let obs = exported.observation().unwrap();
+
+    assert_eq!(obs.epoch_iter().count(), original.epoch_iter().count());
+    assert!(obs
+        .sv_iter()
+        .all(|sv| sv.constellation == Constellation::GPS));
+
+    let header = obs.header.obs.as_ref().unwrap();
+    assert!(header.crinex.is_some());
+    assert_eq!(header.codes.len(), 1);
+    assert_eq!(header.timeof_first_obs, original.first_epoch());
+
+    assert_eq!(exported.stations_iter().collect::<Vec<_>>(), vec!["ESBC"]);
+
+    let _ = std::fs::remove_dir_all(&directory);
+}
+
+#[test]
+fn test_context_export_collision() {
+    let mut context = QcContext::new();
+
+    // both station names are truncated to the same site code
+    for (path, station) in [("rovera.obs", "ROVERA"), ("roverb.obs", "ROVERB")] {
+        let mut rinex = Rinex::from_file("data/OBS/V2/AJAC3550.21O").unwrap();
+        rinex.header.geodetic_marker.as_mut().unwrap().name = station.to_string();
+        context.load_rinex(path, rinex).unwrap();
+    }
+
+    let directory = test_directory("export-collision");
+
+    match context.export(&directory, &QcExportOptions::default()) {
+        Err(Error::FileNameCollision(name)) => assert!(name.starts_with("ROVE00XXX_R_")),
+        other => panic!("unexpected outcome {:?}", other),
+    }
+
+    // single station
+    context.unload_file("roverb.obs").unwrap();
+
+    let files = context
+        .export(&directory, &QcExportOptions::default())
+        .unwrap();
+
+    assert_eq!(files.len(), 1);
+
+    let _ = std::fs::remove_dir_all(&directory);
+}
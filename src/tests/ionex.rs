@@ -0,0 +1,127 @@
+use maud::Render;
+
+use crate::prelude::{
+    Filter, FilterItem, MaskOperand, Preprocessing, ProductType, QcConfig, QcContext, QcReport,
+    QcReportType, Rinex,
+};
+
+const IONEX: &str = "data/IONEX/V1/CKMG0020.22I.gz";
+
+#[test]
+fn test_ionex_loading() {
+    let mut context = QcContext::new();
+
+    let product = context.load_file(IONEX).unwrap();
+    assert_eq!(product, ProductType::IONEX);
+
+    assert!(context.has_ionex());
+    assert!(context.iono_bias_model_optimization());
+    assert_eq!(context.files(ProductType::IONEX).unwrap().len(), 1);
+
+    let ionex = context.ionex().unwrap();
+    assert!(ionex.header.ionex.is_some());
+    assert!(ionex.epoch_iter().count() > 0);
+
+    // not a station dependent product
+    assert!(context.stations_iter().next().is_none());
+
+    let provenance = context.provenance();
+    assert_eq!(provenance.len(), 1);
+    assert_eq!(provenance[0].product, ProductType::IONEX);
+    assert!(provenance[0].header.contains("IONEX"));
+}
+
+#[test]
+fn test_ionex_report() {
+    let mut context = QcContext::new();
+    context.load_file(IONEX).unwrap();
+
+    // summary report: no dedicated tab
+    let cfg = QcConfig::default().with_report_type(QcReportType::Summary);
+    let html = QcReport::new(&context, cfg).render().into_string();
+
+    // described by the provenance summary
+    assert!(html.contains("CKMG0020.22I.gz"));
+    assert!(!html.contains("menu:ionex"));
+
+    // full report: dedicated IONEX tab
+    let html = QcReport::new(&context, QcConfig::default())
+        .render()
+        .into_string();
+
+    assert!(html.contains("menu:ionex"));
+    assert!(html.contains("Ionosphere Maps (IONEX)"));
+    assert!(html.contains("ionex_tec"));
+}
+
+#[test]
+fn test_ionex_merge() {
+    let rinex = Rinex::from_gzip_file(IONEX).unwrap();
+
+    let epochs = rinex.epoch_iter().collect::<Vec<_>>();
+    let mid = epochs[epochs.len() / 2];
+
+    // one file per half
+    let first_half = rinex.filter(&Filter::mask(
+        MaskOperand::LowerThan,
+        FilterItem::EpochItem(mid),
+    ));
+
+    let second_half = rinex.filter(&Filter::mask(
+        MaskOperand::GreaterEquals,
+        FilterItem::EpochItem(mid),
+    ));
+
+    assert!(first_half.epoch_iter().count() > 0);
+    assert!(second_half.epoch_iter().count() > 0);
+
+    let mut context = QcContext::new();
+
+    context
+        .load_rinex("memory/CKMG0020.22I", first_half)
+        .unwrap();
+
+    context
+        .load_rinex("memory/CKMG0021.22I", second_half)
+        .unwrap();
+
+    assert_eq!(context.files(ProductType::IONEX).unwrap().len(), 2);
+
+    // merged into a single product
+    let merged = context.ionex().unwrap();
+    assert_eq!(merged.epoch_iter().collect::<Vec<_>>(), epochs);
+
+    assert_eq!(
+        merged.ionex_tecu_latlong_ddeg_alt_km_iter().count(),
+        rinex.ionex_tecu_latlong_ddeg_alt_km_iter().count()
+    );
+}
+
+#[test]
+fn test_ionex_filter() {
+    let mut context = QcContext::new();
+    context.load_file(IONEX).unwrap();
+
+    let epochs = context.ionex().unwrap().epoch_iter().collect::<Vec<_>>();
+    let mid = epochs[epochs.len() / 2];
+
+    context.filter_mut(&Filter::mask(
+        MaskOperand::GreaterEquals,
+        FilterItem::EpochItem(mid),
+    ));
+
+    let ionex = context.ionex().unwrap();
+
+    assert_eq!(
+        ionex.epoch_iter().collect::<Vec<_>>(),
+        epochs
+            .iter()
+            .copied()
+            .filter(|t| *t >= mid)
+            .collect::<Vec<_>>()
+    );
+
+    for (t, _, _, _, _) in ionex.ionex_tecu_latlong_ddeg_alt_km_iter() {
+        assert!(t >= mid);
+    }
+}
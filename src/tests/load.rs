@@ -0,0 +1,123 @@
+use std::path::Path;
+
+use crate::prelude::{ProductType, QcContext, Rinex};
+
+#[test]
+fn test_load_file_identification() {
+    for (path, expected) in [
+        ("data/OBS/V2/AJAC3550.21O", ProductType::Observation),
+        ("data/NAV/V2/amel0010.21g", ProductType::BroadcastNavigation),
+        #[cfg(feature = "flate2")]
+        (
+            "data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz",
+            ProductType::Observation,
+        ),
+        #[cfg(feature = "flate2")]
+        (
+            "data/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz",
+            ProductType::BroadcastNavigation,
+        ),
+        #[cfg(feature = "sp3")]
+        ("data/SP3/D/example.txt", ProductType::HighPrecisionOrbit),
+        #[cfg(all(feature = "sp3", feature = "flate2"))]
+        (
+            "data/SP3/D/COD0MGXFIN_20230500000_01D_05M_ORB.SP3.gz",
+            ProductType::HighPrecisionOrbit,
+        ),
+    ] {
+        let mut context = QcContext::new();
+
+        let product = context
+            .load_file(path)
+            .unwrap_or_else(|e| panic!("failed to load \"{}\": {}", path, e));
+
+        assert_eq!(product, expected, "invalid identification for \"{}\"", path);
+
+        let files = context.files(expected).unwrap();
+        assert_eq!(files.len(), 1);
+    }
+}
+
+#[test]
+fn test_load_file_non_supported() {
+    let mut context = QcContext::new();
+    assert!(context.load_file("Cargo.toml").is_err());
+    assert!(context.load_file("README.md").is_err());
+}
+
+#[test]
+fn test_load_file_provenance() {
+    let mut context = QcContext::new();
+
+    context.load_file("data/OBS/V2/AJAC3550.21O").unwrap();
+    context.load_file("data/NAV/V2/amel0010.21g").unwrap();
+
+    let provenance = context.provenance();
+    assert_eq!(provenance.len(), 2);
+
+    for file in provenance.iter() {
+        let size = std::fs::metadata(&file.path).unwrap().len();
+        assert_eq!(file.size, Some(size));
+        assert_eq!(file.sha256.as_ref().unwrap().len(), 64);
+        assert!(file.loaded_at.is_some());
+    }
+
+    assert_eq!(provenance[0].product, ProductType::Observation);
+    assert!(provenance[0].header.starts_with("RINEX V2."));
+
+    assert_eq!(provenance[1].product, ProductType::BroadcastNavigation);
+
+    // identical content: identical checksum
+    let mut other = QcContext::new();
+    other.load_file("data/OBS/V2/AJAC3550.21O").unwrap();
+    assert_eq!(other.provenance()[0].sha256, provenance[0].sha256);
+
+    let json = context.provenance_json().unwrap();
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 2);
+}
+
+#[test]
+#[cfg(feature = "flate2")]
+fn test_load_file_digest() {
+    use sha2::{Digest, Sha256};
+
+    let mut context = QcContext::new();
+
+    let paths = [
+        "data/OBS/V2/AJAC3550.21O",
+        "data/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz",
+    ];
+
+    context.load_file(paths[0]).unwrap();
+    context.load_gzip_rinex_file(paths[1]).unwrap();
+
+    // digest computed while parsing describes the file as stored
+    for path in paths {
+        let content = std::fs::read(path).unwrap();
+
+        let expected = Sha256::digest(&content)
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>();
+
+        let meta = context.metadata.get(Path::new(path)).unwrap();
+
+        assert_eq!(meta.sha256.as_ref(), Some(&expected), "{}", path);
+        assert_eq!(meta.size, Some(content.len() as u64), "{}", path);
+    }
+
+    // data parsed externally is not hashed
+    let rinex = Rinex::from_file("data/NAV/V2/amel0010.21g").unwrap();
+    context
+        .load_rinex("data/NAV/V2/amel0010.21g", rinex)
+        .unwrap();
+
+    let meta = context
+        .metadata
+        .get(Path::new("data/NAV/V2/amel0010.21g"))
+        .unwrap();
+
+    assert!(meta.sha256.is_none());
+    assert!(meta.size.is_none());
+}
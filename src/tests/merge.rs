@@ -0,0 +1,217 @@
+use crate::prelude::{
+    Error, ProductType, QcContext, QcMergeConflict, QcMergeConflictType, QcMergePolicies,
+    QcMergePolicy, Rinex,
+};
+
+#[test]
+fn test_rinex_merge_policies() {
+    let path = "data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz";
+
+    let mut context = QcContext::new();
+    context.load_gzip_rinex_file(path).unwrap();
+
+    let rinex = Rinex::from_gzip_file(path).unwrap();
+
+    // identical content
+    assert!(context.rinex_merge_conflicts(path, &rinex).is_empty());
+
+    let mut incoming = rinex.clone();
+
+    if let Some(antenna) = &mut incoming.header.rcvr_antenna {
+        antenna.sn = "0000".to_string();
+    }
+
+    if let Some(obs) = &mut incoming.header.obs {
+        for codes in obs.codes.values_mut() {
+            codes.pop();
+        }
+    }
+
+    let conflicts = context.rinex_merge_conflicts("incoming.crx", &incoming);
+
+    assert!(conflicts
+        .iter()
+        .any(|c| matches!(c, QcMergeConflict::Antenna { .. })));
+
+    assert!(conflicts.iter().all(|c| matches!(
+        c,
+        QcMergeConflict::Antenna { .. } | QcMergeConflict::Observables { .. }
+    )));
+
+    // rejection leaves the context untouched
+    let policies =
+        QcMergePolicies::default().with_policy(QcMergeConflictType::Antenna, QcMergePolicy::Reject);
+
+    match context.load_rinex_with_policies("incoming.crx", incoming.clone(), &policies) {
+        Err(Error::MergeConflict(QcMergeConflict::Antenna { .. })) => {}
+        _ => panic!("antenna conflict should have been rejected"),
+    }
+
+    assert_eq!(context.files(ProductType::Observation).unwrap().len(), 1);
+
+    // forcing merges silently
+    let policies = QcMergePolicies::default().with_default(QcMergePolicy::Force);
+
+    let reported = context
+        .load_rinex_with_policies("incoming.crx", incoming, &policies)
+        .unwrap();
+
+    assert!(reported.is_empty());
+    assert_eq!(context.files(ProductType::Observation).unwrap().len(), 2);
+}
+
+#[test]
+fn test_rinex_merge_station_conflicts() {
+    let path = "data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz";
+
+    let mut context = QcContext::new();
+    context.load_gzip_rinex_file(path).unwrap();
+
+    let rinex = Rinex::from_gzip_file(path).unwrap();
+
+    // same site, described by its site code only
+    let mut incoming = rinex.clone();
+    incoming.header.geodetic_marker.as_mut().unwrap().name = "ESBC".to_string();
+    assert!(context
+        .rinex_merge_conflicts("incoming.crx", &incoming)
+        .is_empty());
+
+    // same site, another monument
+    let mut incoming = rinex.clone();
+    incoming.header.geodetic_marker.as_mut().unwrap().name = "ESBC01DNK".to_string();
+
+    assert_eq!(
+        context.rinex_merge_conflicts("incoming.crx", &incoming),
+        vec![QcMergeConflict::StationName {
+            loaded: "ESBC00DNK".to_string(),
+            incoming: "ESBC01DNK".to_string(),
+        }]
+    );
+
+    // another station is never merged
+    let mut incoming = rinex.clone();
+    incoming.header.geodetic_marker.as_mut().unwrap().name = "MOJN00DNK".to_string();
+    assert!(context
+        .rinex_merge_conflicts("incoming.crx", &incoming)
+        .is_empty());
+
+    // approximate receiver positions
+    let (x, y, z) = rinex.header.rx_position.unwrap();
+
+    let mut incoming = rinex.clone();
+    incoming.header.rx_position = Some((x + 1.0, y, z - 1.0));
+    assert!(context
+        .rinex_merge_conflicts("incoming.crx", &incoming)
+        .is_empty());
+
+    incoming.header.rx_position = Some((x + 100.0, y, z));
+
+    assert_eq!(
+        context.rinex_merge_conflicts("incoming.crx", &incoming),
+        vec![QcMergeConflict::ReceiverPosition {
+            loaded: (x, y, z),
+            incoming: (x + 100.0, y, z),
+        }]
+    );
+
+    let policies = QcMergePolicies::default()
+        .with_policy(QcMergeConflictType::ReceiverPosition, QcMergePolicy::Reject);
+
+    assert!(matches!(
+        context.load_rinex_with_policies("incoming.crx", incoming, &policies),
+        Err(Error::MergeConflict(
+            QcMergeConflict::ReceiverPosition { .. }
+        ))
+    ));
+
+    assert_eq!(context.files(ProductType::Observation).unwrap().len(), 1);
+}
+
+#[test]
+#[cfg(feature = "sp3")]
+fn test_sp3_merge_policies() {
+    let path = "data/SP3/D/COD0MGXFIN_20230500000_01D_05M_ORB.SP3.gz";
+
+    let mut context = QcContext::new();
+
+    // nothing to merge into
+    context.load_gzip_sp3_file(path).unwrap();
+    let sp3 = context.sp3().unwrap().clone();
+    assert!(QcContext::new().sp3_merge_conflicts(&sp3).is_empty());
+
+    // identical content
+    assert!(context.sp3_merge_conflicts(&sp3).is_empty());
+
+    let mut incoming = sp3.clone();
+    incoming.header.coord_system = "ITRF2014".to_string();
+    incoming.header.sampling_period = sp3.header.sampling_period * 2.0;
+
+    let conflicts = context.sp3_merge_conflicts(&incoming);
+
+    assert_eq!(
+        conflicts,
+        vec![
+            QcMergeConflict::SamplingInterval {
+                loaded: sp3.header.sampling_period,
+                incoming: incoming.header.sampling_period,
+            },
+            QcMergeConflict::CoordinateSystem {
+                loaded: sp3.header.coord_system.clone(),
+                incoming: "ITRF2014".to_string(),
+            },
+        ]
+    );
+
+    // rejection leaves the context untouched
+    let policies = QcMergePolicies::default()
+        .with_policy(QcMergeConflictType::CoordinateSystem, QcMergePolicy::Reject);
+
+    assert!(matches!(
+        context.load_sp3_with_policies("incoming.sp3", incoming, &policies),
+        Err(Error::MergeConflict(
+            QcMergeConflict::CoordinateSystem { .. }
+        ))
+    ));
+
+    assert_eq!(
+        context
+            .files(ProductType::HighPrecisionOrbit)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+#[cfg(feature = "sp3")]
+fn test_sp3_merge_failure() {
+    use crate::prelude::TimeScale;
+    use std::path::Path;
+
+    let mut context = QcContext::new();
+
+    context
+        .load_gzip_sp3_file("data/SP3/D/COD0MGXFIN_20230500000_01D_05M_ORB.SP3.gz")
+        .unwrap();
+
+    let mut incoming = context.sp3().unwrap().clone();
+
+    incoming.header.timescale = if incoming.header.timescale == TimeScale::UTC {
+        TimeScale::GPST
+    } else {
+        TimeScale::UTC
+    };
+
+    let path = Path::new("incoming.sp3");
+    let loaded = context.load_sp3(path, incoming).is_ok();
+
+    // files list and metadata always agree, even when the merge fails
+    let listed = context
+        .files(ProductType::HighPrecisionOrbit)
+        .unwrap()
+        .iter()
+        .any(|p| p == path);
+
+    assert_eq!(listed, loaded);
+    assert_eq!(context.metadata.contains_key(path), loaded);
+}
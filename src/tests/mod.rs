@@ -1,3 +1,40 @@
+#[cfg(feature = "flate2")]
+mod batch;
+mod bias;
+#[cfg(feature = "binex")]
+mod binex;
+#[cfg(feature = "flate2")]
+mod directory;
+#[cfg(feature = "flate2")]
+mod doris;
+#[cfg(feature = "navigation")]
+mod eop;
+#[cfg(feature = "flate2")]
+mod export;
+#[cfg(feature = "flate2")]
+mod ionex;
+mod load;
+#[cfg(feature = "flate2")]
+mod merge;
+#[cfg(all(feature = "flate2", feature = "navigation", feature = "sp3"))]
+mod orbit;
+#[cfg(feature = "flate2")]
+mod parallel;
+mod partition;
+#[cfg(feature = "flate2")]
+mod pipeline;
+#[cfg(feature = "flate2")]
+mod realtime;
+#[cfg(feature = "flate2")]
+mod repair;
+#[cfg(feature = "rtcm")]
+mod rtcm;
+#[cfg(feature = "snapshot")]
+mod snapshot;
+mod station;
 mod timeshift;
+#[cfg(feature = "ubx")]
+mod ubx;
+mod unload;
 
 pub mod toolkit;
@@ -0,0 +1,171 @@
+use std::collections::BTreeMap;
+
+use crate::prelude::{Duration, Epoch, QcContext, QcOrbitOptions, QcOrbitSource, SV};
+
+#[test]
+fn test_sp3_orbit_interpolation() {
+    let mut context = QcContext::new();
+
+    context
+        .load_gzip_sp3_file("data/SP3/D/COD0MGXFIN_20230500000_01D_05M_ORB.SP3.gz")
+        .unwrap();
+
+    let options = QcOrbitOptions::default().with_brdc_fallback(false);
+    let interpolator = context.orbit_interpolator(&options);
+
+    let sp3 = context.sp3().unwrap();
+    let (first, last) = (sp3.first_epoch().unwrap(), sp3.last_epoch().unwrap());
+    let max_gap = sp3.header.sampling_period * 1.5;
+
+    let mut epochs = BTreeMap::<SV, Vec<Epoch>>::new();
+
+    for (t, sv, _, _, _) in sp3.satellites_position_km_iter() {
+        epochs.entry(sv).or_default().push(t);
+    }
+
+    // satellites sampled over the whole day, without any gap:
+    // every epoch in between may be interpolated
+    let complete = epochs
+        .iter()
+        .filter_map(|(sv, epochs)| {
+            let mut epochs = epochs.clone();
+            epochs.sort();
+
+            if epochs.first() == Some(&first)
+                && epochs.last() == Some(&last)
+                && epochs.windows(2).all(|pair| pair[1] - pair[0] <= max_gap)
+            {
+                Some(*sv)
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    assert!(!complete.is_empty(), "no complete orbit to test");
+
+    for (t, sv, _, _, (x_km, y_km, z_km)) in sp3.satellites_position_km_iter() {
+        assert!(interpolator.has_sp3_orbit(sv));
+
+        // no extrapolation at the day edges
+        assert!(interpolator
+            .state(sv, first - Duration::from_seconds(1.0))
+            .is_none());
+        assert!(interpolator
+            .state(sv, last + Duration::from_seconds(1.0))
+            .is_none());
+
+        if !complete.contains(&sv) {
+            continue;
+        }
+
+        // the polynomial passes through every node
+        let state = interpolator.state(sv, t);
+        assert!(state.is_some(), "{} {}: no state", sv, t);
+
+        let state = state.unwrap();
+        assert_eq!(state.source, QcOrbitSource::SP3);
+        assert!((state.orbit.radius_km.x - x_km).abs() < 1.0E-6);
+        assert!((state.orbit.radius_km.y - y_km).abs() < 1.0E-6);
+        assert!((state.orbit.radius_km.z - z_km).abs() < 1.0E-6);
+
+        // in between samples
+        let t = t + Duration::from_seconds(90.0);
+
+        if t < last {
+            let state = interpolator.state(sv, t);
+            assert!(state.is_some(), "{} {}: no state", sv, t);
+
+            let state = state.unwrap();
+            assert_eq!(state.orbit.epoch, t);
+
+            // GNSS orbital velocities
+            let speed_km_s = state.orbit.vmag_km_s();
+            assert!(
+                speed_km_s > 1.0 && speed_km_s < 5.0,
+                "{} {}",
+                sv,
+                speed_km_s
+            );
+        }
+    }
+}
+
+#[test]
+fn test_brdc_orbit_fallback() {
+    let mut context = QcContext::new();
+
+    context
+        .load_gzip_rinex_file("data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+        .unwrap();
+
+    context
+        .load_gzip_rinex_file("data/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+        .unwrap();
+
+    let observation = context.observation().unwrap();
+
+    // without SP3, states are resolved from broadcast ephemerides
+    let interpolator = context.orbit_interpolator(&QcOrbitOptions::default());
+
+    let mut total = 0;
+
+    for (t, state) in interpolator.observation_states_iter(observation) {
+        assert_eq!(state.source, QcOrbitSource::BroadcastNavigation);
+        assert_eq!(interpolator.brdc_state(state.sv, t), Some(state));
+        assert!(!interpolator.has_sp3_orbit(state.sv));
+
+        // from MEO to GEO orbits
+        let radius_km = state.orbit.rmag_km();
+        assert!(
+            radius_km > 20_000.0 && radius_km < 45_000.0,
+            "{} {}: {}",
+            state.sv,
+            t,
+            radius_km
+        );
+
+        total += 1;
+    }
+
+    assert!(total > 0, "no state resolved from broadcast ephemerides");
+
+    // without fallback, nothing may be resolved
+    let interpolator =
+        context.orbit_interpolator(&QcOrbitOptions::default().with_brdc_fallback(false));
+
+    assert_eq!(interpolator.observation_states_iter(observation).count(), 0);
+}
+
+#[test]
+fn test_sv_state_cache() {
+    let path = "data/SP3/D/COD0MGXFIN_20230500000_01D_05M_ORB.SP3.gz";
+
+    let mut context = QcContext::new();
+
+    context.load_gzip_sp3_file(path).unwrap();
+
+    let sp3 = context.sp3().unwrap();
+    let t = sp3.first_epoch().unwrap() + Duration::from_hours(12.0) + Duration::from_seconds(90.0);
+
+    let (sv, state) = sp3
+        .satellites_iter()
+        .find_map(|sv| Some((sv, context.sv_state(sv, t)?)))
+        .expect("no state resolved at noon");
+
+    assert_eq!(state.source, QcOrbitSource::SP3);
+
+    let state = Some(state);
+
+    // positions were cached on first use
+    assert!(context.orbit_samples.get().is_some());
+    assert_eq!(context.sv_state(sv, t), state);
+
+    // cache follows the high precision orbits
+    context.unload_file(path).unwrap();
+    assert!(context.orbit_samples.get().is_none());
+    assert!(context.sv_state(sv, t).is_none());
+
+    context.load_gzip_sp3_file(path).unwrap();
+    assert_eq!(context.sv_state(sv, t), state);
+}
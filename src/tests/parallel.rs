@@ -0,0 +1,171 @@
+use crate::{
+    context::BlobData,
+    prelude::QcContext,
+    tests::toolkit::obs_rinex::{header_comparison_eq, rinex_comparison_eq},
+};
+
+/// Compares [FileMetadata] of both contexts, except the loading instant
+fn metadata_comparison_eq(model: &QcContext, dut: &QcContext) {
+    assert_eq!(model.metadata.len(), dut.metadata.len());
+
+    for (path, model) in model.metadata.iter() {
+        let dut = dut
+            .metadata
+            .get(path)
+            .unwrap_or_else(|| panic!("missing metadata for {:?}", path));
+
+        assert_eq!(model.product, dut.product);
+        assert_eq!(model.station, dut.station);
+        assert_eq!(model.agency, dut.agency);
+        assert_eq!(model.operator, dut.operator);
+        assert_eq!(model.sha256, dut.sha256);
+        assert_eq!(model.size, dut.size);
+        assert_eq!(model.header, dut.header);
+    }
+}
+
+#[test]
+fn test_parallel_loading() {
+    let paths = [
+        "data/OBS/V2/AJAC3550.21O",
+        "data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz",
+        "data/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz",
+        #[cfg(feature = "sp3")]
+        "data/SP3/D/COD0MGXFIN_20230500000_01D_05M_ORB.SP3.gz",
+    ];
+
+    let mut sequential = QcContext::new();
+
+    for path in paths.iter() {
+        sequential
+            .load_file(path)
+            .unwrap_or_else(|e| panic!("failed to load \"{}\": {}", path, e));
+    }
+
+    let mut parallel = QcContext::new();
+
+    let products = parallel.load_files_parallel(&paths).unwrap();
+
+    assert_eq!(products.len(), paths.len());
+
+    for (path, product) in paths.iter().zip(products.iter()) {
+        assert_eq!(
+            sequential
+                .metadata
+                .get(std::path::Path::new(path))
+                .unwrap()
+                .product,
+            *product,
+            "invalid identification for \"{}\"",
+            path
+        );
+    }
+
+    assert_eq!(sequential.files, parallel.files);
+    metadata_comparison_eq(&sequential, &parallel);
+
+    assert_eq!(sequential.blob.len(), parallel.blob.len());
+
+    for (key, model) in sequential.blob.iter() {
+        let dut = parallel
+            .blob
+            .get(key)
+            .unwrap_or_else(|| panic!("missing {:?}", key));
+
+        match (model, dut) {
+            (BlobData::RINEX(model), BlobData::RINEX(dut)) => {
+                if model.is_observation_rinex() {
+                    rinex_comparison_eq(model, dut);
+                } else {
+                    header_comparison_eq(&model.header, &dut.header);
+                    assert_eq!(
+                        model.epoch_iter().collect::<Vec<_>>(),
+                        dut.epoch_iter().collect::<Vec<_>>(),
+                    );
+                    assert_eq!(
+                        model.sv_iter().collect::<Vec<_>>(),
+                        dut.sv_iter().collect::<Vec<_>>(),
+                    );
+                }
+            }
+            #[cfg(feature = "sp3")]
+            (BlobData::SP3(model), BlobData::SP3(dut)) => {
+                assert_eq!(model.header.agency, dut.header.agency);
+                assert_eq!(
+                    model.satellites_iter().collect::<Vec<_>>(),
+                    dut.satellites_iter().collect::<Vec<_>>(),
+                );
+            }
+            #[allow(unreachable_patterns)]
+            _ => panic!("{:?}: product mismatch", key),
+        }
+    }
+
+    // loading on top of existing data
+    let mut sequential = QcContext::new();
+    let mut parallel = QcContext::new();
+
+    sequential.load_file(paths[0]).unwrap();
+    parallel.load_file(paths[0]).unwrap();
+
+    sequential.load_file(paths[1]).unwrap();
+    sequential.load_file(paths[2]).unwrap();
+    parallel.load_files_parallel(&paths[1..3]).unwrap();
+
+    assert_eq!(sequential.files, parallel.files);
+    metadata_comparison_eq(&sequential, &parallel);
+
+    // failure does not modify the context
+    assert!(parallel
+        .load_files_parallel(&["data/OBS/V2/AJAC3550.21O", "Cargo.toml"])
+        .is_err());
+
+    assert_eq!(sequential.files, parallel.files);
+}
+
+#[test]
+fn test_parallel_loading_preprocessed() {
+    use crate::prelude::{Constellation, QcPipeline, QcPipelineStep};
+
+    let paths = [
+        "data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz",
+        "data/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz",
+    ];
+
+    let pipeline = QcPipeline::default().with_step(QcPipelineStep::Filter("GPS".to_string()));
+
+    let mut sequential = QcContext::new();
+    let mut parallel = QcContext::new();
+
+    for context in [&mut sequential, &mut parallel] {
+        context.load_file("data/OBS/V2/AJAC3550.21O").unwrap();
+        context.apply_pipeline(&pipeline).unwrap();
+    }
+
+    for path in paths.iter() {
+        sequential.load_file(path).unwrap();
+    }
+
+    parallel.load_files_parallel(&paths).unwrap();
+
+    assert_eq!(sequential.files, parallel.files);
+    metadata_comparison_eq(&sequential, &parallel);
+
+    // new data is preprocessed like the rest of the context
+    for station in ["AJAC", "ESBC"] {
+        let model = sequential.observation_of(station).unwrap();
+        let dut = parallel.observation_of(station).unwrap();
+
+        rinex_comparison_eq(model, dut);
+
+        for sv in dut.sv_iter() {
+            assert_eq!(sv.constellation, Constellation::GPS);
+        }
+    }
+
+    for sv in parallel.brdc_navigation().unwrap().sv_iter() {
+        assert_eq!(sv.constellation, Constellation::GPS);
+    }
+
+    assert_eq!(parallel.preprocessing(), &pipeline);
+}
@@ -0,0 +1,133 @@
+use std::fs::{copy, remove_dir_all};
+
+use crate::{
+    prelude::{
+        Constellation, Filter, FilterItem, MaskOperand, Preprocessing, QcContext, QcPipeline,
+        QcPipelineStep, QcScope, QcScopeType, Rinex,
+    },
+    tests::toolkit::test_directory,
+};
+
+#[test]
+fn test_partition_same_file_names() {
+    let mut context = QcContext::new();
+
+    let mut paths = Vec::new();
+
+    let mut directories = Vec::new();
+
+    for dir in ["partition-a", "partition-b"] {
+        let dir = test_directory(dir);
+
+        let path = dir.join("AJAC3550.21O");
+        copy("data/OBS/V2/AJAC3550.21O", &path).unwrap();
+
+        context.load_rinex_file(&path).unwrap();
+        paths.push(path);
+        directories.push(dir);
+    }
+
+    // same file name, different files
+    let partitions = context.partition(QcScopeType::FileName).unwrap();
+    assert_eq!(partitions.len(), 2);
+
+    for (scope, ctx) in partitions.iter() {
+        let path = match scope {
+            QcScope::FileName(path) => path,
+            scope => panic!("invalid scope {}", scope),
+        };
+
+        let files = ctx.station_files("AJAC");
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].to_string_lossy(), path.as_str());
+
+        assert!(ctx.has_observation());
+    }
+
+    for dir in directories {
+        let _ = remove_dir_all(&dir);
+    }
+}
+
+#[test]
+fn test_partition_in_memory() {
+    let rinex = Rinex::from_file("data/OBS/V2/AJAC3550.21O").unwrap();
+
+    let epochs = rinex.epoch_iter().collect::<Vec<_>>();
+    let mid = epochs[epochs.len() / 2];
+
+    let first_half = rinex.filter(&Filter::mask(
+        MaskOperand::LowerThan,
+        FilterItem::EpochItem(mid),
+    ));
+
+    let second_half = rinex.filter(&Filter::mask(
+        MaskOperand::GreaterEquals,
+        FilterItem::EpochItem(mid),
+    ));
+
+    let first_size = first_half.epoch_iter().count();
+    let second_size = second_half.epoch_iter().count();
+
+    assert_eq!(first_size + second_size, epochs.len());
+
+    // loaded from memory: these files do not exist
+    let mut context = QcContext::new();
+
+    context
+        .load_rinex("memory/AJAC3550.21O", first_half)
+        .unwrap();
+
+    context
+        .load_rinex("memory/AJAC3551.21O", second_half)
+        .unwrap();
+
+    context
+        .apply_pipeline(&QcPipeline::default().with_step(QcPipelineStep::Filter("GPS".to_string())))
+        .unwrap();
+
+    // one station: single merged blob
+    assert_eq!(context.stations_iter().count(), 1);
+
+    let partitions = context.partition(QcScopeType::FileName).unwrap();
+    assert_eq!(partitions.len(), 2);
+
+    for (scope, ctx) in partitions.iter() {
+        let expected = match scope {
+            QcScope::FileName(path) if path.ends_with("AJAC3550.21O") => first_size,
+            QcScope::FileName(_) => second_size,
+            scope => panic!("invalid scope {}", scope),
+        };
+
+        let obs = ctx.observation().unwrap();
+
+        // contribution of each file is extracted from memory
+        assert_eq!(obs.epoch_iter().count(), expected, "{}", scope);
+
+        // preprocessing is preserved
+        assert_eq!(ctx.preprocessing(), context.preprocessing());
+
+        for sv in obs.sv_iter() {
+            assert_eq!(sv.constellation, Constellation::GPS);
+        }
+    }
+}
+
+#[test]
+fn test_scoped_by_station() {
+    let mut context = QcContext::new();
+
+    context.load_rinex_file("data/OBS/V2/AJAC3550.21O").unwrap();
+    context.load_rinex_file("data/NAV/V2/amel0010.21g").unwrap();
+
+    for station in ["AJAC", "ajac", "AJAC00FRA"] {
+        let ctx = context
+            .scoped(&QcScope::Station(station.to_string()))
+            .unwrap();
+
+        assert!(ctx.observation_of("AJAC").is_some(), "{}", station);
+
+        // shared products are preserved
+        assert!(ctx.has_brdc_navigation(), "{}", station);
+    }
+}
@@ -0,0 +1,156 @@
+use qc_traits::{Filter, FilterItem, MaskOperand, Preprocessing};
+
+use maud::Render;
+
+use rinex::observation::ObsKey;
+
+use crate::prelude::{
+    Constellation, Duration, QcConfig, QcContext, QcPipeline, QcPipelineStep, QcReport, Rinex,
+};
+
+fn assert_gps_only(rinex: &Rinex) {
+    for sv in rinex.sv_iter() {
+        assert_eq!(sv.constellation, Constellation::GPS);
+    }
+}
+
+#[test]
+fn test_pipeline_new_files() {
+    let mut context = QcContext::new();
+
+    context.load_file("data/OBS/V2/AJAC3550.21O").unwrap();
+
+    let pipeline = QcPipeline::default().with_step(QcPipelineStep::Filter("GPS".to_string()));
+    context.apply_pipeline(&pipeline).unwrap();
+
+    // files loaded afterwards are preprocessed as well
+    context
+        .load_file("data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+        .unwrap();
+
+    context
+        .load_file("data/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+        .unwrap();
+
+    assert_eq!(context.stations_iter().count(), 2);
+
+    for station in ["AJAC", "ESBC"] {
+        let obs = context.observation_of(station).unwrap();
+        assert!(obs.epoch_iter().count() > 0);
+        assert_gps_only(obs);
+    }
+
+    let brdc = context.brdc_navigation().unwrap();
+    assert!(brdc.sv_iter().count() > 0);
+    assert_gps_only(brdc);
+
+    assert_eq!(context.preprocessing(), &pipeline);
+}
+
+#[test]
+fn test_pipeline_appended_chunks() {
+    let path = "data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz";
+
+    let full = Rinex::from_gzip_file(path).unwrap();
+    let t_end = full.last_epoch().unwrap();
+    let mid = full
+        .epoch_iter()
+        .nth(full.epoch_iter().count() / 2)
+        .unwrap();
+
+    let first_half = full.filter(&Filter::mask(
+        MaskOperand::LowerThan,
+        FilterItem::EpochItem(mid),
+    ));
+
+    let second_half = full.filter(&Filter::mask(
+        MaskOperand::GreaterEquals,
+        FilterItem::EpochItem(mid),
+    ));
+
+    let mut context = QcContext::new();
+
+    context.append_rinex(path, first_half).unwrap();
+
+    let pipeline = QcPipeline::default().with_step(QcPipelineStep::Filter("GPS".to_string()));
+    context.apply_pipeline(&pipeline).unwrap();
+
+    context.append_rinex(path, second_half).unwrap();
+
+    let obs = context.observation().unwrap();
+    assert_eq!(obs.last_epoch(), Some(t_end));
+    assert_gps_only(obs);
+
+    // single epochs are preprocessed as well
+    let (key, observations) = full.record.as_obs().unwrap().iter().last().unwrap();
+
+    assert!(observations
+        .signals
+        .iter()
+        .any(|signal| signal.sv.constellation != Constellation::GPS));
+
+    let key = ObsKey {
+        epoch: key.epoch + Duration::from_seconds(30.0),
+        flag: key.flag,
+    };
+
+    context
+        .append_observation("ESBC", key, observations.clone())
+        .unwrap();
+
+    let obs = context.observation().unwrap();
+    assert_eq!(obs.last_epoch(), Some(key.epoch));
+    assert_gps_only(obs);
+}
+
+#[test]
+fn test_pipeline_from_config() {
+    let cfg: QcConfig = toml::from_str(
+        r#"
+        report = "Full"
+
+        [[pipeline.steps]]
+        filter = "GPS"
+
+        [[pipeline.steps]]
+        repair = "zero"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        cfg.pipeline,
+        QcPipeline::default()
+            .with_step(QcPipelineStep::Filter("GPS".to_string()))
+            .with_step(QcPipelineStep::Repair("zero".to_string()))
+    );
+
+    let mut context = QcContext::new();
+    context.load_file("data/OBS/V2/AJAC3550.21O").unwrap();
+
+    // the configured pipeline is applied by the report
+    let html = QcReport::new(&context, cfg.clone()).render().into_string();
+
+    assert!(html.contains("Preprocessing"));
+    assert!(html.contains("filter: GPS"));
+    assert!(html.contains("repair: zero"));
+
+    // the context itself is not modified
+    assert!(context.preprocessing().steps.is_empty());
+
+    // nor preprocessed twice
+    context.apply_pipeline(&cfg.pipeline).unwrap();
+    let html = QcReport::new(&context, cfg).render().into_string();
+    assert_eq!(html.matches("filter: GPS").count(), 1);
+
+    // invalid pipelines are not applied
+    let cfg = QcConfig::default().with_pipeline(
+        QcPipeline::default().with_step(QcPipelineStep::Repair("unknown".to_string())),
+    );
+
+    let mut context = QcContext::new();
+    context.load_file("data/OBS/V2/AJAC3550.21O").unwrap();
+
+    let html = QcReport::new(&context, cfg).render().into_string();
+    assert!(!html.contains("repair: unknown"));
+}
@@ -0,0 +1,131 @@
+use std::path::Path;
+
+use qc_traits::{Filter, FilterItem, MaskOperand, Preprocessing};
+
+use rinex::observation::ObsKey;
+
+use crate::{
+    context::{BlobData, BlobKey},
+    prelude::{Duration, EarthOrientation, EopFormat, Error, ProductType, QcContext, Rinex},
+};
+
+#[test]
+fn test_realtime_appending() {
+    let path = "data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz";
+
+    let full = Rinex::from_gzip_file(path).unwrap();
+    let total = full.epoch_iter().count();
+
+    let t0 = full.first_epoch().unwrap();
+    let t_end = full.last_epoch().unwrap();
+
+    let mut context = QcContext::new();
+    let mut window = context.sliding_window(Duration::from_hours(1.0));
+    let mut first_chunk = true;
+
+    // simulates a file tail, one hour at a time
+    let mut t = t0;
+
+    while t <= t_end {
+        let chunk = full
+            .filter(&Filter::mask(
+                MaskOperand::GreaterEquals,
+                FilterItem::EpochItem(t),
+            ))
+            .filter(&Filter::mask(
+                MaskOperand::LowerThan,
+                FilterItem::EpochItem(t + Duration::from_hours(1.0)),
+            ));
+
+        window.push_rinex(&chunk);
+        context.append_rinex(path, chunk).unwrap();
+
+        let meta = context.metadata.get(Path::new(path)).unwrap();
+        assert_eq!(meta.time_span.unwrap().0, t0);
+
+        // chunks no longer match the checksum of the first one
+        if !first_chunk {
+            assert!(meta.sha256.is_none());
+            assert!(meta.size.is_none());
+        }
+
+        first_chunk = false;
+        t += Duration::from_hours(1.0);
+    }
+
+    let meta = context.metadata.get(Path::new(path)).unwrap();
+    assert_eq!(meta.time_span, Some((t0, t_end)));
+
+    assert_eq!(context.files(ProductType::Observation).unwrap().len(), 1);
+    assert_eq!(context.observation().unwrap().epoch_iter().count(), total);
+
+    let summary = window.summary();
+    assert_eq!(summary.last_epoch, Some(t_end));
+    assert_eq!(summary.epochs, 120);
+
+    // the window is up to date with the context
+    let reference = context.sliding_window(Duration::from_hours(1.0)).summary();
+    assert_eq!(reference.first_epoch, summary.first_epoch);
+    assert_eq!(reference.satellites, summary.satellites);
+
+    for (constellation, cn0) in reference.cn0_dbhz.iter() {
+        assert!((summary.cn0_dbhz[constellation] - cn0).abs() < 1.0E-6);
+    }
+
+    // bounded memory
+    context.discard_before_mut(t_end - Duration::from_hours(1.0) + Duration::from_seconds(30.0));
+    assert_eq!(context.observation().unwrap().epoch_iter().count(), 120);
+
+    // individual records
+    let (key, observations) = full.record.as_obs().unwrap().iter().last().unwrap();
+
+    let key = ObsKey {
+        epoch: key.epoch + Duration::from_seconds(30.0),
+        flag: key.flag,
+    };
+
+    let station = context.stations_iter().next().unwrap().to_string();
+
+    context
+        .append_observation(&station, key, observations.clone())
+        .unwrap();
+
+    window.push(&key, observations);
+
+    assert_eq!(context.observation().unwrap().epoch_iter().count(), 121);
+    assert_eq!(window.summary().epochs, 120);
+    assert_eq!(window.summary().last_epoch, Some(key.epoch));
+
+    match context.append_observation("UNKNOWN", key, observations.clone()) {
+        Err(Error::UnknownStation(station)) => assert_eq!(station, "UNKNOWN"),
+        _ => panic!("should have failed"),
+    }
+}
+
+#[test]
+fn test_realtime_appending_mismatch() {
+    let path = "data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz";
+
+    let full = Rinex::from_gzip_file(path).unwrap();
+
+    let mut context = QcContext::new();
+    context.append_rinex(path, full.clone()).unwrap();
+
+    // corrupt the loaded blob: appending must not silently drop the chunk
+    let key = BlobKey {
+        product: ProductType::Observation,
+        station: Some("ESBC".to_string()),
+    };
+
+    let eop = EarthOrientation {
+        format: EopFormat::IgsErp,
+        samples: Default::default(),
+    };
+
+    assert!(context.blob.insert(key, BlobData::EOP(eop)).is_some());
+
+    match context.append_rinex(path, full) {
+        Err(Error::ProductTypeMismatch) => {}
+        _ => panic!("should have failed"),
+    }
+}
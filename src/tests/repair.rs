@@ -0,0 +1,145 @@
+use rinex::navigation::{NavFrame, OrbitItem};
+
+use crate::prelude::{Constellation, ProductType, QcContext, Repair};
+
+#[test]
+fn test_repair_clock() {
+    let mut context = QcContext::new();
+
+    context
+        .load_gzip_rinex_file("data/CLK/V3/GRG0MGXFIN_20201770000_01D_30S_CLK.CLK.gz")
+        .unwrap();
+
+    // original data
+    context.repair_mut(Repair::Zero);
+
+    let size = {
+        let record = context.clock_mut().unwrap().record.as_mut_clock().unwrap();
+        let size = record
+            .values()
+            .map(|profiles| profiles.len())
+            .sum::<usize>();
+
+        let mut profiles = record
+            .values_mut()
+            .flat_map(|profiles| profiles.values_mut());
+
+        profiles.next().unwrap().bias = 0.0;
+        profiles.next().unwrap().bias = f64::NAN;
+        size
+    };
+
+    let summary = context.repair_mut(Repair::Zero);
+
+    assert_eq!(summary.removed(ProductType::HighPrecisionClock), 2);
+    assert_eq!(summary.invalidated(ProductType::HighPrecisionClock), 0);
+    assert_eq!(summary.total(), 2);
+
+    let record = context.clock().unwrap().record.as_clock().unwrap();
+
+    assert_eq!(
+        record
+            .values()
+            .map(|profiles| profiles.len())
+            .sum::<usize>(),
+        size - 2
+    );
+
+    // nothing left to repair
+    assert_eq!(context.repair_mut(Repair::Zero).total(), 0);
+}
+
+#[test]
+fn test_repair_navigation() {
+    let mut context = QcContext::new();
+
+    context
+        .load_gzip_rinex_file("data/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+        .unwrap();
+
+    // original data
+    context.repair_mut(Repair::Zero);
+
+    let size = {
+        let record = context
+            .brdc_navigation_mut()
+            .unwrap()
+            .record
+            .as_mut_nav()
+            .unwrap();
+
+        let size = record.len();
+
+        let mut ephemerides = record
+            .iter_mut()
+            .filter(|(k, _)| k.sv.constellation == Constellation::GPS)
+            .filter_map(|(_, frame)| match frame {
+                NavFrame::EPH(eph) => Some(eph),
+                _ => None,
+            });
+
+        ephemerides.next().unwrap().clock_bias = f64::NAN;
+
+        ephemerides
+            .next()
+            .unwrap()
+            .orbits
+            .insert("sqrta".to_string(), OrbitItem::F64(-1.0));
+
+        size
+    };
+
+    let summary = context.repair_mut(Repair::Zero);
+
+    assert_eq!(summary.removed(ProductType::BroadcastNavigation), 2);
+    assert_eq!(summary.total(), 2);
+
+    let record = context.brdc_navigation().unwrap().record.as_nav().unwrap();
+    assert_eq!(record.len(), size - 2);
+}
+
+#[test]
+#[cfg(feature = "sp3")]
+fn test_repair_sp3() {
+    let mut context = QcContext::new();
+
+    context
+        .load_gzip_sp3_file("data/SP3/D/COD0MGXFIN_20230500000_01D_05M_ORB.SP3.gz")
+        .unwrap();
+
+    // original data
+    context.repair_mut(Repair::Zero);
+
+    let size = {
+        let sp3 = context.sp3_mut().unwrap();
+        let size = sp3.data.len();
+
+        let mut entries = sp3.data.values_mut();
+
+        entries.next().unwrap().position_km = (0.0, 0.0, 0.0);
+        entries.next().unwrap().position_km = (999999.0, 0.0, 0.0);
+
+        let entry = entries.find(|entry| entry.clock_us.is_some()).unwrap();
+        entry.clock_us = Some(999999.0);
+
+        size
+    };
+
+    let summary = context.repair_mut(Repair::Zero);
+
+    // bad clocks are reported separately: samples are preserved
+    assert_eq!(summary.removed(ProductType::HighPrecisionOrbit), 2);
+    assert_eq!(summary.invalidated(ProductType::HighPrecisionOrbit), 1);
+    assert_eq!(summary.total(), 2);
+
+    let sp3 = context.sp3().unwrap();
+    assert_eq!(sp3.data.len(), size - 2);
+
+    assert_eq!(
+        sp3.data
+            .values()
+            .filter(|entry| entry.clock_us == Some(999999.0))
+            .count(),
+        0
+    );
+}
@@ -0,0 +1,131 @@
+use std::{io::Write, str::FromStr};
+
+use crate::{
+    error::Error,
+    prelude::{Epoch, ProductType, QcContext, StreamError},
+    tests::toolkit::{
+        rtcm::{gps_ephemeris, gps_msm4, MSG_1005},
+        test_directory,
+    },
+};
+
+#[test]
+fn test_rtcm_stream_loading() {
+    let directory = test_directory("rtcm-loading");
+    let path = directory.join("MLVL00FRA_R_20240070200_01H_MO.rtcm3");
+
+    let mut fd = std::fs::File::create(&path).unwrap();
+    fd.write_all(&MSG_1005).unwrap();
+    fd.write_all(&gps_ephemeris(12, 2296, 7200)).unwrap();
+
+    // sunday 02:00:10 and 02:00:20
+    for tow_ms in [7_210_000, 7_220_000] {
+        fd.write_all(&gps_msm4(12, tow_ms, 70.25, 70.25, 42))
+            .unwrap();
+        fd.write_all(&gps_msm4(25, tow_ms, 75.5, 75.5, 38)).unwrap();
+    }
+    drop(fd);
+
+    let mut context = QcContext::new();
+
+    // the observations prevail
+    let product = context.load_file(&path).unwrap();
+    assert_eq!(product, ProductType::Observation);
+
+    assert!(context.has_observation());
+    assert!(context.has_brdc_navigation());
+    assert!(context.is_navigation_compatible());
+
+    assert_eq!(context.stations_iter().collect::<Vec<_>>(), vec!["MLVL"]);
+
+    let obs = context.observation().unwrap();
+    assert_eq!(obs.epoch_iter().count(), 2);
+    assert!(obs.header.rx_position.is_some());
+
+    let provenance = context.provenance();
+    assert_eq!(provenance.len(), 2);
+
+    for entry in provenance.iter() {
+        assert!(entry.header.starts_with("RTCM3 stream"));
+
+        if entry.product == ProductType::Observation {
+            assert_eq!(entry.station.as_deref(), Some("MLVL"));
+        } else {
+            assert_eq!(entry.product, ProductType::BroadcastNavigation);
+            assert!(entry.station.is_none());
+        }
+    }
+
+    // all products are unloaded at once
+    context.unload_file(&path).unwrap();
+
+    assert!(!context.has_observation());
+    assert!(!context.has_brdc_navigation());
+    assert!(context.provenance().is_empty());
+
+    // explicit loading
+    let products = context.load_rtcm_file(&path).unwrap();
+
+    assert_eq!(
+        products,
+        vec![ProductType::BroadcastNavigation, ProductType::Observation]
+    );
+
+    let _ = std::fs::remove_dir_all(&directory);
+}
+
+#[test]
+fn test_rtcm_stream_rejection() {
+    let directory = test_directory("rtcm-rejection");
+    let path = directory.join("invalid.bin");
+
+    let mut fd = std::fs::File::create(&path).unwrap();
+    fd.write_all(&[0xD3, 0x00, 0x13, 0xFF, 0xFF, 0x00, 0x01])
+        .unwrap();
+    drop(fd);
+
+    let mut context = QcContext::new();
+
+    assert!(context.load_file(&path).is_err());
+    assert!(context.load_rtcm_file(&path).is_err());
+
+    let _ = std::fs::remove_dir_all(&directory);
+}
+
+#[test]
+fn test_rtcm_stream_reference_epoch() {
+    let directory = test_directory("rtcm-reference-epoch");
+    let named = directory.join("MLVL00FRA_R_20240070200_01H_MO.obs.rtcm3");
+    let unnamed = directory.join("observations.bin");
+
+    // observations only: the time of week is resolved against the file name
+    for path in [&named, &unnamed] {
+        let mut fd = std::fs::File::create(path).unwrap();
+        fd.write_all(&MSG_1005).unwrap();
+        fd.write_all(&gps_msm4(12, 7_210_000, 70.25, 70.25, 42))
+            .unwrap();
+    }
+
+    let mut context = QcContext::new();
+
+    context.load_rtcm_file(&named).unwrap();
+
+    let expected = Epoch::from_str("2024-01-07T02:00:10 GPST").unwrap();
+    assert_eq!(context.observation().unwrap().first_epoch(), Some(expected));
+
+    let mut context = QcContext::new();
+
+    assert!(matches!(
+        context.load_rtcm_file(&unnamed),
+        Err(Error::StreamDecoding(StreamError::NoReferenceEpoch))
+    ));
+
+    assert!(context.provenance().is_empty());
+
+    let reference = Epoch::from_str("2024-01-08T00:00:00 GPST").unwrap();
+    context.load_rtcm_file_at(&unnamed, reference).unwrap();
+
+    assert_eq!(context.observation().unwrap().first_epoch(), Some(expected));
+
+    let _ = std::fs::remove_dir_all(&directory);
+}
@@ -0,0 +1,111 @@
+use std::{fs::remove_dir_all, io::Write};
+
+use crate::{
+    context::snapshot::SNAPSHOT_REVISION,
+    error::Error,
+    prelude::{ProductType, QcContext},
+    tests::toolkit::{obs_rinex::rinex_comparison_eq, test_directory},
+};
+
+#[test]
+fn test_snapshot_roundtrip() {
+    let mut context = QcContext::new();
+
+    context.load_file("data/OBS/V2/AJAC3550.21O").unwrap();
+    context.load_file("data/NAV/V2/amel0010.21g").unwrap();
+
+    let directory = test_directory("snapshot-roundtrip");
+    let path = directory.join("context.qcs");
+
+    context.save_snapshot(&path).unwrap();
+
+    let restored = QcContext::from_snapshot(&path).unwrap();
+
+    assert_eq!(context.files, restored.files);
+    assert_eq!(context.metadata, restored.metadata);
+    assert_eq!(context.blob.len(), restored.blob.len());
+
+    rinex_comparison_eq(
+        context.observation().unwrap(),
+        restored.observation().unwrap(),
+    );
+
+    assert_eq!(
+        context
+            .brdc_navigation()
+            .unwrap()
+            .epoch_iter()
+            .collect::<Vec<_>>(),
+        restored
+            .brdc_navigation()
+            .unwrap()
+            .epoch_iter()
+            .collect::<Vec<_>>(),
+    );
+
+    assert!(restored.files(ProductType::Observation).is_some());
+
+    #[cfg(feature = "navigation")]
+    assert_eq!(context.earth_cef, restored.earth_cef);
+
+    let _ = remove_dir_all(&directory);
+}
+
+#[test]
+fn test_snapshot_rejection() {
+    let directory = test_directory("snapshot-rejection");
+
+    // not a snapshot
+    let path = directory.join("invalid.qcs");
+
+    let mut fd = std::fs::File::create(&path).unwrap();
+    fd.write_all(b"this is not a snapshot").unwrap();
+    drop(fd);
+
+    assert!(matches!(
+        QcContext::from_snapshot(&path),
+        Err(Error::NotASnapshot)
+    ));
+
+    // valid magic, followed by a huge string length
+    let mut fd = std::fs::File::create(&path).unwrap();
+    fd.write_all(b"GQCS").unwrap();
+    fd.write_all(&SNAPSHOT_REVISION.to_le_bytes()).unwrap();
+    fd.write_all(&u64::MAX.to_le_bytes()).unwrap();
+    drop(fd);
+
+    assert!(matches!(
+        QcContext::from_snapshot(&path),
+        Err(Error::NotASnapshot)
+    ));
+
+    // valid header, corrupt content
+    let current = (
+        *b"GQCS",
+        SNAPSHOT_REVISION,
+        env!("CARGO_PKG_VERSION").to_string(),
+    );
+
+    let mut fd = std::fs::File::create(&path).unwrap();
+    bincode::serialize_into(&mut fd, &current).unwrap();
+    fd.write_all(&u64::MAX.to_le_bytes()).unwrap();
+    drop(fd);
+
+    assert!(matches!(
+        QcContext::from_snapshot(&path),
+        Err(Error::SnapshotEncoding(_))
+    ));
+
+    // stale snapshot
+    let path = directory.join("stale.qcs");
+
+    let fd = std::fs::File::create(&path).unwrap();
+    bincode::serialize_into(fd, &(*b"GQCS", 0u32, "0.0.0".to_string())).unwrap();
+
+    assert!(matches!(
+        QcContext::from_snapshot(&path),
+        Err(Error::OutdatedSnapshot(0, _))
+    ));
+
+    let _ = remove_dir_all(&directory);
+}
@@ -0,0 +1,72 @@
+use std::fs::{copy, remove_dir_all};
+
+use crate::{
+    prelude::{ProductType, QcContext},
+    tests::toolkit::test_directory,
+};
+
+#[test]
+fn test_station_keyed_storage() {
+    // same station, V2 short and V3 long file names
+    let directory = test_directory("station-keyed-storage");
+    let long_name = directory.join("AJAC00FRA_R_20213550000_01D_30S_MO.rnx");
+    copy("data/OBS/V2/AJAC3550.21O", &long_name).unwrap();
+
+    let mut context = QcContext::new();
+
+    context.load_rinex_file("data/OBS/V2/AJAC3550.21O").unwrap();
+    context.load_rinex_file(&long_name).unwrap();
+
+    // one station, described by both files
+    assert_eq!(context.stations_iter().collect::<Vec<_>>(), vec!["AJAC"]);
+    assert_eq!(context.station_files("AJAC").len(), 2);
+
+    // any form of the station name is resolved
+    for name in ["AJAC", "ajac", "AJAC00FRA"] {
+        assert!(context.observation_of(name).is_some(), "{}", name);
+    }
+
+    assert_eq!(context.files(ProductType::Observation).unwrap().len(), 2);
+
+    // other stations are kept apart
+    #[cfg(feature = "flate2")]
+    {
+        context
+            .load_gzip_rinex_file("data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+            .unwrap();
+
+        assert_eq!(
+            context.stations_iter().collect::<Vec<_>>(),
+            vec!["AJAC", "ESBC"]
+        );
+
+        assert!(context.observation_of("ESBC00DNK").is_some());
+        assert!(context.observation_of("ESBC").is_some());
+    }
+
+    let _ = remove_dir_all(&directory);
+}
+
+#[test]
+#[cfg(feature = "navigation")]
+fn test_unnamed_station_reference_position() {
+    use crate::prelude::{ReferencePositionSource, Rinex};
+
+    let mut rinex = Rinex::from_file("data/OBS/V2/AJAC3550.21O").unwrap();
+    assert!(rinex.header.rx_position.is_some());
+
+    // station is neither named by the header, nor by the file name
+    rinex.header.geodetic_marker = None;
+
+    let mut context = QcContext::new();
+    context.load_rinex("obs", rinex).unwrap();
+
+    assert!(context.stations_iter().next().is_none());
+    assert!(context.observation().is_some());
+
+    // header position is used
+    let reference = context.reference_rx_position().unwrap();
+    assert_eq!(reference.source, ReferencePositionSource::RinexHeader);
+    assert!(reference.ecef_m.0.abs() > 1.0E6);
+    assert!(context.reference_rx_orbit().is_some());
+}
@@ -74,3 +74,227 @@ fn test_gps_gst_timescale_transposition() {
 
     // verify_dut_header(&transposed_obs.header, TimeScale::GST);
 }
+
+#[test]
+fn test_navigation_timescale_transposition() {
+    let mut context = QcContext::new();
+
+    context
+        .load_gzip_rinex_file("data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+        .unwrap();
+
+    context
+        .load_gzip_rinex_file("data/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+        .unwrap();
+
+    // multi-GNSS navigation frames are natively expressed in several timescales,
+    // which is not a conflict
+    assert!(context.timescales().len() > 2);
+    assert!(context.timescale_conflicts().is_empty());
+
+    let original = context.brdc_navigation().unwrap().clone();
+
+    context.timescale_transposition_mut(TimeScale::GST);
+
+    assert_eq!(context.timescale(), Some(TimeScale::GST));
+    assert!(context.timescale_conflicts().is_empty());
+
+    // ToC and ToE remain expressed in the timescale of each constellation
+    let brdc = context.brdc_navigation().unwrap();
+
+    assert_eq!(
+        brdc.record.as_nav().unwrap().keys().collect::<Vec<_>>(),
+        original.record.as_nav().unwrap().keys().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+#[cfg(feature = "navigation")]
+fn test_navigation_transposition_positions() {
+    use crate::prelude::Constellation;
+
+    let mut context = QcContext::new();
+
+    context
+        .load_gzip_rinex_file("data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+        .unwrap();
+
+    context
+        .load_gzip_rinex_file("data/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+        .unwrap();
+
+    let epochs = context
+        .observation()
+        .unwrap()
+        .epoch_iter()
+        .step_by(120)
+        .collect::<Vec<_>>();
+
+    let satellites = context
+        .brdc_navigation()
+        .unwrap()
+        .sv_iter()
+        .filter(|sv| {
+            matches!(
+                sv.constellation,
+                Constellation::GPS | Constellation::Galileo | Constellation::BeiDou
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let positions = |context: &QcContext| {
+        let brdc = context.brdc_navigation().unwrap();
+
+        epochs
+            .iter()
+            .flat_map(|t| {
+                satellites
+                    .iter()
+                    .filter_map(move |sv| Some(((*sv, *t), brdc.sv_orbit(*sv, *t)?.radius_km)))
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let original = positions(&context);
+    assert!(!original.is_empty());
+
+    let database = context.time_corrections_database().unwrap();
+
+    context
+        .precise_time_correction_mut(&database, TimeScale::GST)
+        .unwrap();
+
+    assert_eq!(context.timescale(), Some(TimeScale::GST));
+    assert_eq!(positions(&context), original);
+
+    context.timescale_transposition_mut(TimeScale::BDT);
+
+    assert_eq!(context.timescale(), Some(TimeScale::BDT));
+    assert_eq!(positions(&context), original);
+}
+
+#[test]
+#[cfg(feature = "navigation")]
+fn test_time_corrections_sources() {
+    use crate::prelude::{Epoch, QcTimeCorrectionSource, QcTimeCorrectionsDB};
+    use std::str::FromStr;
+
+    let mut context = QcContext::new();
+
+    context
+        .load_gzip_rinex_file("data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+        .unwrap();
+
+    // no navigation data
+    assert!(context.time_corrections().is_empty());
+    assert!(context.time_corrections_database().is_none());
+
+    let mut corrections = QcTimeCorrectionsDB::default();
+
+    corrections
+        .parse_table(
+            "utc-k",
+            "# UTC(k) - GPST [ns]
+            2020-06-25T00:00:00 UTC, UTC, GPST, 12.0, 0.0, 86400"
+                .as_bytes(),
+        )
+        .unwrap();
+
+    assert_eq!(corrections.corrections.len(), 1);
+
+    let source = QcTimeCorrectionSource::UserTable("utc-k".to_string());
+    assert_eq!(corrections.source_iter(&source).count(), 1);
+
+    let correction = &corrections.corrections[0];
+    assert_eq!(correction.lhs, TimeScale::UTC);
+    assert_eq!(correction.rhs, TimeScale::GPST);
+    assert_eq!(
+        correction.reference,
+        Epoch::from_str("2020-06-25T00:00:00 UTC").unwrap()
+    );
+    assert!((correction.polynomial.0 - 12.0E-9).abs() < 1.0E-15);
+
+    // invalid tables are reported with their line number
+    assert!(corrections
+        .parse_table("invalid", "2020-06-25T00:00:00 UTC, UTC".as_bytes())
+        .is_err());
+
+    // broadcast navigation header offsets
+    context
+        .load_gzip_rinex_file("data/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+        .unwrap();
+
+    let corrections = context.time_corrections();
+
+    assert!(corrections
+        .source_iter(&QcTimeCorrectionSource::NavigationHeader)
+        .any(|correction| correction.lhs == TimeScale::GPST));
+
+    // each offset is described once
+    for (i, lhs) in corrections.corrections.iter().enumerate() {
+        for rhs in corrections.corrections.iter().skip(i + 1) {
+            assert!((lhs.lhs, lhs.rhs, lhs.reference) != (rhs.lhs, rhs.rhs, rhs.reference));
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "navigation")]
+fn test_precise_time_corrections() {
+    use crate::prelude::{Duration, Epoch, QcTimeCorrectionSource, QcTimeCorrectionsDB};
+    use std::str::FromStr;
+
+    // residual offsets: lhs - rhs = a0 + a1 * dt
+    let mut corrections = QcTimeCorrectionsDB::default();
+
+    corrections
+        .parse_table(
+            "table",
+            "2020-06-25T00:00:00 GPST, UTC, GPST, 12.0
+            2020-06-25T00:00:00 GST, GST, GPST, -5.0, 0.1"
+                .as_bytes(),
+        )
+        .unwrap();
+
+    let db = corrections.database();
+
+    let residual_ns = |corrected: Epoch, nominal: Epoch| (corrected - nominal).to_seconds() * 1.0E9;
+
+    // GPST -> UTC: leap seconds come from the conversion, the residual from the table
+    let t = Epoch::from_str("2020-06-25T12:00:00 GPST").unwrap();
+    let corrected = db.precise_epoch_correction(t, TimeScale::UTC).unwrap();
+
+    assert_eq!(corrected.time_scale, TimeScale::UTC);
+    assert!((residual_ns(corrected, t.to_time_scale(TimeScale::UTC)) - 12.0).abs() < 1.0E-3);
+
+    // GST -> GPST, 100s after the reference: -5.0 + 0.1 * 100 = 5.0 ns
+    let t = Epoch::from_str("2020-06-25T00:00:00 GST").unwrap() + Duration::from_seconds(100.0);
+    let corrected = db.precise_epoch_correction(t, TimeScale::GPST).unwrap();
+
+    assert_eq!(corrected.time_scale, TimeScale::GPST);
+    assert!((residual_ns(corrected, t.to_time_scale(TimeScale::GPST)) + 5.0).abs() < 1.0E-3);
+
+    // Clock RINEX header: leap seconds are not a residual offset
+    let mut context = QcContext::new();
+
+    context
+        .load_gzip_rinex_file("data/CLK/V3/GRG0MGXFIN_20201770000_01D_30S_CLK.CLK.gz")
+        .unwrap();
+
+    let corrections = context.time_corrections();
+
+    let clock = corrections
+        .source_iter(&QcTimeCorrectionSource::ClockHeader)
+        .next()
+        .unwrap();
+
+    assert_eq!(clock.polynomial, (0.0, 0.0, 0.0));
+
+    let t = context.clock().unwrap().first_epoch().unwrap();
+    let corrected = corrections
+        .database()
+        .precise_epoch_correction(t, TimeScale::UTC)
+        .unwrap();
+
+    assert!(residual_ns(corrected, t.to_time_scale(TimeScale::UTC)).abs() < 1.0E-3);
+}
@@ -0,0 +1,155 @@
+//! BINEX records encoding, to test the BINEX decoder
+use crate::{prelude::Epoch, stream::binex::crc16, tests::toolkit::stream::BitWriter};
+
+/// One observed signal of a 0x7f-05 record
+pub struct BinexSignal {
+    /// Signal code identifier
+    pub code: u8,
+    /// Pseudo range [m]
+    pub pseudo_range_m: f64,
+    /// Carrier phase [m]
+    pub phase_m: f64,
+    /// Doppler shift [Hz]
+    pub doppler_hz: Option<f64>,
+    /// C/N0 [dB.Hz]
+    pub cn0_dbhz: f64,
+}
+
+/// Encodes this value as an ubnxi (up to 2^14)
+fn ubnxi(value: usize) -> Vec<u8> {
+    if value < 0x80 {
+        vec![value as u8]
+    } else {
+        vec![0x80 | (value >> 7) as u8, (value & 0x7F) as u8]
+    }
+}
+
+/// Wraps this message into one forward readable, big endian BINEX record
+pub fn record(id: u8, message: &[u8]) -> Vec<u8> {
+    let mut content = ubnxi(id as usize);
+    content.extend(ubnxi(message.len()));
+    content.extend_from_slice(message);
+
+    let mut record = vec![0xE2];
+    record.extend_from_slice(&content);
+
+    if content.len() < 128 {
+        record.push(content.iter().fold(0, |acc, byte| acc ^ byte));
+    } else {
+        record.extend(crc16(&content).to_be_bytes());
+    }
+
+    record
+}
+
+/// Encodes one site metadata record (0x00), made of these string fields
+pub fn metadata(fields: &[(u8, &str)]) -> Vec<u8> {
+    // creation time and data source
+    let mut message = vec![0; 6];
+
+    for (field, value) in fields {
+        message.push(*field);
+        message.extend(ubnxi(value.len()));
+        message.extend_from_slice(value.as_bytes());
+    }
+
+    record(0x00, &message)
+}
+
+/// Encodes one GPS ephemeris record (0x01-01), on a circular orbit inclined by 45°
+pub fn gps_ephemeris(prn: u8, week: u32, toe_s: u32) -> Vec<u8> {
+    let mut w = BitWriter::default();
+    w.u(8, 0x01);
+    w.u(8, prn as u64 - 1);
+    w.u(16, week as u64);
+    w.i(32, toe_s as i64); // time of transmission
+    w.i(32, toe_s as i64);
+    w.u(32, 0.0_f32.to_bits() as u64); // tgd
+    w.i(32, 42); // IODC
+    w.u(32, 0.0_f32.to_bits() as u64); // af2
+    w.u(32, 0.0_f32.to_bits() as u64); // af1
+    w.u(32, 2.0_f32.powi(-20).to_bits() as u64); // af0
+    w.i(32, 42); // IODE
+    w.u(32, 0.0_f32.to_bits() as u64); // delta n
+    w.u(64, 0.0_f64.to_bits()); // m0
+    w.u(64, 0.015625_f64.to_bits()); // e
+    w.u(64, 5153.0_f64.to_bits()); // sqrt(a)
+    for _ in 0..6 {
+        w.u(32, 0.0_f32.to_bits() as u64); // harmonic corrections
+    }
+    w.u(64, 0.0_f64.to_bits()); // omega0
+    w.u(64, 0.0_f64.to_bits()); // omega
+    w.u(64, std::f64::consts::FRAC_PI_4.to_bits()); // i0
+    w.u(32, 0.0_f32.to_bits() as u64); // omega dot
+    w.u(32, 0.0_f32.to_bits() as u64); // IDOT
+    w.u(32, 20.0_f32.to_bits() as u64); // accuracy [dm]
+    w.u(16, 0); // health
+    w.u(16, 0); // fit interval, L2P flag and L2 codes
+    record(0x01, &w.into_bytes())
+}
+
+/// Encodes one GNSS observations record (0x7f-05), made of
+/// (system, PRN, signals) satellites.
+pub fn observations(t: Epoch, satellites: &[(u8, u8, Vec<BinexSignal>)]) -> Vec<u8> {
+    let seconds = t.to_gpst_seconds();
+    let minutes = (seconds / 60.0).floor();
+    let ms = ((seconds - minutes * 60.0) * 1.0E3).round();
+
+    let mut w = BitWriter::default();
+    w.u(8, 0x05);
+    w.u(32, minutes as u64);
+    w.u(16, ms as u64);
+    // no receiver clock offset, no time system offsets
+    w.u(2, 0);
+    w.u(6, satellites.len() as u64 - 1);
+
+    for (sys, prn, signals) in satellites {
+        w.u(8, *prn as u64);
+        w.u(1, 0);
+        w.u(3, signals.len() as u64);
+        w.u(4, *sys as u64);
+
+        let mut reference_m = 0.0;
+
+        for (i, signal) in signals.iter().enumerate() {
+            w.u(1, signal.doppler_hz.is_some() as u64);
+            w.u(2, 0);
+            w.u(5, signal.code as u64);
+
+            if signal.doppler_hz.is_some() {
+                // doppler is present
+                w.u(8, 0x04);
+            }
+
+            let cn0 = (signal.cn0_dbhz / 0.4).round();
+            let cn0_fraction = ((signal.cn0_dbhz - cn0 * 0.4) / 0.1).round();
+            w.u(8, cn0 as u64);
+
+            let pseudo_range_m = if i == 0 {
+                let coarse = (signal.pseudo_range_m / 0.064).floor();
+                let fine = ((signal.pseudo_range_m - coarse * 0.064) / 0.001).round();
+                w.i(2, 0);
+                w.u(32, coarse as u64);
+                w.u(6, fine as u64);
+                reference_m = coarse * 0.064 + fine * 0.001;
+                reference_m
+            } else {
+                let delta = ((signal.pseudo_range_m - reference_m) / 0.001).round();
+                w.i(16, delta as i64);
+                reference_m + delta * 0.001
+            };
+
+            w.i(2, cn0_fraction as i64);
+            w.i(
+                22,
+                ((signal.phase_m - pseudo_range_m) / 0.00002).round() as i64,
+            );
+
+            if let Some(doppler_hz) = signal.doppler_hz {
+                w.i(24, (doppler_hz * 256.0).round() as i64);
+            }
+        }
+    }
+
+    record(0x7f, &w.into_bytes())
+}
@@ -1 +1,21 @@
+use std::path::PathBuf;
+
+#[cfg(feature = "binex")]
+pub mod binex;
 pub mod obs_rinex;
+#[cfg(feature = "rtcm")]
+pub mod rtcm;
+#[cfg(any(feature = "rtcm", feature = "ubx", feature = "binex"))]
+pub mod stream;
+#[cfg(feature = "ubx")]
+pub mod ubx;
+
+/// Creates an empty directory dedicated to this test, in the system temporary directory.
+/// Its name contains the process ID, so concurrent or repeated runs do not collide.
+pub fn test_directory(name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("gnss-qc-{}-{}", name, std::process::id()));
+
+    let _ = std::fs::remove_dir_all(&directory);
+    std::fs::create_dir_all(&directory).unwrap();
+    directory
+}
@@ -0,0 +1,81 @@
+//! RTCM3 messages encoding, to test the stream decoder
+use crate::{stream::rtcm::crc24q, tests::toolkit::stream::BitWriter};
+
+/// Wraps this payload into one RTCM3 frame
+pub fn frame(payload: Vec<u8>) -> Vec<u8> {
+    let len = payload.len();
+    let mut frame = vec![0xD3, (len >> 8) as u8, len as u8];
+    frame.extend_from_slice(&payload);
+    let crc = crc24q(&frame);
+    frame.extend_from_slice(&[(crc >> 16) as u8, (crc >> 8) as u8, crc as u8]);
+    frame
+}
+
+/// Station coordinates (1005) example of the RTCM 10403 standard
+pub const MSG_1005: [u8; 25] = [
+    0xD3, 0x00, 0x13, 0x3E, 0xD7, 0xD3, 0x02, 0x02, 0x98, 0x0E, 0xDE, 0xEF, 0x34, 0xB4, 0xBD, 0x62,
+    0xAC, 0x09, 0x41, 0x98, 0x6F, 0x33, 0x36, 0x0B, 0x98,
+];
+
+/// Encodes one GPS MSM4 (1074) frame, with a single L1 C/A signal.
+/// Range and phase are expressed in milliseconds.
+pub fn gps_msm4(prn: u8, tow_ms: u64, range_ms: f64, phase_ms: f64, cnr: u64) -> Vec<u8> {
+    let mut w = BitWriter::default();
+    w.u(12, 1074);
+    w.u(12, 0); // station ID
+    w.u(30, tow_ms);
+    w.u(1 + 3 + 7 + 2 + 2 + 1 + 3, 0);
+    w.u(64, 1 << (64 - prn as u64));
+    w.u(32, 1 << (32 - 2));
+    w.u(1, 1);
+
+    let int_ms = range_ms.floor();
+    let mod_ms = ((range_ms - int_ms) * 1024.0).floor() / 1024.0;
+    w.u(8, int_ms as u64);
+    w.u(10, (mod_ms * 1024.0) as u64);
+
+    let rough = int_ms + mod_ms;
+    w.i(15, ((range_ms - rough) * 2.0_f64.powi(24)).round() as i64);
+    w.i(22, ((phase_ms - rough) * 2.0_f64.powi(29)).round() as i64);
+    w.u(4, 0); // lock time
+    w.u(1, 0); // half cycle
+    w.u(6, cnr);
+    frame(w.into_bytes())
+}
+
+/// Encodes one GPS ephemeris (1019) frame, on a circular orbit inclined by 45°
+pub fn gps_ephemeris(prn: u8, week: u32, toc_s: u64) -> Vec<u8> {
+    let mut w = BitWriter::default();
+    w.u(12, 1019);
+    w.u(6, prn as u64);
+    w.u(10, (week % 1024) as u64);
+    w.u(4, 0); // accuracy
+    w.u(2, 1); // L2 codes
+    w.i(14, 0); // IDOT
+    w.u(8, 42); // IODE
+    w.u(16, toc_s / 16);
+    w.i(8, 0); // af2
+    w.i(16, -10); // af1
+    w.i(22, 1000); // af0
+    w.u(10, 42); // IODC
+    w.i(16, 0); // crs
+    w.i(16, 0); // delta n
+    w.i(32, 0); // m0
+    w.i(16, 0); // cuc
+    w.u(32, 1 << 27); // e
+    w.i(16, 0); // cus
+    w.u(32, 5153 << 19); // sqrt(a)
+    w.u(16, toc_s / 16); // toe
+    w.i(16, 0); // cic
+    w.i(32, 0); // omega0
+    w.i(16, 0); // cis
+    w.i(32, 1 << 29); // i0
+    w.i(16, 0); // crc
+    w.i(32, 0); // omega
+    w.i(24, 0); // omega dot
+    w.i(8, 0); // tgd
+    w.u(6, 0); // health
+    w.u(1, 0); // L2P flag
+    w.u(1, 0); // fit interval
+    frame(w.into_bytes())
+}
@@ -0,0 +1,32 @@
+//! Binary streams encoding, to test the stream decoders
+
+/// Big endian bit writer
+#[derive(Default)]
+pub struct BitWriter {
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl BitWriter {
+    /// Writes an unsigned field
+    pub fn u(&mut self, bits: usize, value: u64) {
+        for i in (0..bits).rev() {
+            if self.pos % 8 == 0 {
+                self.buf.push(0);
+            }
+            let bit = ((value >> i) & 0x01) as u8;
+            self.buf[self.pos / 8] |= bit << (7 - (self.pos % 8));
+            self.pos += 1;
+        }
+    }
+
+    /// Writes a two's complement signed field
+    pub fn i(&mut self, bits: usize, value: i64) {
+        self.u(bits, (value as u64) & ((1u64 << bits) - 1));
+    }
+
+    /// Returns the encoded bytes
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buf
+    }
+}
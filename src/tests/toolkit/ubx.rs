@@ -0,0 +1,199 @@
+//! UBX messages encoding, to test the stream decoder
+use crate::{stream::ubx::checksum, tests::toolkit::stream::BitWriter};
+
+/// Wraps this payload into one UBX frame
+pub fn frame(class: u8, id: u8, payload: &[u8]) -> Vec<u8> {
+    let len = payload.len() as u16;
+    let mut frame = vec![0xB5, 0x62, class, id];
+    frame.extend_from_slice(&len.to_le_bytes());
+    frame.extend_from_slice(payload);
+    let (ck_a, ck_b) = checksum(&frame[2..]);
+    frame.extend_from_slice(&[ck_a, ck_b]);
+    frame
+}
+
+/// One RXM-RAWX measurement
+pub struct RawxMeasurement {
+    pub gnss_id: u8,
+    pub sv_id: u8,
+    pub sig_id: u8,
+    pub freq_id: u8,
+    pub pseudo_range_m: f64,
+    pub phase_cycles: f64,
+    pub doppler_hz: f32,
+    pub cno: u8,
+    pub trk_stat: u8,
+}
+
+/// Encodes one RXM-RAWX frame
+pub fn rawx(week: u16, tow_s: f64, measurements: &[RawxMeasurement]) -> Vec<u8> {
+    let mut payload = Vec::new();
+    payload.extend_from_slice(&tow_s.to_le_bytes());
+    payload.extend_from_slice(&week.to_le_bytes());
+    payload.push(18); // leap seconds
+    payload.push(measurements.len() as u8);
+    payload.extend_from_slice(&[0x01, 0x01, 0x00, 0x00]);
+
+    for meas in measurements {
+        payload.extend_from_slice(&meas.pseudo_range_m.to_le_bytes());
+        payload.extend_from_slice(&meas.phase_cycles.to_le_bytes());
+        payload.extend_from_slice(&meas.doppler_hz.to_le_bytes());
+        payload.extend_from_slice(&[meas.gnss_id, meas.sv_id, meas.sig_id, meas.freq_id]);
+        payload.extend_from_slice(&[0x00, 0x10]); // lock time
+        payload.extend_from_slice(&[meas.cno, 0x00, 0x00, 0x00, meas.trk_stat, 0x00]);
+    }
+
+    frame(0x02, 0x15, &payload)
+}
+
+/// Encodes one GPS LNAV subframe (data bits only, 8 words after TLM and HOW)
+/// into one RXM-SFRBX frame
+fn sfrbx_lnav(prn: u8, id: u64, w: BitWriter) -> Vec<u8> {
+    let mut subframe = BitWriter::default();
+    subframe.u(8, 0x8B); // preamble
+    subframe.u(16, 0);
+    subframe.u(17, 0); // TOW
+    subframe.u(2, 0); // alert, A/S
+    subframe.u(3, id);
+    subframe.u(2, 0);
+
+    let mut data = subframe.into_bytes();
+    data.extend(w.into_bytes());
+
+    let mut payload = vec![0x00, prn, 0x00, 0x00, 10, 0x00, 0x02, 0x00];
+
+    for word in data.chunks_exact(3) {
+        let word = u32::from_be_bytes([0, word[0], word[1], word[2]]) << 6;
+        payload.extend_from_slice(&word.to_le_bytes());
+    }
+
+    frame(0x02, 0x13, &payload)
+}
+
+/// Encodes GPS LNAV subframes 1, 2 and 3, on a circular orbit inclined by 45°
+pub fn gps_lnav_subframes(prn: u8, week: u32, toc_s: u64) -> Vec<Vec<u8>> {
+    let mut sf1 = BitWriter::default();
+    sf1.u(10, (week % 1024) as u64);
+    sf1.u(2, 1); // L2 codes
+    sf1.u(4, 0); // accuracy
+    sf1.u(6, 0); // health
+    sf1.u(2, 0); // IODC MSB
+    sf1.u(1, 0); // L2P flag
+    sf1.u(23 + 24 + 24 + 16, 0);
+    sf1.i(8, 0); // tgd
+    sf1.u(8, 42); // IODC LSB
+    sf1.u(16, toc_s / 16);
+    sf1.i(8, 0); // af2
+    sf1.i(16, -10); // af1
+    sf1.i(22, 1000); // af0
+    sf1.u(2, 0);
+
+    let mut sf2 = BitWriter::default();
+    sf2.u(8, 42); // IODE
+    sf2.i(16, 0); // crs
+    sf2.i(16, 0); // delta n
+    sf2.i(32, 0); // m0
+    sf2.i(16, 0); // cuc
+    sf2.u(32, 1 << 27); // e
+    sf2.i(16, 0); // cus
+    sf2.u(32, 5153 << 19); // sqrt(a)
+    sf2.u(16, toc_s / 16); // toe
+    sf2.u(1 + 5 + 2, 0);
+
+    let mut sf3 = BitWriter::default();
+    sf3.i(16, 0); // cic
+    sf3.i(32, 0); // omega0
+    sf3.i(16, 0); // cis
+    sf3.i(32, 1 << 29); // i0
+    sf3.i(16, 0); // crc
+    sf3.i(32, 0); // omega
+    sf3.i(24, 0); // omega dot
+    sf3.u(8, 42); // IODE
+    sf3.i(14, 0); // IDOT
+    sf3.u(2, 0);
+
+    vec![
+        sfrbx_lnav(prn, 1, sf1),
+        sfrbx_lnav(prn, 2, sf2),
+        sfrbx_lnav(prn, 3, sf3),
+    ]
+}
+
+/// Encodes one Galileo I/NAV word (128 bits) into one RXM-SFRBX frame,
+/// as one nominal even page followed by one nominal odd page
+fn sfrbx_inav(prn: u8, w: BitWriter) -> Vec<u8> {
+    let word = w.into_bytes();
+
+    let mut pages = BitWriter::default();
+    pages.u(2, 0); // even page, nominal
+    for byte in word[..14].iter() {
+        pages.u(8, *byte as u64);
+    }
+    pages.u(14, 0); // tail
+    pages.u(2, 0b10); // odd page, nominal
+    for byte in word[14..16].iter() {
+        pages.u(8, *byte as u64);
+    }
+    pages.u(64, 0);
+    pages.u(46, 0);
+
+    let mut payload = vec![0x02, prn, 0x00, 0x00, 8, 0x00, 0x02, 0x00];
+
+    for word in pages.into_bytes().chunks_exact(4) {
+        let word = u32::from_be_bytes(word.try_into().unwrap());
+        payload.extend_from_slice(&word.to_le_bytes());
+    }
+
+    frame(0x02, 0x13, &payload)
+}
+
+/// Encodes Galileo I/NAV words 1 to 4, on a circular orbit inclined by 45°
+pub fn gal_inav_words(prn: u8, toc_s: u64) -> Vec<Vec<u8>> {
+    let mut w1 = BitWriter::default();
+    w1.u(6, 1);
+    w1.u(10, 7); // IODnav
+    w1.u(14, toc_s / 60); // toe
+    w1.i(32, 0); // m0
+    w1.u(32, 0); // e
+    w1.u(32, 5440 << 19); // sqrt(a)
+    w1.u(2, 0);
+
+    let mut w2 = BitWriter::default();
+    w2.u(6, 2);
+    w2.u(10, 7); // IODnav
+    w2.i(32, 0); // omega0
+    w2.i(32, 1 << 29); // i0
+    w2.i(32, 0); // omega
+    w2.i(14, 0); // IDOT
+    w2.u(2, 0);
+
+    let mut w3 = BitWriter::default();
+    w3.u(6, 3);
+    w3.u(10, 7); // IODnav
+    w3.i(24, 0); // omega dot
+    w3.i(16, 0); // delta n
+    w3.i(16, 0); // cuc
+    w3.i(16, 0); // cus
+    w3.i(16, 0); // crc
+    w3.i(16, 0); // crs
+    w3.u(8, 107); // SISA
+
+    let mut w4 = BitWriter::default();
+    w4.u(6, 4);
+    w4.u(10, 7); // IODnav
+    w4.u(6, prn as u64);
+    w4.i(16, 0); // cic
+    w4.i(16, 0); // cis
+    w4.u(14, toc_s / 60);
+    w4.i(31, 1000); // af0
+    w4.i(21, 0); // af1
+    w4.i(6, 0); // af2
+    w4.u(2, 0);
+
+    vec![
+        sfrbx_inav(prn, w1),
+        sfrbx_inav(prn, w2),
+        sfrbx_inav(prn, w3),
+        sfrbx_inav(prn, w4),
+    ]
+}
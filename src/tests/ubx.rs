@@ -0,0 +1,129 @@
+use std::{io::Write, str::FromStr};
+
+use crate::{
+    prelude::{Constellation, Epoch, ProductType, QcContext, SV},
+    tests::toolkit::{
+        test_directory,
+        ubx::{gal_inav_words, gps_lnav_subframes, rawx, RawxMeasurement},
+    },
+};
+
+#[test]
+fn test_ubx_log_loading() {
+    let directory = test_directory("ubx-loading");
+    let path = directory.join("UBX100FRA_R_20240070200_01H_MO.ubx");
+
+    let mut fd = std::fs::File::create(&path).unwrap();
+
+    // sunday 02:00:00 and 02:00:01
+    for tow_s in [7200.0, 7201.0] {
+        let measurements = [12, 25]
+            .into_iter()
+            .map(|sv_id| RawxMeasurement {
+                gnss_id: 0,
+                sv_id,
+                sig_id: 0,
+                freq_id: 0,
+                pseudo_range_m: 21_000_000.0 + sv_id as f64,
+                phase_cycles: 110_000_000.0,
+                doppler_hz: 100.0,
+                cno: 40,
+                trk_stat: 0x03,
+            })
+            .collect::<Vec<_>>();
+
+        fd.write_all(&rawx(2296, tow_s, &measurements)).unwrap();
+    }
+
+    for subframe in gps_lnav_subframes(12, 2296, 7200) {
+        fd.write_all(&subframe).unwrap();
+    }
+
+    drop(fd);
+
+    let mut context = QcContext::new();
+
+    // the observations prevail
+    let product = context.load_file(&path).unwrap();
+    assert_eq!(product, ProductType::Observation);
+
+    assert!(context.is_navigation_compatible());
+    assert_eq!(context.stations_iter().collect::<Vec<_>>(), vec!["UBX1"]);
+
+    let obs = context.observation().unwrap();
+    assert_eq!(obs.epoch_iter().count(), 2);
+
+    let nav = context.brdc_navigation().unwrap();
+    assert_eq!(nav.nav_ephemeris_frames_iter().count(), 1);
+
+    for entry in context.provenance() {
+        assert!(entry.header.starts_with("UBX stream"));
+    }
+
+    context.unload_file(&path).unwrap();
+    assert!(!context.has_observation());
+    assert!(!context.has_brdc_navigation());
+
+    // explicit loading
+    let products = context.load_ubx_file(&path).unwrap();
+
+    assert_eq!(
+        products,
+        vec![ProductType::BroadcastNavigation, ProductType::Observation]
+    );
+
+    let _ = std::fs::remove_dir_all(&directory);
+}
+
+#[test]
+fn test_ubx_ephemeris_first() {
+    let directory = test_directory("ubx-ephemeris-first");
+    let path = directory.join("ephemeris-first.ubx");
+
+    let mut fd = std::fs::File::create(&path).unwrap();
+
+    // Galileo I/NAV words precede the first RXM-RAWX measurement
+    for word in gal_inav_words(11, 7200) {
+        fd.write_all(&word).unwrap();
+    }
+
+    // sunday 02:00:00
+    let measurement = RawxMeasurement {
+        gnss_id: 2,
+        sv_id: 11,
+        sig_id: 0,
+        freq_id: 0,
+        pseudo_range_m: 24_000_000.0,
+        phase_cycles: 126_000_000.0,
+        doppler_hz: 100.0,
+        cno: 40,
+        trk_stat: 0x03,
+    };
+
+    fd.write_all(&rawx(2296, 7200.0, &[measurement])).unwrap();
+    drop(fd);
+
+    // the time of clock is resolved against the RXM-RAWX week:
+    // this reference (three weeks later) is not used
+    let reference = Epoch::from_str("2024-01-28T00:00:00 GPST").unwrap();
+
+    let mut context = QcContext::new();
+    context.load_ubx_file_at(&path, reference).unwrap();
+
+    let nav = context.brdc_navigation().unwrap();
+    let (key, ephemeris) = nav.nav_ephemeris_frames_iter().next().unwrap();
+
+    assert_eq!(key.sv, SV::new(Constellation::Galileo, 11));
+    assert_eq!(
+        key.epoch,
+        Epoch::from_str("2024-01-07T02:00:00 GST").unwrap()
+    );
+    assert_eq!(ephemeris.get_orbit_f64("week"), Some(2296.0));
+
+    // no reference at all
+    let mut context = QcContext::new();
+    context.load_file(&path).unwrap();
+    assert!(context.has_brdc_navigation());
+
+    let _ = std::fs::remove_dir_all(&directory);
+}
@@ -0,0 +1,172 @@
+use std::fs::{copy, remove_dir_all};
+
+use crate::{
+    prelude::{
+        Constellation, Error, Filter, FilterItem, MaskOperand, Preprocessing, ProductType,
+        QcContext, QcPipeline, QcPipelineStep, Rinex,
+    },
+    tests::toolkit::test_directory,
+};
+
+/// Splits this file in two halves, loaded from memory (these paths do not exist)
+fn in_memory_context() -> (QcContext, usize, usize) {
+    let rinex = Rinex::from_file("data/OBS/V2/AJAC3550.21O").unwrap();
+
+    let epochs = rinex.epoch_iter().collect::<Vec<_>>();
+    let mid = epochs[epochs.len() / 2];
+
+    let first_half = rinex.filter(&Filter::mask(
+        MaskOperand::LowerThan,
+        FilterItem::EpochItem(mid),
+    ));
+
+    let second_half = rinex.filter(&Filter::mask(
+        MaskOperand::GreaterEquals,
+        FilterItem::EpochItem(mid),
+    ));
+
+    let sizes = (
+        first_half.epoch_iter().count(),
+        second_half.epoch_iter().count(),
+    );
+
+    let mut context = QcContext::new();
+
+    context
+        .load_rinex("memory/AJAC3550.21O", first_half)
+        .unwrap();
+
+    context
+        .load_rinex("memory/AJAC3551.21O", second_half)
+        .unwrap();
+
+    (context, sizes.0, sizes.1)
+}
+
+#[test]
+fn test_unload_in_memory() {
+    let (mut context, first_size, _) = in_memory_context();
+
+    let pipeline = QcPipeline::default().with_step(QcPipelineStep::Filter("GPS".to_string()));
+    context.apply_pipeline(&pipeline).unwrap();
+
+    context.unload_file("memory/AJAC3551.21O").unwrap();
+
+    let files = context.files(ProductType::Observation).unwrap();
+    assert_eq!(files.len(), 1);
+
+    // contribution of the other file is preserved, as preprocessed
+    let obs = context.observation().unwrap();
+    assert_eq!(obs.epoch_iter().count(), first_size);
+
+    for sv in obs.sv_iter() {
+        assert_eq!(sv.constellation, Constellation::GPS);
+    }
+
+    assert_eq!(context.preprocessing(), &pipeline);
+
+    // last file
+    context.unload_file("memory/AJAC3550.21O").unwrap();
+    assert!(!context.has_observation());
+    assert!(context.files(ProductType::Observation).is_none());
+
+    assert!(context.unload_file("memory/AJAC3550.21O").is_err());
+}
+
+#[test]
+fn test_replace_same_product() {
+    let (mut context, first_size, second_size) = in_memory_context();
+
+    context.load_rinex_file("data/NAV/V2/amel0010.21g").unwrap();
+
+    let pipeline = QcPipeline::default().with_step(QcPipelineStep::Filter("GPS".to_string()));
+    context.apply_pipeline(&pipeline).unwrap();
+
+    let directory = test_directory("replace-same-product");
+    let new = directory.join("AJAC3550.21O");
+    copy("data/OBS/V2/AJAC3550.21O", &new).unwrap();
+
+    let product = context.replace_file("memory/AJAC3551.21O", &new).unwrap();
+
+    assert_eq!(product, ProductType::Observation);
+
+    // takes the place of the previous file
+    let files = context.files(ProductType::Observation).unwrap();
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[1], new);
+
+    // new data is preprocessed like the rest of the context
+    let obs = context.observation().unwrap();
+    assert_eq!(obs.epoch_iter().count(), first_size + second_size);
+
+    for sv in obs.sv_iter() {
+        assert_eq!(sv.constellation, Constellation::GPS);
+    }
+
+    assert!(context.has_brdc_navigation());
+
+    let _ = remove_dir_all(&directory);
+}
+
+#[test]
+fn test_replace_other_product() {
+    let (mut context, _, _) = in_memory_context();
+
+    context.load_rinex_file("data/NAV/V2/amel0010.21g").unwrap();
+
+    // failure: nothing is modified
+    assert!(context
+        .replace_file("data/NAV/V2/amel0010.21g", "Cargo.toml")
+        .is_err());
+
+    assert!(context.has_brdc_navigation());
+    assert_eq!(context.files(ProductType::Observation).unwrap().len(), 2);
+
+    let directory = test_directory("replace-other-product");
+    let new = directory.join("AJAC3552.21O");
+    copy("data/OBS/V2/AJAC3550.21O", &new).unwrap();
+
+    let product = context
+        .replace_file("data/NAV/V2/amel0010.21g", &new)
+        .unwrap();
+
+    assert_eq!(product, ProductType::Observation);
+
+    assert!(!context.has_brdc_navigation());
+    assert!(context.files(ProductType::BroadcastNavigation).is_none());
+
+    let files = context.files(ProductType::Observation).unwrap();
+    assert_eq!(files.len(), 3);
+    assert_eq!(files[2], new);
+
+    let _ = remove_dir_all(&directory);
+}
+
+#[test]
+fn test_replace_by_loaded_file() {
+    let path = "data/OBS/V2/AJAC3550.21O";
+
+    let mut context = QcContext::new();
+
+    context.load_rinex_file(path).unwrap();
+    context.load_rinex_file("data/NAV/V2/amel0010.21g").unwrap();
+
+    let size = context.observation().unwrap().epoch_iter().count();
+
+    // its data would be duplicated: nothing is modified
+    assert!(matches!(
+        context.replace_file("data/NAV/V2/amel0010.21g", path),
+        Err(Error::FileAlreadyLoaded)
+    ));
+
+    assert!(context.has_brdc_navigation());
+    assert_eq!(context.files(ProductType::Observation).unwrap().len(), 1);
+    assert_eq!(context.observation().unwrap().epoch_iter().count(), size);
+
+    // reloading a file in place is allowed
+    let product = context.replace_file(path, path).unwrap();
+    assert_eq!(product, ProductType::Observation);
+
+    assert_eq!(context.files(ProductType::Observation).unwrap().len(), 1);
+    assert_eq!(context.observation().unwrap().epoch_iter().count(), size);
+}